@@ -0,0 +1,152 @@
+//! TODO
+
+use alloc::borrow::Cow;
+
+use super::{check_u16_len, write_all, ByteOrder, Endec};
+use crate::{de::read::Read, error::Result, util};
+
+/// The binary NBT dialect used by Minecraft: Bedrock Edition to store data on
+/// disk.
+///
+/// Numbers are stored in little-endian byte order, strings are encoded as
+/// UTF-8 and prefixed by an unsigned 16-bit length.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct LittleEndian;
+
+impl Endec for LittleEndian {
+    #[inline]
+    fn read_i16<'de, R>(read: &mut R) -> Result<i16>
+    where
+        R: Read<'de>,
+    {
+        read.read_array().map(i16::from_le_bytes)
+    }
+
+    #[inline]
+    fn read_i32<'de, R>(read: &mut R) -> Result<i32>
+    where
+        R: Read<'de>,
+    {
+        read.read_array().map(i32::from_le_bytes)
+    }
+
+    #[inline]
+    fn read_i64<'de, R>(read: &mut R) -> Result<i64>
+    where
+        R: Read<'de>,
+    {
+        read.read_array().map(i64::from_le_bytes)
+    }
+
+    #[inline]
+    fn read_f32<'de, R>(read: &mut R) -> Result<f32>
+    where
+        R: Read<'de>,
+    {
+        read.read_array().map(f32::from_le_bytes)
+    }
+
+    #[inline]
+    fn read_f64<'de, R>(read: &mut R) -> Result<f64>
+    where
+        R: Read<'de>,
+    {
+        read.read_array().map(f64::from_le_bytes)
+    }
+
+    #[inline]
+    fn read_string_len<'de, R>(read: &mut R) -> Result<usize>
+    where
+        R: Read<'de>,
+    {
+        read.read_array().map(u16::from_le_bytes).map(usize::from)
+    }
+
+    #[inline]
+    fn read_len<'de, R>(read: &mut R) -> Result<i32>
+    where
+        R: Read<'de>,
+    {
+        Self::read_i32(read)
+    }
+
+    #[inline]
+    fn decode_str(bytes: Cow<'_, [u8]>) -> Option<Cow<'_, str>> {
+        util::decode_utf8(bytes)
+    }
+
+    #[inline]
+    fn write_i16<W>(writer: &mut W, value: i16) -> Result<()>
+    where
+        W: zc_io::Write,
+    {
+        write_all(writer, &value.to_le_bytes())
+    }
+
+    #[inline]
+    fn write_i32<W>(writer: &mut W, value: i32) -> Result<()>
+    where
+        W: zc_io::Write,
+    {
+        write_all(writer, &value.to_le_bytes())
+    }
+
+    #[inline]
+    fn write_i64<W>(writer: &mut W, value: i64) -> Result<()>
+    where
+        W: zc_io::Write,
+    {
+        write_all(writer, &value.to_le_bytes())
+    }
+
+    #[inline]
+    fn write_f32<W>(writer: &mut W, value: f32) -> Result<()>
+    where
+        W: zc_io::Write,
+    {
+        write_all(writer, &value.to_le_bytes())
+    }
+
+    #[inline]
+    fn write_f64<W>(writer: &mut W, value: f64) -> Result<()>
+    where
+        W: zc_io::Write,
+    {
+        write_all(writer, &value.to_le_bytes())
+    }
+
+    #[inline]
+    fn write_string_len<W>(writer: &mut W, len: usize) -> Result<()>
+    where
+        W: zc_io::Write,
+    {
+        let len = check_u16_len(len)?;
+        write_all(writer, &len.to_le_bytes())
+    }
+
+    #[inline]
+    fn encode_str(s: &str) -> Cow<'_, [u8]> {
+        Cow::Borrowed(s.as_bytes())
+    }
+
+    #[inline]
+    fn skip_i32s<'de, R>(read: &mut R, len: usize) -> Result<()>
+    where
+        R: Read<'de>,
+    {
+        read.skip(len.saturating_mul(4))
+    }
+
+    #[inline]
+    fn skip_i64s<'de, R>(read: &mut R, len: usize) -> Result<()>
+    where
+        R: Read<'de>,
+    {
+        read.skip(len.saturating_mul(8))
+    }
+
+    #[inline]
+    fn array_byte_order() -> Option<ByteOrder> {
+        Some(ByteOrder::Little)
+    }
+}
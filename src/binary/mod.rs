@@ -0,0 +1,515 @@
+//! TODO
+
+#[cfg(feature = "be")]
+mod be;
+#[cfg(feature = "le")]
+mod le;
+mod patch;
+mod raw;
+#[cfg(feature = "varint")]
+mod varint;
+
+#[cfg(feature = "be")]
+pub use self::be::BigEndian;
+#[cfg(feature = "le")]
+pub use self::le::LittleEndian;
+#[cfg(feature = "varint")]
+pub use self::varint::VarInt;
+pub use self::{
+    patch::patch,
+    raw::{RawNbt, RawNbtBuf},
+};
+
+use alloc::{borrow::Cow, format};
+
+use crate::{
+    de::read::Read,
+    error::{Category, Error, Result},
+};
+
+/// Trait describing how each primitive of a binary NBT dialect is encoded.
+///
+/// The tag layout is shared by every binary dialect; only the way numbers,
+/// lengths and strings are stored differs between them. The binary
+/// serializer and deserializer are generic over this trait so the same
+/// machinery can drive every dialect, including ones defined outside of
+/// `serde_nbt`.
+///
+/// A dialect is usually a unit struct implementing this trait, which is then
+/// passed as the `E` parameter of a binary [`Deserializer`], [`Serializer`]
+/// or their `Options`. Bytes are pulled from the input through the methods of
+/// [`Read`] and pushed to the output through [`zc_io::Write`]; errors from
+/// either can be propagated with `?`.
+///
+/// The provided methods only need to be overridden where a dialect can do
+/// better than the default, such as skipping fixed-size arrays in one step.
+///
+/// [`Deserializer`]: crate::de::binary::Deserializer
+/// [`Serializer`]: crate::ser::binary::Serializer
+pub trait Endec {
+    /// Reads the payload of a `Short`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the input ends early or cannot be read.
+    fn read_i16<'de, R>(read: &mut R) -> Result<i16>
+    where
+        R: Read<'de>;
+
+    /// Reads the payload of an `Int`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the input ends early, cannot be read, or does not hold a
+    /// valid `Int` in this dialect.
+    fn read_i32<'de, R>(read: &mut R) -> Result<i32>
+    where
+        R: Read<'de>;
+
+    /// Reads the payload of a `Long`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the input ends early, cannot be read, or does not hold a
+    /// valid `Long` in this dialect.
+    fn read_i64<'de, R>(read: &mut R) -> Result<i64>
+    where
+        R: Read<'de>;
+
+    /// Reads the payload of a `Float`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the input ends early or cannot be read.
+    fn read_f32<'de, R>(read: &mut R) -> Result<f32>
+    where
+        R: Read<'de>;
+
+    /// Reads the payload of a `Double`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the input ends early or cannot be read.
+    fn read_f64<'de, R>(read: &mut R) -> Result<f64>
+    where
+        R: Read<'de>;
+
+    /// Reads the length prefix of a `String`, in bytes.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the input ends early, cannot be read, or does not hold a
+    /// valid length in this dialect.
+    fn read_string_len<'de, R>(read: &mut R) -> Result<usize>
+    where
+        R: Read<'de>;
+
+    /// Reads the length prefix of a `List` or one of the array types, in
+    /// elements.
+    ///
+    /// The length is returned as-is; negative lengths are rejected by the
+    /// caller.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the input ends early, cannot be read, or does not hold a
+    /// valid length in this dialect.
+    fn read_len<'de, R>(read: &mut R) -> Result<i32>
+    where
+        R: Read<'de>;
+
+    /// Decodes the payload of a `String`, borrowing from `bytes` if possible.
+    ///
+    /// Returns `None` if the bytes are not valid in this dialect's string
+    /// encoding.
+    fn decode_str(bytes: Cow<'_, [u8]>) -> Option<Cow<'_, str>>;
+
+    /// Writes the payload of a `Short`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the output cannot be written.
+    fn write_i16<W>(writer: &mut W, value: i16) -> Result<()>
+    where
+        W: zc_io::Write;
+
+    /// Writes the payload of an `Int`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the output cannot be written.
+    fn write_i32<W>(writer: &mut W, value: i32) -> Result<()>
+    where
+        W: zc_io::Write;
+
+    /// Writes the payload of a `Long`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the output cannot be written.
+    fn write_i64<W>(writer: &mut W, value: i64) -> Result<()>
+    where
+        W: zc_io::Write;
+
+    /// Writes the payload of a `Float`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the output cannot be written.
+    fn write_f32<W>(writer: &mut W, value: f32) -> Result<()>
+    where
+        W: zc_io::Write;
+
+    /// Writes the payload of a `Double`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the output cannot be written.
+    fn write_f64<W>(writer: &mut W, value: f64) -> Result<()>
+    where
+        W: zc_io::Write;
+
+    /// Writes the length prefix of a `String`, in bytes.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the output cannot be written, or if the length cannot be
+    /// represented in this dialect.
+    fn write_string_len<W>(writer: &mut W, len: usize) -> Result<()>
+    where
+        W: zc_io::Write;
+
+    /// Writes the length prefix of a `List` or one of the array types, in
+    /// elements.
+    ///
+    /// The default implementation writes the length as an `Int`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the output cannot be written, or if the length does not fit
+    /// in an `i32`.
+    fn write_len<W>(writer: &mut W, len: usize) -> Result<()>
+    where
+        W: zc_io::Write,
+    {
+        match i32::try_from(len) {
+            Ok(len) => Self::write_i32(writer, len),
+            Err(_) => Err(Error::new(
+                Category::InvalidData,
+                format!("length {len} is too large"),
+            )),
+        }
+    }
+
+    /// Encodes a string into this dialect's string encoding, borrowing it if
+    /// possible.
+    fn encode_str(s: &str) -> Cow<'_, [u8]>;
+
+    /// Skips over `len` elements of an `IntArray`.
+    ///
+    /// The default implementation reads and discards each element.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the input ends early, cannot be read, or does not hold
+    /// valid `Int`s in this dialect.
+    fn skip_i32s<'de, R>(read: &mut R, len: usize) -> Result<()>
+    where
+        R: Read<'de>,
+    {
+        for _ in 0..len {
+            Self::read_i32(read)?;
+        }
+
+        Ok(())
+    }
+
+    /// Skips over `len` elements of a `LongArray`.
+    ///
+    /// The default implementation reads and discards each element.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the input ends early, cannot be read, or does not hold
+    /// valid `Long`s in this dialect.
+    fn skip_i64s<'de, R>(read: &mut R, len: usize) -> Result<()>
+    where
+        R: Read<'de>,
+    {
+        for _ in 0..len {
+            Self::read_i64(read)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the byte order the elements of `IntArray`s and `LongArray`s
+    /// are stored in, if they are stored as plain fixed-width integers.
+    ///
+    /// Dialects that return `Some` let [`IntArrayRef`] and [`LongArrayRef`]
+    /// borrow arrays straight from the input. The default implementation
+    /// returns `None`, which makes them decode each element with
+    /// [`read_i32`](Self::read_i32) or [`read_i64`](Self::read_i64) instead.
+    ///
+    /// [`IntArrayRef`]: crate::IntArrayRef
+    /// [`LongArrayRef`]: crate::LongArrayRef
+    #[must_use]
+    #[inline]
+    fn array_byte_order() -> Option<ByteOrder> {
+        None
+    }
+}
+
+/// The order of the bytes in a fixed-width integer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ByteOrder {
+    /// The most significant byte comes first.
+    Big,
+    /// The least significant byte comes first.
+    Little,
+}
+
+/// A compression format binary NBT is commonly wrapped in.
+///
+/// Minecraft: Java Edition gzips most files it stores on disk, such as
+/// `level.dat` and player data, and zlib-compresses the chunks inside region
+/// files.
+#[cfg(feature = "compression")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "compression")))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Compression {
+    /// The gzip file format, as described in [RFC 1952].
+    ///
+    /// [RFC 1952]: https://www.rfc-editor.org/rfc/rfc1952
+    Gzip,
+    /// The zlib data format, as described in [RFC 1950].
+    ///
+    /// [RFC 1950]: https://www.rfc-editor.org/rfc/rfc1950
+    Zlib,
+}
+
+/// Writes all of `bytes`, converting any I/O error.
+#[inline]
+pub(crate) fn write_all<W>(writer: &mut W, bytes: &[u8]) -> Result<()>
+where
+    W: zc_io::Write,
+{
+    writer.write_all(bytes).map_err(Error::io)
+}
+
+/// Fails if a string of `len` bytes is too long for a 16-bit length prefix.
+#[cfg(any(feature = "be", feature = "le"))]
+fn check_u16_len(len: usize) -> Result<u16> {
+    u16::try_from(len).map_err(|_| {
+        Error::new(
+            Category::InvalidData,
+            format!("string of {len} bytes is too long"),
+        )
+    })
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(all(test, feature = "binary"))]
+mod tests {
+    use alloc::{borrow::Cow, string::String, vec, vec::Vec};
+    use core::str;
+
+    use serde::{Deserialize, Serialize};
+
+    use super::{write_all, Endec};
+    use crate::{
+        de::{binary::Deserializer, read::Read, read::SliceRead},
+        error::Result,
+        ser::binary::Serializer,
+        IntArrayRef, LongArrayRef, Value,
+    };
+
+    /// A dialect with little-endian numbers and UTF-8 strings behind a 32-bit
+    /// length, which relies on every provided method of `Endec`.
+    struct Toy;
+
+    impl Endec for Toy {
+        fn read_i16<'de, R>(read: &mut R) -> Result<i16>
+        where
+            R: Read<'de>,
+        {
+            read.read_array().map(i16::from_le_bytes)
+        }
+
+        fn read_i32<'de, R>(read: &mut R) -> Result<i32>
+        where
+            R: Read<'de>,
+        {
+            read.read_array().map(i32::from_le_bytes)
+        }
+
+        fn read_i64<'de, R>(read: &mut R) -> Result<i64>
+        where
+            R: Read<'de>,
+        {
+            read.read_array().map(i64::from_le_bytes)
+        }
+
+        fn read_f32<'de, R>(read: &mut R) -> Result<f32>
+        where
+            R: Read<'de>,
+        {
+            read.read_array().map(f32::from_le_bytes)
+        }
+
+        fn read_f64<'de, R>(read: &mut R) -> Result<f64>
+        where
+            R: Read<'de>,
+        {
+            read.read_array().map(f64::from_le_bytes)
+        }
+
+        fn read_string_len<'de, R>(read: &mut R) -> Result<usize>
+        where
+            R: Read<'de>,
+        {
+            read.read_array()
+                .map(u32::from_le_bytes)
+                .map(|len| len as usize)
+        }
+
+        fn read_len<'de, R>(read: &mut R) -> Result<i32>
+        where
+            R: Read<'de>,
+        {
+            Self::read_i32(read)
+        }
+
+        fn decode_str(bytes: Cow<'_, [u8]>) -> Option<Cow<'_, str>> {
+            match bytes {
+                Cow::Borrowed(bytes) => str::from_utf8(bytes).ok().map(Cow::Borrowed),
+                Cow::Owned(bytes) => String::from_utf8(bytes).ok().map(Cow::Owned),
+            }
+        }
+
+        fn write_i16<W>(writer: &mut W, value: i16) -> Result<()>
+        where
+            W: zc_io::Write,
+        {
+            write_all(writer, &value.to_le_bytes())
+        }
+
+        fn write_i32<W>(writer: &mut W, value: i32) -> Result<()>
+        where
+            W: zc_io::Write,
+        {
+            write_all(writer, &value.to_le_bytes())
+        }
+
+        fn write_i64<W>(writer: &mut W, value: i64) -> Result<()>
+        where
+            W: zc_io::Write,
+        {
+            write_all(writer, &value.to_le_bytes())
+        }
+
+        fn write_f32<W>(writer: &mut W, value: f32) -> Result<()>
+        where
+            W: zc_io::Write,
+        {
+            write_all(writer, &value.to_le_bytes())
+        }
+
+        fn write_f64<W>(writer: &mut W, value: f64) -> Result<()>
+        where
+            W: zc_io::Write,
+        {
+            write_all(writer, &value.to_le_bytes())
+        }
+
+        fn write_string_len<W>(writer: &mut W, len: usize) -> Result<()>
+        where
+            W: zc_io::Write,
+        {
+            let len = u32::try_from(len).unwrap();
+            write_all(writer, &len.to_le_bytes())
+        }
+
+        fn encode_str(s: &str) -> Cow<'_, [u8]> {
+            Cow::Borrowed(s.as_bytes())
+        }
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Level {
+        name: String,
+        items: Vec<i16>,
+        heights: Value,
+        seeds: Value,
+    }
+
+    fn level() -> Level {
+        Level {
+            name: String::from("Überwelt"),
+            items: vec![1, -2],
+            heights: Value::IntArray(vec![1, -2, 3]),
+            seeds: Value::LongArray(vec![i64::MIN, 5]),
+        }
+    }
+
+    fn to_toy_vec<T>(value: &T) -> Vec<u8>
+    where
+        T: Serialize,
+    {
+        let mut ser = Serializer::<_, Toy>::new(Vec::new());
+        value.serialize(&mut ser).unwrap();
+        ser.into_inner()
+    }
+
+    fn from_toy_slice<'de, T>(bytes: &'de [u8]) -> T
+    where
+        T: Deserialize<'de>,
+    {
+        let mut de = Deserializer::<_, Toy>::new(SliceRead::new(bytes));
+        T::deserialize(&mut de).unwrap()
+    }
+
+    #[test]
+    fn custom_dialect_round_trips() {
+        let bytes = to_toy_vec(&level());
+
+        // `write_len` writes lengths as the dialect's `Int`.
+        let mut items = vec![9, 5, 0, 0, 0];
+        items.extend_from_slice(b"items");
+        items.extend_from_slice(&[2, 2, 0, 0, 0, 1, 0, 0xFE, 0xFF]);
+        assert!(bytes.windows(items.len()).any(|window| window == items));
+
+        assert_eq!(from_toy_slice::<Level>(&bytes), level());
+    }
+
+    #[test]
+    fn custom_dialect_decodes_array_views_element_by_element() {
+        #[derive(Deserialize)]
+        struct Arrays<'a> {
+            #[serde(borrow)]
+            heights: IntArrayRef<'a>,
+            #[serde(borrow)]
+            seeds: LongArrayRef<'a>,
+        }
+
+        let bytes = to_toy_vec(&level());
+        let arrays = from_toy_slice::<Arrays>(&bytes);
+
+        assert!(!arrays.heights.is_raw());
+        assert_eq!(arrays.heights.to_vec(), [1, -2, 3]);
+        assert!(!arrays.seeds.is_raw());
+        assert_eq!(arrays.seeds.to_vec(), [i64::MIN, 5]);
+    }
+
+    #[test]
+    fn custom_dialect_skips_arrays() {
+        #[derive(Deserialize)]
+        struct Name {
+            name: String,
+        }
+
+        let bytes = to_toy_vec(&level());
+        assert_eq!(from_toy_slice::<Name>(&bytes).name, "Überwelt");
+    }
+}
@@ -0,0 +1,235 @@
+//! In-place edits of binary NBT.
+
+use alloc::{format, vec::Vec};
+
+use super::{write_all, Endec};
+use crate::{
+    de::binary::locate,
+    error::{Category, Detail, Error, Position, Result, Segment},
+    Value,
+};
+
+/// Overwrites the tag at `path` below the root tag of `input` with `value`,
+/// without decoding or re-encoding anything else.
+///
+/// The path starts at the root tag, whose name is ignored, and steps into a
+/// `Compound` by key or into a `List` or array by index, e.g. `["Data",
+/// "Time"]` or `["Pos", 1]` as [`Segment`]s. When a key appears more than
+/// once in a `Compound`, the last entry is patched.
+///
+/// Only numbers and `String`s can be patched, and only if `value` has the
+/// same type as the tag and encodes to exactly as many bytes in the dialect
+/// `E`, so that no other byte of `input` moves. This always holds for
+/// numbers in the fixed-width dialects, whereas a `String` must encode to as
+/// many bytes as the one it replaces. `input` is either patched as a whole or
+/// left untouched.
+///
+/// `input` must be uncompressed.
+///
+/// # Errors
+///
+/// Returns an error if `input` is not valid binary NBT up to the end of the
+/// tag, if nothing exists at `path`, or if writing `value` would change the
+/// layout of `input`.
+pub fn patch<E>(input: &mut [u8], path: &[Segment], value: &Value) -> Result<()>
+where
+    E: Endec,
+{
+    let (ty, range) = locate::<E>(input, path)?;
+    let offset = range.start as u64;
+    let prepend_path = |error: Error| {
+        path.iter()
+            .rev()
+            .fold(error, |error, segment| error.prepend_path(segment.clone()))
+    };
+
+    if value.ty() != ty {
+        let message = format!("cannot replace {ty} with {}", value.ty());
+        let error = Error::with_position(Category::InvalidData, message, Position::Byte(offset));
+        let error = error.with_detail(Detail::InvalidType {
+            expected: Some(ty),
+            found: Some(value.ty()),
+        });
+        return Err(prepend_path(error));
+    }
+
+    let payload = encode::<E>(value)
+        .map_err(|error| prepend_path(error.fix_position(|| Position::Byte(offset))))?;
+
+    if payload.len() != range.len() {
+        let message = format!(
+            "the new payload is {} bytes long, but the old one is {}",
+            payload.len(),
+            range.len(),
+        );
+        let error = Error::with_position(Category::InvalidData, message, Position::Byte(offset));
+        return Err(prepend_path(error));
+    }
+
+    input[range].copy_from_slice(&payload);
+    Ok(())
+}
+
+/// Encodes the payload of a number or `String`.
+fn encode<E>(value: &Value) -> Result<Vec<u8>>
+where
+    E: Endec,
+{
+    let mut payload = Vec::new();
+
+    match value {
+        #[allow(clippy::cast_sign_loss)]
+        Value::Byte(v) => payload.push(v.to_i8() as u8),
+        Value::Short(v) => E::write_i16(&mut payload, *v)?,
+        Value::Int(v) => E::write_i32(&mut payload, *v)?,
+        Value::Long(v) => E::write_i64(&mut payload, *v)?,
+        Value::Float(v) => E::write_f32(&mut payload, *v)?,
+        Value::Double(v) => E::write_f64(&mut payload, *v)?,
+        Value::String(v) => {
+            let bytes = E::encode_str(v);
+            E::write_string_len(&mut payload, bytes.len())?;
+            write_all(&mut payload, &bytes)?;
+        }
+        _ => {
+            let message = format!("{} cannot be patched in place", value.ty());
+            return Err(Error::new(Category::InvalidData, message));
+        }
+    }
+
+    Ok(payload)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(all(test, feature = "be"))]
+mod tests {
+    use alloc::{
+        string::{String, ToString},
+        vec,
+        vec::Vec,
+    };
+
+    use serde::{Deserialize, Serialize};
+
+    use super::patch;
+    use crate::{
+        binary::BigEndian,
+        de,
+        error::{Category, Detail, Segment},
+        ser, Compound, Type, Value,
+    };
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Level {
+        data: Data,
+        pos: Vec<f64>,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Data {
+        time: i64,
+        level_name: String,
+    }
+
+    fn level() -> Level {
+        Level {
+            data: Data {
+                time: 1,
+                level_name: "world".into(),
+            },
+            pos: vec![0.5, 64.0, -0.5],
+        }
+    }
+
+    fn path(segments: &[&str]) -> Vec<Segment> {
+        segments.iter().map(|&key| key.into()).collect()
+    }
+
+    #[test]
+    fn patches_numbers_and_strings() {
+        let mut bytes = ser::to_be_vec(&level()).unwrap();
+
+        patch::<BigEndian>(&mut bytes, &path(&["Data", "Time"]), &Value::Long(24000)).unwrap();
+        patch::<BigEndian>(&mut bytes, &["Pos".into(), 1.into()], &Value::Double(80.0)).unwrap();
+        patch::<BigEndian>(
+            &mut bytes,
+            &path(&["Data", "LevelName"]),
+            &Value::String("earth".into()),
+        )
+        .unwrap();
+
+        let mut expected = level();
+        expected.data.time = 24000;
+        expected.data.level_name = "earth".into();
+        expected.pos[1] = 80.0;
+        assert_eq!(de::from_be_slice::<Level>(&bytes).unwrap(), expected);
+    }
+
+    #[test]
+    fn rejects_strings_of_a_different_size() {
+        let mut bytes = ser::to_be_vec(&level()).unwrap();
+        let original = bytes.clone();
+
+        let error = patch::<BigEndian>(
+            &mut bytes,
+            &path(&["Data", "LevelName"]),
+            &Value::String("planet".into()),
+        )
+        .unwrap_err();
+
+        assert_eq!(error.category(), Category::InvalidData);
+        assert_eq!(error.path().unwrap().to_string(), "Data.LevelName");
+        assert_eq!(bytes, original);
+    }
+
+    #[test]
+    fn rejects_a_different_type() {
+        let mut bytes = ser::to_be_vec(&level()).unwrap();
+        let original = bytes.clone();
+
+        let error =
+            patch::<BigEndian>(&mut bytes, &path(&["Data", "Time"]), &Value::Int(1)).unwrap_err();
+
+        assert_eq!(
+            error.detail(),
+            Some(&Detail::InvalidType {
+                expected: Some(Type::Long),
+                found: Some(Type::Int),
+            })
+        );
+        assert_eq!(bytes, original);
+    }
+
+    #[test]
+    fn rejects_containers_and_missing_tags() {
+        let mut bytes = ser::to_be_vec(&level()).unwrap();
+        let original = bytes.clone();
+
+        let compound = Value::Compound(Compound::new());
+        assert!(patch::<BigEndian>(&mut bytes, &path(&["Data"]), &compound).is_err());
+        assert!(patch::<BigEndian>(&mut bytes, &path(&["Missing"]), &Value::Int(1)).is_err());
+        assert!(
+            patch::<BigEndian>(&mut bytes, &["Pos".into(), 3.into()], &Value::Double(0.0)).is_err()
+        );
+        assert_eq!(bytes, original);
+    }
+
+    #[cfg(feature = "varint")]
+    #[test]
+    fn rejects_varints_of_a_different_size() {
+        use crate::binary::VarInt;
+
+        let mut bytes = ser::to_varint_vec(&level()).unwrap();
+        let original = bytes.clone();
+
+        patch::<VarInt>(&mut bytes, &path(&["Data", "Time"]), &Value::Long(-1)).unwrap();
+        let error = patch::<VarInt>(&mut bytes, &path(&["Data", "Time"]), &Value::Long(1 << 40))
+            .unwrap_err();
+
+        assert_eq!(error.category(), Category::InvalidData);
+        patch::<VarInt>(&mut bytes, &path(&["Data", "Time"]), &Value::Long(1)).unwrap();
+        assert_eq!(bytes, original);
+    }
+}
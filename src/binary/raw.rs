@@ -0,0 +1,380 @@
+//! Undecoded subtrees of binary NBT.
+
+use alloc::{borrow::Cow, vec::Vec};
+use core::{fmt, marker::PhantomData};
+
+use serde::{
+    de::{self, Deserialize, Deserializer, EnumAccess, VariantAccess, Visitor},
+    ser::{Serialize, Serializer},
+};
+
+use super::Endec;
+use crate::{de::binary::from_payload, error, util::RAW_NBT_TOKEN, Type};
+
+/// The encoded payload of a tag, captured as-is.
+///
+/// Deserializing a `RawNbt` from binary NBT skips over the tag without
+/// decoding it and keeps its exact bytes, borrowing them from the input when
+/// reading from a slice. Serializing it into binary NBT writes those bytes
+/// back verbatim, so subtrees a program does not understand, such as data
+/// added by mods, survive a round trip byte for byte.
+///
+/// The bytes are only meaningful in the dialect they were read in. Writing
+/// them with a serializer for a different dialect produces corrupt output.
+///
+/// Like other borrowed types that aren't `&str` or `&[u8]`, a field of this
+/// type needs `#[serde(borrow)]` to borrow from the input. Formats other
+/// than binary NBT cannot deserialize or serialize it.
+///
+/// A `RawNbt` can only be deserialized for as long as the input lives, so
+/// even `RawNbt<'static>` is not [`DeserializeOwned`]; use [`RawNbtBuf`]
+/// with the entry points that read from an I/O stream.
+///
+/// [`DeserializeOwned`]: serde::de::DeserializeOwned
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RawNbt<'a> {
+    ty: Type,
+    bytes: Cow<'a, [u8]>,
+}
+
+impl<'a> RawNbt<'a> {
+    /// Wraps the encoded payload of a tag of type `ty` in the dialect `E`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` is not exactly one valid payload of type
+    /// `ty`.
+    pub fn from_payload<E, B>(ty: Type, bytes: B) -> error::Result<Self>
+    where
+        E: Endec,
+        B: Into<Cow<'a, [u8]>>,
+    {
+        let bytes = bytes.into();
+        from_payload::<de::IgnoredAny, E>(ty, &bytes)?;
+        Ok(RawNbt { ty, bytes })
+    }
+
+    /// Returns the type of the tag.
+    #[must_use]
+    #[inline]
+    pub fn ty(&self) -> Type {
+        self.ty
+    }
+
+    /// Returns the encoded payload of the tag.
+    #[must_use]
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Copies the payload if it is borrowed, so that it no longer borrows
+    /// from the input.
+    #[must_use]
+    pub fn into_owned(self) -> RawNbt<'static> {
+        RawNbt {
+            ty: self.ty,
+            bytes: Cow::Owned(self.bytes.into_owned()),
+        }
+    }
+
+    /// Decodes the payload in the dialect `E` as a `T`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the structure of the payload does not match the
+    /// structure expected by `T`.
+    pub fn decode<'b, T, E>(&'b self) -> error::Result<T>
+    where
+        T: Deserialize<'b>,
+        E: Endec,
+    {
+        from_payload::<T, E>(self.ty, &self.bytes)
+    }
+}
+
+impl Serialize for RawNbt<'_> {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_newtype_variant(
+            RAW_NBT_TOKEN,
+            u32::from(self.ty.id()),
+            self.ty.as_str(),
+            &Payload(&self.bytes),
+        )
+    }
+}
+
+/// Serializes a payload as bytes rather than as a sequence.
+struct Payload<'a>(&'a [u8]);
+
+impl Serialize for Payload<'_> {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for RawNbt<'a> {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct(
+            RAW_NBT_TOKEN,
+            RawNbtVisitor {
+                lifetime: PhantomData,
+            },
+        )
+    }
+}
+
+struct RawNbtVisitor<'a> {
+    lifetime: PhantomData<&'a ()>,
+}
+
+impl<'de: 'a, 'a> Visitor<'de> for RawNbtVisitor<'a> {
+    type Value = RawNbt<'a>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a tag of binary NBT")
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: EnumAccess<'de>,
+    {
+        let (id, payload) = data.variant::<u8>()?;
+
+        let Some(ty) = Type::from_id(id) else {
+            return Err(de::Error::invalid_value(
+                de::Unexpected::Unsigned(id.into()),
+                &"a tag ID",
+            ));
+        };
+
+        let bytes = payload.newtype_variant_seed(PayloadVisitor {
+            lifetime: PhantomData,
+        })?;
+
+        Ok(RawNbt { ty, bytes })
+    }
+}
+
+/// Deserializes the bytes of a payload, borrowing them if possible.
+struct PayloadVisitor<'a> {
+    lifetime: PhantomData<&'a ()>,
+}
+
+impl<'de: 'a, 'a> de::DeserializeSeed<'de> for PayloadVisitor<'a> {
+    type Value = Cow<'a, [u8]>;
+
+    #[inline]
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_bytes(self)
+    }
+}
+
+impl<'de: 'a, 'a> Visitor<'de> for PayloadVisitor<'a> {
+    type Value = Cow<'a, [u8]>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("the payload of a tag")
+    }
+
+    #[inline]
+    fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+        Ok(Cow::Borrowed(v))
+    }
+
+    #[inline]
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(Cow::Owned(v.to_vec()))
+    }
+
+    #[inline]
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(Cow::Owned(v))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// An owned [`RawNbt`], which can be deserialized without borrowing from the
+/// input.
+///
+/// Unlike `RawNbt`, this is [`DeserializeOwned`], so it works with the entry
+/// points that read from an I/O stream. The payload is always copied out of
+/// the input.
+///
+/// [`DeserializeOwned`]: serde::de::DeserializeOwned
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RawNbtBuf(RawNbt<'static>);
+
+impl RawNbtBuf {
+    /// Returns the type of the tag.
+    #[must_use]
+    #[inline]
+    pub fn ty(&self) -> Type {
+        self.0.ty
+    }
+
+    /// Returns the encoded payload of the tag.
+    #[must_use]
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0.bytes
+    }
+
+    /// Borrows this payload as a [`RawNbt`].
+    #[must_use]
+    #[inline]
+    pub fn as_raw(&self) -> RawNbt<'_> {
+        RawNbt {
+            ty: self.0.ty,
+            bytes: Cow::Borrowed(&self.0.bytes),
+        }
+    }
+
+    /// Unwraps this `RawNbtBuf`, returning the owned [`RawNbt`].
+    #[must_use]
+    #[inline]
+    pub fn into_inner(self) -> RawNbt<'static> {
+        self.0
+    }
+}
+
+impl From<RawNbt<'_>> for RawNbtBuf {
+    #[inline]
+    fn from(raw: RawNbt<'_>) -> Self {
+        RawNbtBuf(raw.into_owned())
+    }
+}
+
+impl Serialize for RawNbtBuf {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for RawNbtBuf {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        RawNbt::deserialize(deserializer).map(RawNbtBuf::from)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(all(test, feature = "be"))]
+mod tests {
+    use alloc::borrow::Cow;
+
+    use serde::{Deserialize, Serialize};
+
+    use super::RawNbt;
+    use crate::{de, ser};
+
+    #[derive(Serialize, Deserialize)]
+    struct Root<'a> {
+        #[serde(borrow)]
+        raw: RawNbt<'a>,
+    }
+
+    /// A root `Compound` whose `Compound` named `raw` holds an `Int` named
+    /// `x` with the value 1, in big-endian.
+    const BE: &[u8] = &[
+        10, 0, 0, //
+        10, 0, 3, b'r', b'a', b'w', //
+        3, 0, 1, b'x', 0, 0, 0, 1, //
+        0, 0,
+    ];
+
+    #[test]
+    fn be_round_trip() {
+        let root = de::from_be_slice::<Root>(BE).unwrap();
+
+        assert!(matches!(root.raw.bytes, Cow::Borrowed(_)));
+        assert_eq!(root.raw.as_bytes(), &BE[9..18]);
+        assert_eq!(ser::to_be_vec(&root).unwrap(), BE);
+    }
+
+    #[cfg(feature = "le")]
+    #[test]
+    fn le_round_trip() {
+        const LE: &[u8] = &[
+            10, 0, 0, //
+            10, 3, 0, b'r', b'a', b'w', //
+            3, 1, 0, b'x', 1, 0, 0, 0, //
+            0, 0,
+        ];
+
+        let root = de::from_le_slice::<Root>(LE).unwrap();
+
+        assert_eq!(root.raw.as_bytes(), &LE[9..18]);
+        assert_eq!(ser::to_le_vec(&root).unwrap(), LE);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn buf_deserializes_from_a_reader() {
+        use super::RawNbtBuf;
+        use crate::binary::BigEndian;
+
+        #[derive(Deserialize)]
+        struct Owned {
+            raw: RawNbtBuf,
+        }
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Inner {
+            x: i32,
+        }
+
+        let root = de::from_be_reader::<_, Owned>(BE).unwrap();
+        let inner = root.raw.as_raw().decode::<Inner, BigEndian>().unwrap();
+
+        assert_eq!(root.raw.as_bytes(), &BE[9..18]);
+        assert_eq!(inner, Inner { x: 1 });
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn recording_charges_the_quota_as_it_reads() {
+        use super::RawNbtBuf;
+        use crate::error::Category;
+
+        #[derive(Debug, Deserialize)]
+        struct Owned {
+            #[allow(dead_code)]
+            raw: RawNbtBuf,
+        }
+
+        // A `Byte Array` named `raw` that declares a megabyte but ends
+        // after its length.
+        let input: &[u8] = &[10, 0, 0, 7, 0, 3, b'r', b'a', b'w', 0, 16, 0, 0];
+        let error = de::Options::java()
+            .with_quota(1024)
+            .from_reader::<_, Owned>(input)
+            .unwrap_err();
+
+        assert_eq!(error.category(), Category::QuotaExceeded);
+    }
+}
@@ -0,0 +1,191 @@
+//! TODO
+
+use alloc::{borrow::Cow, format};
+
+use zende::Zigzag;
+
+use super::{write_all, Endec};
+use crate::{
+    de::read::Read,
+    error::{Category, Error, Result},
+    util,
+};
+
+/// The binary NBT dialect used by Minecraft: Bedrock Edition to send data over
+/// the network.
+///
+/// `Int`s, `Long`s and the lengths of `List`s and arrays are stored as
+/// zigzag-encoded [LEB128] integers, while string lengths are stored as
+/// unsigned LEB128 integers. Everything else matches [`LittleEndian`].
+///
+/// [LEB128]: https://en.wikipedia.org/wiki/LEB128
+/// [`LittleEndian`]: super::LittleEndian
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct VarInt;
+
+impl Endec for VarInt {
+    #[inline]
+    fn read_i16<'de, R>(read: &mut R) -> Result<i16>
+    where
+        R: Read<'de>,
+    {
+        read.read_array().map(i16::from_le_bytes)
+    }
+
+    #[inline]
+    fn read_i32<'de, R>(read: &mut R) -> Result<i32>
+    where
+        R: Read<'de>,
+    {
+        #[allow(clippy::cast_possible_truncation)]
+        let n = read_leb128(read, u32::BITS)? as u32;
+        Ok(n.zigzag())
+    }
+
+    #[inline]
+    fn read_i64<'de, R>(read: &mut R) -> Result<i64>
+    where
+        R: Read<'de>,
+    {
+        let n = read_leb128(read, u64::BITS)?;
+        Ok(n.zigzag())
+    }
+
+    #[inline]
+    fn read_f32<'de, R>(read: &mut R) -> Result<f32>
+    where
+        R: Read<'de>,
+    {
+        read.read_array().map(f32::from_le_bytes)
+    }
+
+    #[inline]
+    fn read_f64<'de, R>(read: &mut R) -> Result<f64>
+    where
+        R: Read<'de>,
+    {
+        read.read_array().map(f64::from_le_bytes)
+    }
+
+    #[inline]
+    fn read_string_len<'de, R>(read: &mut R) -> Result<usize>
+    where
+        R: Read<'de>,
+    {
+        #[allow(clippy::cast_possible_truncation)]
+        let len = read_leb128(read, u32::BITS)? as u32;
+        Ok(len as usize)
+    }
+
+    #[inline]
+    fn read_len<'de, R>(read: &mut R) -> Result<i32>
+    where
+        R: Read<'de>,
+    {
+        Self::read_i32(read)
+    }
+
+    #[inline]
+    fn decode_str(bytes: Cow<'_, [u8]>) -> Option<Cow<'_, str>> {
+        util::decode_utf8(bytes)
+    }
+
+    #[inline]
+    fn write_i16<W>(writer: &mut W, value: i16) -> Result<()>
+    where
+        W: zc_io::Write,
+    {
+        write_all(writer, &value.to_le_bytes())
+    }
+
+    #[inline]
+    fn write_i32<W>(writer: &mut W, value: i32) -> Result<()>
+    where
+        W: zc_io::Write,
+    {
+        mini_leb128::write_u32(writer, value.zigzag())
+            .map(drop)
+            .map_err(Error::io)
+    }
+
+    #[inline]
+    fn write_i64<W>(writer: &mut W, value: i64) -> Result<()>
+    where
+        W: zc_io::Write,
+    {
+        mini_leb128::write_u64(writer, value.zigzag())
+            .map(drop)
+            .map_err(Error::io)
+    }
+
+    #[inline]
+    fn write_f32<W>(writer: &mut W, value: f32) -> Result<()>
+    where
+        W: zc_io::Write,
+    {
+        write_all(writer, &value.to_le_bytes())
+    }
+
+    #[inline]
+    fn write_f64<W>(writer: &mut W, value: f64) -> Result<()>
+    where
+        W: zc_io::Write,
+    {
+        write_all(writer, &value.to_le_bytes())
+    }
+
+    #[inline]
+    fn write_string_len<W>(writer: &mut W, len: usize) -> Result<()>
+    where
+        W: zc_io::Write,
+    {
+        let Ok(len) = u32::try_from(len) else {
+            return Err(Error::new(
+                Category::InvalidData,
+                format!("string of {len} bytes is too long"),
+            ));
+        };
+
+        mini_leb128::write_u32(writer, len)
+            .map(drop)
+            .map_err(Error::io)
+    }
+
+    #[inline]
+    fn encode_str(s: &str) -> Cow<'_, [u8]> {
+        Cow::Borrowed(s.as_bytes())
+    }
+}
+
+/// Reads an unsigned LEB128 integer that must fit in `bits` bits.
+fn read_leb128<'de, R>(read: &mut R, bits: u32) -> Result<u64>
+where
+    R: Read<'de>,
+{
+    let offset = read.byte_offset();
+    let mut value = 0;
+    let mut shift = 0;
+
+    loop {
+        let byte = read.next()?;
+        let chunk = u64::from(byte & 0x7F);
+
+        if shift >= bits || (chunk << shift) >> shift != chunk {
+            return Err(read.error(Category::InvalidInput, "VarInt is too large", offset));
+        }
+
+        value |= chunk << shift;
+
+        if byte & 0x80 == 0 {
+            break;
+        }
+
+        shift += 7;
+    }
+
+    if bits < u64::BITS && value >> bits != 0 {
+        return Err(read.error(Category::InvalidInput, "VarInt is too large", offset));
+    }
+
+    Ok(value)
+}
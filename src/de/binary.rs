@@ -0,0 +1,3086 @@
+//! TODO
+
+use alloc::{borrow::Cow, format, string::String, vec::Vec};
+use core::{fmt, iter::FusedIterator, marker::PhantomData, ops::Range};
+
+use serde::de::{
+    self,
+    value::{BorrowedBytesDeserializer, BorrowedStrDeserializer, BytesDeserializer},
+    Deserialize, DeserializeSeed, IntoDeserializer, Unexpected, Visitor,
+};
+
+use super::{
+    key::{KeyDeserializer, Reference},
+    read::{Read, SliceRead},
+};
+use crate::{
+    binary::{ByteOrder, Endec},
+    error::{BacktraceGuard, Category, Detail, Error, Result, Segment},
+    map::Map,
+    util::{
+        Depth, Quota, BYTE_ARRAY_TOKEN, INT_ARRAY_REF_TOKEN, INT_ARRAY_TOKEN, LONG_ARRAY_REF_TOKEN,
+        LONG_ARRAY_TOKEN, NAMED_TOKEN, RAW_NBT_TOKEN,
+    },
+    Type,
+};
+
+pub use crate::util::{DISK_QUOTA, NETWORK_QUOTA};
+
+/// A structure that deserializes binary NBT into Rust values.
+///
+/// The dialect of binary NBT is selected with `E`, e.g.
+/// [`BigEndian`](crate::binary::BigEndian) for Minecraft: Java Edition.
+///
+/// When reading from a slice, strings, names and `ByteArray`s are borrowed
+/// from the input whenever their bytes can be used as-is, so fields of type
+/// `&'de str`, `&'de [u8]` or a `#[serde(borrow)]` `Cow<'de, str>` avoid
+/// copying them. Strings in modified UTF-8 can only be borrowed if they are
+/// also valid UTF-8.
+pub struct Deserializer<R, E> {
+    read: R,
+    depth: Depth,
+    quota: Quota,
+    backtraces: bool,
+    lossy_strings: bool,
+    root_name: RootName,
+    endec: PhantomData<E>,
+}
+
+/// What a [`Deserializer`] does with the name of the root tag.
+enum RootName {
+    /// Hand it to [`Named`](crate::Named), and skip it otherwise.
+    Keep,
+    /// Always skip it.
+    Ignore,
+    /// Fail unless it is exactly this.
+    Require(String),
+    /// There is none in the input.
+    Absent,
+}
+
+impl<'de, R, E> Deserializer<R, E>
+where
+    R: Read<'de>,
+    E: Endec,
+{
+    /// Creates a binary NBT deserializer from one of the possible `serde_nbt`
+    /// input sources.
+    ///
+    /// Typically it is more convenient to use one of these functions instead:
+    ///
+    /// - [`from_be_slice`](crate::de::from_be_slice)
+    /// - [`from_be_reader`](crate::de::from_be_reader)
+    /// - [`Options`](crate::de::Options), to configure it first
+    #[must_use]
+    #[inline]
+    pub fn new(read: R) -> Self {
+        Deserializer {
+            read,
+            depth: Depth::default(),
+            quota: Quota::default(),
+            backtraces: true,
+            lossy_strings: false,
+            root_name: RootName::Keep,
+            endec: PhantomData,
+        }
+    }
+
+    /// Sets how deeply `List`s and `Compound`s may nest before
+    /// deserialization fails with [`Category::RecursionLimitExceeded`].
+    ///
+    /// The default limit is 512, the same as Minecraft's.
+    #[inline]
+    pub fn set_recursion_limit(&mut self, limit: usize) {
+        self.depth = Depth::new(limit);
+    }
+
+    /// Lets `List`s and `Compound`s nest arbitrarily deeply.
+    ///
+    /// Only do this for input from a trusted source; a deep enough input will
+    /// overflow the stack.
+    #[inline]
+    pub fn disable_recursion_limit(&mut self) {
+        self.depth = Depth::unbounded();
+    }
+
+    /// Sets how many bytes of memory decoding may allocate before it fails
+    /// with [`Category::QuotaExceeded`].
+    ///
+    /// Strings, arrays, `List`s and `Compound` entries are charged against
+    /// the quota before they are allocated, using the same estimates as
+    /// Minecraft's `NbtAccounter`. The default is [`DISK_QUOTA`]; use
+    /// [`NETWORK_QUOTA`] for packets from untrusted clients.
+    ///
+    /// The quota covers the whole lifetime of the deserializer, except that
+    /// a [`StreamDeserializer`] gives each root tag a fresh quota.
+    #[inline]
+    pub fn set_quota(&mut self, bytes: usize) {
+        self.quota = Quota::new(bytes);
+    }
+
+    /// Lets decoding allocate as much memory as the input asks for.
+    ///
+    /// Only do this for input from a trusted source; a few bytes of input can
+    /// declare lengths of gigabytes.
+    #[inline]
+    pub fn disable_quota(&mut self) {
+        self.quota = Quota::unlimited();
+    }
+
+    /// Stops errors raised while this deserializer is running from capturing
+    /// a backtrace or a [`Hexdump`](crate::error::Hexdump) of the input.
+    ///
+    /// Capturing a backtrace is expensive whenever `RUST_BACKTRACE` or
+    /// `RUST_LIB_BACKTRACE` is set, which adds up when rejecting large volumes
+    /// of bad input. Errors still record their category, message and position.
+    ///
+    /// This covers errors raised by `Deserialize` implementations as well,
+    /// as long as they are raised on the thread driving this deserializer.
+    #[inline]
+    pub fn disable_backtraces(&mut self) {
+        self.backtraces = false;
+        self.read.disable_error_context();
+    }
+
+    /// Replaces malformed sequences in strings with U+FFFD REPLACEMENT
+    /// CHARACTER instead of failing with [`Category::InvalidInput`].
+    ///
+    /// Files written by third-party tools sometimes hold names or text that
+    /// were never valid in the dialect's string encoding; this lets them
+    /// load, at the cost of not being able to write those strings back
+    /// byte-for-byte.
+    #[inline]
+    pub fn enable_lossy_strings(&mut self) {
+        self.lossy_strings = true;
+    }
+
+    /// Fails with [`Category::InvalidData`] unless the root tag is named
+    /// `name`.
+    #[inline]
+    pub fn require_root_name<S>(&mut self, name: S)
+    where
+        S: Into<String>,
+    {
+        self.root_name = RootName::Require(name.into());
+    }
+
+    /// Skips over the name of the root tag, even when deserializing a
+    /// [`Named`](crate::Named), which is given an empty name instead.
+    #[inline]
+    pub fn ignore_root_name(&mut self) {
+        self.root_name = RootName::Ignore;
+    }
+
+    /// Reads the root tag as an ID followed directly by its payload, with no
+    /// name in between.
+    ///
+    /// This is how Minecraft: Java Edition sends NBT over the network since
+    /// 1.20.2. A [`Named`](crate::Named) is given an empty name.
+    #[inline]
+    pub fn expect_unnamed_root(&mut self) {
+        self.root_name = RootName::Absent;
+    }
+
+    /// Returns the offset of the next byte this deserializer will read,
+    /// counted from the start of the input.
+    ///
+    /// After a root tag has been deserialized, this is where whatever follows
+    /// it in the input begins.
+    #[must_use]
+    #[inline]
+    pub fn byte_offset(&self) -> u64 {
+        self.read.byte_offset()
+    }
+
+    /// Turns this deserializer into an iterator over the root tags packed
+    /// back to back in its input, deserializing each as a `T`.
+    ///
+    /// See [`StreamDeserializer`].
+    #[allow(clippy::should_implement_trait)]
+    #[must_use]
+    #[inline]
+    pub fn into_iter<T>(self) -> StreamDeserializer<'de, R, T, E>
+    where
+        T: Deserialize<'de>,
+    {
+        StreamDeserializer {
+            offset: self.read.byte_offset(),
+            quota: self.quota,
+            de: self,
+            failed: false,
+            output: PhantomData,
+            lifetime: PhantomData,
+        }
+    }
+
+    /// Turns this deserializer into a pull parser over the root tag of its
+    /// input.
+    ///
+    /// See [`Parser`].
+    #[must_use]
+    #[inline]
+    pub fn into_parser(self) -> Parser<'de, R, E> {
+        Parser::from_deserializer(self)
+    }
+
+    /// The `Deserializer::end` method should be called after a value has
+    /// been fully deserialized. This allows the `Deserializer` to validate
+    /// that the input has been fully consumed.
+    ///
+    /// Input sources of unknown length, such as readers, are never checked.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of [`Category::InvalidInput`] if any bytes remain in
+    /// the input.
+    pub fn end(&mut self) -> Result<()> {
+        let _guard = BacktraceGuard::new(self.backtraces);
+
+        match self.read.remaining() {
+            Some(0) | None => Ok(()),
+            Some(n) => {
+                let offset = self.read.byte_offset();
+                let message = format!("{n} trailing bytes after the root tag");
+                Err(self.read.error(Category::InvalidInput, message, offset))
+            }
+        }
+    }
+
+    /// Like [`Deserializer::end`], but reads ahead to check input of unknown
+    /// length as well.
+    ///
+    /// Only call this on input known to be finite, such as a decompressed
+    /// slice; on a live stream it blocks until more data or EOF arrives.
+    #[cfg(feature = "compression")]
+    pub(crate) fn end_of_input(&mut self) -> Result<()> {
+        self.end()?;
+
+        let _guard = BacktraceGuard::new(self.backtraces);
+        let offset = self.read.byte_offset();
+
+        match self.read.next() {
+            Ok(_) => Err(self.read.error(
+                Category::InvalidInput,
+                "trailing bytes after the root tag",
+                offset,
+            )),
+            Err(error) if error.is_eof() => Ok(()),
+            Err(error) => Err(error),
+        }
+    }
+
+    ////////////////////////////////////////////////////////////////////////////
+    // Parsing Methods
+    ////////////////////////////////////////////////////////////////////////////
+
+    /// Parses the header of the root tag, returning its type, the offset at
+    /// which it starts and its name.
+    ///
+    /// The name is only read if `keep_name` is set or a specific name is
+    /// required; otherwise it is skipped and an empty name is returned, as it
+    /// is when the root tag has no name at all.
+    fn parse_root(&mut self, keep_name: bool) -> Result<(Type, u64, Cow<'de, str>)> {
+        let offset = self.read.byte_offset();
+        let id = self.read.next()?;
+        let ty = self.parse_type(id, offset)?;
+
+        let name = match self.root_name {
+            RootName::Keep if keep_name => self.parse_str()?,
+            RootName::Require(_) => self.parse_str()?,
+            RootName::Keep | RootName::Ignore => {
+                let len = E::read_string_len(&mut self.read)?;
+                self.read.skip(len)?;
+                Cow::Borrowed("")
+            }
+            RootName::Absent => Cow::Borrowed(""),
+        };
+
+        if let RootName::Require(expected) = &self.root_name {
+            if *expected != name {
+                let message = format!("expected root tag to be named {expected:?}, found {name:?}");
+                return Err(self.read.error(Category::InvalidData, message, offset));
+            }
+        }
+
+        Ok((ty, offset, name))
+    }
+
+    fn parse_type(&self, id: u8, offset: u64) -> Result<Type> {
+        match Type::from_id(id) {
+            Some(ty) => Ok(ty),
+            None if id == 0 => {
+                Err(self
+                    .read
+                    .error(Category::InvalidInput, "unexpected End tag", offset))
+            }
+            None => Err(self.read.error(
+                Category::InvalidInput,
+                format!("invalid tag ID {id}"),
+                offset,
+            )),
+        }
+    }
+
+    fn parse_str(&mut self) -> Result<Cow<'de, str>> {
+        let offset = self.read.byte_offset();
+        let len = E::read_string_len(&mut self.read)?;
+        self.charge(offset, |quota| quota.charge_str(len))?;
+        let bytes = self.read.read_slice(len)?;
+
+        if self.lossy_strings {
+            return Ok(decode_str_lossy::<E>(bytes));
+        }
+
+        match E::decode_str(bytes) {
+            Some(s) => Ok(s),
+            None => Err(self.read.error(
+                Category::InvalidInput,
+                "string contains invalid characters",
+                offset,
+            )),
+        }
+    }
+
+    /// Parses the length of a `List` or one of the array types.
+    ///
+    /// Every element takes up at least one byte, so when the size of the
+    /// input is known a length that could not possibly fit in what remains
+    /// is rejected before anything is allocated for it.
+    fn parse_len(&mut self) -> Result<usize> {
+        let offset = self.read.byte_offset();
+        let len = E::read_len(&mut self.read)?;
+
+        let Ok(len) = usize::try_from(len) else {
+            return Err(self.read.error(
+                Category::InvalidInput,
+                format!("invalid negative length {len}"),
+                offset,
+            ));
+        };
+
+        match self.read.remaining() {
+            Some(remaining) if len > remaining => {
+                let message = format!("length {len} exceeds the {remaining} bytes remaining");
+                let error = self.read.error(Category::UnexpectedEof, message, offset);
+                Err(error.with_detail(Detail::Incomplete {
+                    needed: len - remaining,
+                }))
+            }
+            _ => Ok(len),
+        }
+    }
+
+    /// Parses the element type and length of a `List`.
+    ///
+    /// Empty lists are permitted to declare `TAG_End` as their element type,
+    /// in which case `None` is returned in place of the element type.
+    fn parse_list_header(&mut self) -> Result<(Option<Type>, usize)> {
+        let offset = self.read.byte_offset();
+        let id = self.read.next()?;
+        let len = self.parse_len()?;
+
+        if id == 0 {
+            if len != 0 {
+                let message = format!("list of End tags has non-zero length {len}");
+                let error = self.read.error(Category::InvalidInput, message, offset);
+                return Err(error.with_detail(Detail::InvalidLength {
+                    declared: len,
+                    actual: 0,
+                }));
+            }
+
+            return Ok((None, 0));
+        }
+
+        let ty = self.parse_type(id, offset)?;
+        Ok((Some(ty), len))
+    }
+
+    /// Charges the allocation `f` describes for the tag at `offset` against
+    /// the memory quota.
+    fn charge<F>(&mut self, offset: u64, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut Quota) -> Result<()>,
+    {
+        f(&mut self.quota).map_err(|error| self.read.fix_position(error, offset))
+    }
+
+    /// Enters a `List` or `Compound` starting at `offset`.
+    fn enter(&mut self, offset: u64) -> Result<()> {
+        self.depth
+            .enter()
+            .map_err(|error| self.read.fix_position(error, offset))
+    }
+
+    /// Skips over the payload of a tag of the given type, returning its
+    /// encoded bytes.
+    ///
+    /// The bytes are borrowed from the input if the source allows it, and
+    /// recorded as they are skipped otherwise.
+    fn read_payload(&mut self, ty: Type) -> Result<Cow<'de, [u8]>> {
+        let offset = self.read.byte_offset();
+
+        if self.read.consumed_since(offset).is_some() {
+            self.skip_payload(ty)?;
+
+            if let Some(bytes) = self.read.consumed_since(offset) {
+                return Ok(Cow::Borrowed(bytes));
+            }
+        }
+
+        self.charge(offset, |quota| quota.charge_array(0, 1))?;
+
+        let mut recorder = Deserializer::<_, E> {
+            read: Recorder {
+                inner: &mut self.read,
+                bytes: Vec::new(),
+                quota: self.quota,
+            },
+            depth: self.depth,
+            quota: self.quota,
+            backtraces: self.backtraces,
+            lossy_strings: self.lossy_strings,
+            root_name: RootName::Ignore,
+            endec: PhantomData,
+        };
+
+        recorder.skip_payload(ty)?;
+        self.quota = recorder.read.quota;
+        Ok(Cow::Owned(recorder.read.bytes))
+    }
+
+    /// Skips over the payload of a tag of the given type without allocating.
+    fn skip_payload(&mut self, ty: Type) -> Result<()> {
+        if let Type::List | Type::Compound = ty {
+            let offset = self.read.byte_offset();
+            self.enter(offset)?;
+            let result = self.skip_nested(ty);
+            self.depth.leave();
+            result
+        } else {
+            self.skip_nested(ty)
+        }
+    }
+
+    /// Skips over the payload of a tag of the given type, assuming the
+    /// recursion limit has already been checked for `List`s and `Compound`s.
+    fn skip_nested(&mut self, ty: Type) -> Result<()> {
+        match ty {
+            Type::Byte => self.read.skip(1),
+            Type::Short => E::read_i16(&mut self.read).map(drop),
+            Type::Int => E::read_i32(&mut self.read).map(drop),
+            Type::Long => E::read_i64(&mut self.read).map(drop),
+            Type::Float => E::read_f32(&mut self.read).map(drop),
+            Type::Double => E::read_f64(&mut self.read).map(drop),
+            Type::ByteArray => {
+                let len = self.parse_len()?;
+                self.read.skip(len)
+            }
+            Type::String => {
+                let len = E::read_string_len(&mut self.read)?;
+                self.read.skip(len)
+            }
+            Type::List => {
+                let (ty, len) = self.parse_list_header()?;
+
+                if let Some(ty) = ty {
+                    for _ in 0..len {
+                        self.skip_payload(ty)?;
+                    }
+                }
+
+                Ok(())
+            }
+            Type::Compound => loop {
+                let offset = self.read.byte_offset();
+                let id = self.read.next()?;
+
+                if id == 0 {
+                    return Ok(());
+                }
+
+                let ty = self.parse_type(id, offset)?;
+                let len = E::read_string_len(&mut self.read)?;
+                self.read.skip(len)?;
+                self.skip_payload(ty)?;
+            },
+            Type::IntArray => {
+                let len = self.parse_len()?;
+                E::skip_i32s(&mut self.read, len)
+            }
+            Type::LongArray => {
+                let len = self.parse_len()?;
+                E::skip_i64s(&mut self.read, len)
+            }
+        }
+    }
+}
+
+impl<E> Deserializer<SliceRead<'_>, E>
+where
+    E: Endec,
+{
+    /// Moves to the payload of the last entry named `key` in a `Compound`
+    /// whose payload starts at the current position, returning its type.
+    ///
+    /// Names are compared in their encoded form, so nothing is decoded or
+    /// charged to the quota for the entries passed over.
+    fn locate_entry(&mut self, key: &str) -> Result<Type> {
+        let offset = self.read.byte_offset();
+        let key_bytes = E::encode_str(key);
+        let mut found = None;
+
+        loop {
+            let entry = self.read.byte_offset();
+            let id = self.read.next()?;
+
+            if id == 0 {
+                break;
+            }
+
+            let ty = self.parse_type(id, entry)?;
+            let len = E::read_string_len(&mut self.read)?;
+            let name = self.read.read_slice(len)?;
+            #[allow(clippy::cast_possible_truncation)]
+            let start = self.read.byte_offset() as usize;
+            self.skip_payload(ty)
+                .map_err(|error| error.prepend_path(&*decode_str_lossy::<E>(name.clone())))?;
+
+            if *name == *key_bytes {
+                found = Some((ty, start));
+            }
+        }
+
+        let Some((ty, start)) = found else {
+            let message = format!("no entry named {key:?}");
+            return Err(self.read.error(Category::InvalidData, message, offset));
+        };
+
+        self.read.seek(start);
+        Ok(ty)
+    }
+
+    /// Moves to the payload of the element at `index` of a `List` whose
+    /// payload starts at the current position, returning its type.
+    fn locate_element(&mut self, index: usize) -> Result<Type> {
+        let offset = self.read.byte_offset();
+        let (ty, len) = self.parse_list_header()?;
+
+        let ty = match ty {
+            Some(ty) if index < len => ty,
+            _ => return Err(self.index_out_of_bounds(index, len, offset)),
+        };
+
+        for i in 0..index {
+            self.skip_payload(ty)
+                .map_err(|error| error.prepend_path(i))?;
+        }
+
+        Ok(ty)
+    }
+
+    /// Moves to the element at `index` of an array of the given type whose
+    /// payload starts at the current position, returning the element type.
+    fn locate_array_element(&mut self, ty: Type, index: usize) -> Result<Type> {
+        let offset = self.read.byte_offset();
+        let len = self.parse_len()?;
+
+        if index >= len {
+            return Err(self.index_out_of_bounds(index, len, offset));
+        }
+
+        match ty {
+            Type::ByteArray => self.read.skip(index).map(|()| Type::Byte),
+            Type::IntArray => E::skip_i32s(&mut self.read, index).map(|()| Type::Int),
+            _ => E::skip_i64s(&mut self.read, index).map(|()| Type::Long),
+        }
+    }
+
+    #[cold]
+    fn index_out_of_bounds(&self, index: usize, len: usize, offset: u64) -> Error {
+        let message = format!("index {index} is out of bounds for a length of {len}");
+        self.read.error(Category::InvalidData, message, offset)
+    }
+}
+
+/// Deserializes an instance of type `T` from exactly the payload of a tag of
+/// type `ty`.
+pub(crate) fn from_payload<'de, T, E>(ty: Type, slice: &'de [u8]) -> Result<T>
+where
+    T: Deserialize<'de>,
+    E: Endec,
+{
+    let mut de = Deserializer::<_, E>::new(SliceRead::new(slice));
+    let _guard = BacktraceGuard::new(de.backtraces);
+    let value = T::deserialize(PayloadDeserializer::new(&mut de, ty, 0))?;
+    de.end()?;
+    Ok(value)
+}
+
+/// Finds the tag at `path` below the root tag of `slice`, returning its type
+/// and the range of its payload.
+///
+/// When a key appears more than once in a `Compound`, the last entry wins.
+pub(crate) fn locate<E>(slice: &[u8], path: &[Segment]) -> Result<(Type, Range<usize>)>
+where
+    E: Endec,
+{
+    let mut de = Deserializer::<_, E>::new(SliceRead::new(slice));
+    let _guard = BacktraceGuard::new(de.backtraces);
+    de.root_name = RootName::Ignore;
+    let (mut ty, _, _) = de.parse_root(false)?;
+
+    for (depth, segment) in path.iter().enumerate() {
+        let offset = de.read.byte_offset();
+
+        let result = match (segment, ty) {
+            (Segment::Key(key), Type::Compound) => de.locate_entry(key),
+            (Segment::Index(index), Type::List) => de.locate_element(*index),
+            (&Segment::Index(index), Type::ByteArray | Type::IntArray | Type::LongArray) => {
+                de.locate_array_element(ty, index)
+            }
+            (Segment::Key(_), _) => {
+                let message = format!("expected a Compound, found {ty}");
+                let error = de.read.error(Category::InvalidData, message, offset);
+                Err(error.with_detail(Detail::InvalidType {
+                    expected: Some(Type::Compound),
+                    found: Some(ty),
+                }))
+            }
+            (Segment::Index(_), _) => {
+                let message = format!("expected a List or array, found {ty}");
+                let error = de.read.error(Category::InvalidData, message, offset);
+                Err(error.with_detail(Detail::InvalidType {
+                    expected: None,
+                    found: Some(ty),
+                }))
+            }
+        };
+
+        ty = result.map_err(|error| {
+            path[..depth]
+                .iter()
+                .rev()
+                .fold(error, |error, segment| error.prepend_path(segment.clone()))
+        })?;
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    let start = de.read.byte_offset() as usize;
+    de.skip_payload(ty).map_err(|error| {
+        path.iter()
+            .rev()
+            .fold(error, |error, segment| error.prepend_path(segment.clone()))
+    })?;
+    #[allow(clippy::cast_possible_truncation)]
+    let end = de.read.byte_offset() as usize;
+
+    Ok((ty, start..end))
+}
+
+/// Decodes a string, replacing anything the dialect's encoding considers
+/// malformed with U+FFFD REPLACEMENT CHARACTER.
+fn decode_str_lossy<E>(bytes: Cow<'_, [u8]>) -> Cow<'_, str>
+where
+    E: Endec,
+{
+    match bytes {
+        Cow::Borrowed(bytes) => {
+            E::decode_str(Cow::Borrowed(bytes)).unwrap_or_else(|| String::from_utf8_lossy(bytes))
+        }
+        Cow::Owned(bytes) if E::decode_str(Cow::Borrowed(&bytes)).is_some() => {
+            E::decode_str(Cow::Owned(bytes)).expect("string was already decoded")
+        }
+        Cow::Owned(bytes) => Cow::Owned(String::from_utf8_lossy(&bytes).into_owned()),
+    }
+}
+
+macro_rules! deserialize_root {
+    ($($method:ident$(($($arg:ident: $ty:ty),*))?,)*) => {
+        $(
+            #[inline]
+            fn $method<V>(self, $($($arg: $ty,)*)? visitor: V) -> Result<V::Value>
+            where
+                V: Visitor<'de>,
+            {
+                let _guard = BacktraceGuard::new(self.backtraces);
+                let (ty, offset, _) = self.parse_root(false)?;
+                PayloadDeserializer::new(self, ty, offset).$method($($($arg,)*)? visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, R, E> de::Deserializer<'de> for &mut Deserializer<R, E>
+where
+    R: Read<'de>,
+    E: Endec,
+{
+    type Error = Error;
+
+    deserialize_root! {
+        deserialize_any,
+        deserialize_bool,
+        deserialize_i8,
+        deserialize_i16,
+        deserialize_i32,
+        deserialize_i64,
+        deserialize_i128,
+        deserialize_u8,
+        deserialize_u16,
+        deserialize_u32,
+        deserialize_u64,
+        deserialize_u128,
+        deserialize_f32,
+        deserialize_f64,
+        deserialize_char,
+        deserialize_str,
+        deserialize_string,
+        deserialize_bytes,
+        deserialize_byte_buf,
+        deserialize_option,
+        deserialize_unit,
+        deserialize_unit_struct(name: &'static str),
+        deserialize_seq,
+        deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+        deserialize_map,
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+        deserialize_identifier,
+        deserialize_ignored_any,
+    }
+
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let _guard = BacktraceGuard::new(self.backtraces);
+        let (ty, offset, root_name) = self.parse_root(name == NAMED_TOKEN)?;
+        let payload = PayloadDeserializer::new(self, ty, offset);
+
+        if name == NAMED_TOKEN {
+            visitor.visit_seq(NamedAccess {
+                name: Some(root_name),
+                payload: Some(payload),
+            })
+        } else {
+            payload.deserialize_newtype_struct(name, visitor)
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// An iterator that deserializes a sequence of root tags packed back to back
+/// in the same input, such as the block entities of a Bedrock sub-chunk.
+///
+/// A stream deserializer is created with [`Deserializer::into_iter`] or
+/// [`StreamDeserializer::new`]. The iterator ends cleanly once the input is
+/// exhausted exactly between two root tags; the first error, including a
+/// root tag cut off partway through, is yielded and ends the iteration.
+///
+/// [`byte_offset`](Self::byte_offset) tells where each root tag starts when
+/// called before [`next`](Iterator::next), and where the stream stopped once
+/// the iteration is over.
+///
+/// The memory quota applies to each root tag on its own, so a long stream of
+/// small tags does not run out of quota.
+pub struct StreamDeserializer<'de, R, T, E> {
+    de: Deserializer<R, E>,
+    /// The quota the deserializer was configured with, restored before each
+    /// root tag.
+    quota: Quota,
+    offset: u64,
+    failed: bool,
+    output: PhantomData<T>,
+    lifetime: PhantomData<&'de ()>,
+}
+
+impl<'de, R, T, E> StreamDeserializer<'de, R, T, E>
+where
+    R: Read<'de>,
+    T: Deserialize<'de>,
+    E: Endec,
+{
+    /// Creates a stream deserializer from one of the possible `serde_nbt`
+    /// input sources, with the same defaults as [`Deserializer::new`].
+    #[must_use]
+    #[inline]
+    pub fn new(read: R) -> Self {
+        Deserializer::new(read).into_iter()
+    }
+
+    /// Returns the offset just past the last root tag that was successfully
+    /// deserialized, counted from the start of the input.
+    ///
+    /// Before the first call to `next` this is where the stream starts, and
+    /// after the last successful one it is where any data following the
+    /// stream begins.
+    #[must_use]
+    #[inline]
+    pub fn byte_offset(&self) -> u64 {
+        self.offset
+    }
+}
+
+impl<'de, R, T, E> Iterator for StreamDeserializer<'de, R, T, E>
+where
+    R: Read<'de>,
+    T: Deserialize<'de>,
+    E: Endec,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Result<T>> {
+        if self.failed || self.de.read.remaining() == Some(0) {
+            return None;
+        }
+
+        self.de.quota = self.quota;
+
+        match T::deserialize(&mut self.de) {
+            Ok(value) => {
+                self.offset = self.de.read.byte_offset();
+                Some(Ok(value))
+            }
+            // Input sources of unknown length only reveal that they are
+            // exhausted when reading the next root tag's ID fails.
+            Err(error) if error.is_eof() && self.de.read.byte_offset() == self.offset => None,
+            Err(error) => {
+                self.failed = true;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+impl<'de, R, T, E> FusedIterator for StreamDeserializer<'de, R, T, E>
+where
+    R: Read<'de>,
+    T: Deserialize<'de>,
+    E: Endec,
+{
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Finds where a root tag ends in a buffer that is still being received,
+/// without decoding it.
+///
+/// A `Scanner` walks the structure of a root tag, skipping over payloads and
+/// remembering which `List`s and `Compound`s it is inside of, until it runs
+/// out of input. It then reports how many more bytes it needs at the very
+/// least, and picks up where it left off the next time it is handed the
+/// buffer. Nothing is allocated for the tags themselves and nothing before
+/// the point where the buffer ended is looked at again.
+///
+/// Once the scanner reports that the root tag is complete, deserialize it
+/// from that prefix of the buffer, for example with
+/// [`Options::from_slice_partial`](crate::de::Options::from_slice_partial).
+///
+/// The scanner does not enforce a recursion limit or a memory quota, since
+/// it never recurses or allocates per tag; those still apply when the root
+/// tag is deserialized.
+pub struct Scanner<E> {
+    /// How many bytes of the buffer have been fully accounted for.
+    offset: usize,
+    state: ScanState,
+    /// The `List`s and `Compound`s enclosing the current position.
+    stack: Vec<Frame>,
+    endec: PhantomData<E>,
+}
+
+/// What a [`Scanner`] expects to find next.
+#[derive(Clone, Copy)]
+enum ScanState {
+    /// The ID and name of the root tag.
+    Root,
+    /// The payload of a tag of the given type.
+    Payload(Type),
+    /// Whatever follows the end of a payload inside the innermost `List` or
+    /// `Compound`, or nothing once the root tag is complete.
+    Next,
+}
+
+enum Frame {
+    List { ty: Option<Type>, remaining: usize },
+    Compound,
+}
+
+/// The outcome of [`Scanner::scan`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Progress {
+    /// The root tag takes up this many bytes at the start of the buffer.
+    Complete(usize),
+    /// The buffer ends partway through the root tag, and needs at least
+    /// `needed` more bytes before the scanner can get any further.
+    Incomplete {
+        /// How many more bytes are needed at the very least.
+        needed: usize,
+    },
+}
+
+impl<E> Scanner<E>
+where
+    E: Endec,
+{
+    /// Creates a scanner for a root tag at the start of a buffer.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Scanner {
+            offset: 0,
+            state: ScanState::Root,
+            stack: Vec::new(),
+            endec: PhantomData,
+        }
+    }
+
+    /// Continues scanning `buf` from where the last call left off.
+    ///
+    /// `buf` must start with the same bytes as the buffers handed to earlier
+    /// calls; only bytes appended to it since are new to the scanner.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of [`Category::InvalidInput`] if the bytes received
+    /// so far are not valid binary NBT. Running out of input is never an
+    /// error; it is reported as [`Progress::Incomplete`] instead.
+    pub fn scan(&mut self, buf: &[u8]) -> Result<Progress> {
+        let mut de = Deserializer::<_, E>::new(SliceRead::new(buf));
+
+        let result = de.read.skip(self.offset).and_then(|()| loop {
+            match self.step(&mut de)? {
+                Some(len) => break Ok(len),
+                #[allow(clippy::cast_possible_truncation)]
+                None => self.offset = de.read.byte_offset() as usize,
+            }
+        });
+
+        match result {
+            Ok(len) => Ok(Progress::Complete(len)),
+            Err(error) => match error.bytes_needed() {
+                Some(needed) => Ok(Progress::Incomplete { needed }),
+                None => Err(error),
+            },
+        }
+    }
+
+    /// Reads the next thing the scanner expects in one go, returning the
+    /// length of the root tag once there is nothing left to read.
+    ///
+    /// The state only changes once everything it depends on has been read,
+    /// so that running out of input partway through leaves the scanner
+    /// where it was.
+    fn step<'de, R>(&mut self, de: &mut Deserializer<R, E>) -> Result<Option<usize>>
+    where
+        R: Read<'de>,
+    {
+        match self.state {
+            ScanState::Root => {
+                let offset = de.read.byte_offset();
+                let id = de.read.next()?;
+                let ty = de.parse_type(id, offset)?;
+                let len = E::read_string_len(&mut de.read)?;
+                de.read.skip(len)?;
+                self.state = ScanState::Payload(ty);
+            }
+            ScanState::Payload(Type::List) => {
+                let (ty, remaining) = de.parse_list_header()?;
+                self.stack.push(Frame::List { ty, remaining });
+                self.state = ScanState::Next;
+            }
+            ScanState::Payload(Type::Compound) => {
+                self.stack.push(Frame::Compound);
+                self.state = ScanState::Next;
+            }
+            ScanState::Payload(ty) => {
+                de.skip_nested(ty)?;
+                self.state = ScanState::Next;
+            }
+            ScanState::Next => match self.stack.last_mut() {
+                None => return Ok(Some(self.offset)),
+                Some(Frame::List {
+                    ty: Some(ty),
+                    remaining,
+                }) if *remaining > 0 => {
+                    *remaining -= 1;
+                    self.state = ScanState::Payload(*ty);
+                }
+                Some(Frame::List { .. }) => {
+                    self.stack.pop();
+                }
+                Some(Frame::Compound) => {
+                    let offset = de.read.byte_offset();
+                    let id = de.read.next()?;
+
+                    if id == 0 {
+                        self.stack.pop();
+                    } else {
+                        let ty = de.parse_type(id, offset)?;
+                        let len = E::read_string_len(&mut de.read)?;
+                        de.read.skip(len)?;
+                        self.state = ScanState::Payload(ty);
+                    }
+                }
+            },
+        }
+
+        Ok(None)
+    }
+}
+
+impl<E> Default for Scanner<E>
+where
+    E: Endec,
+{
+    #[inline]
+    fn default() -> Self {
+        Scanner::new()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// A `Compound` in a slice of binary NBT whose entries are only decoded when
+/// they are asked for.
+///
+/// Creating a `LazyCompound` walks the compound once, skipping over every
+/// payload without decoding or allocating for it, and remembers where each
+/// entry's payload lies in the input. An entry can then be decoded into a
+/// [`Value`](crate::Value) or any `T: Deserialize` on its own, which is much
+/// cheaper than decoding the whole compound when only a few of its entries
+/// are needed. Nested compounds can be indexed lazily in turn with
+/// [`get_lazy`](Self::get_lazy).
+///
+/// Entries are decoded with the recursion limit, memory quota and string
+/// handling of the deserializer the compound was indexed with, which are the
+/// defaults of [`Deserializer::new`] for [`from_slice`](Self::from_slice).
+/// Each entry decoded gets a fresh quota. When a key appears more than once,
+/// the last entry wins.
+pub struct LazyCompound<'de, E> {
+    input: &'de [u8],
+    name: Cow<'de, str>,
+    entries: Map<Cow<'de, str>, LazyEntry>,
+    /// The keys leading from the root tag to this compound.
+    path: Vec<Cow<'de, str>>,
+    depth: Depth,
+    quota: Quota,
+    backtraces: bool,
+    lossy_strings: bool,
+    endec: PhantomData<E>,
+}
+
+/// Where the payload of an entry lies in the input of a [`LazyCompound`].
+#[derive(Clone, Copy)]
+struct LazyEntry {
+    ty: Type,
+    start: usize,
+    end: usize,
+}
+
+impl<'de, E> LazyCompound<'de, E>
+where
+    E: Endec,
+{
+    /// Indexes the root tag of `slice`, which must be a `Compound`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input is not valid binary NBT, if the root tag
+    /// is not a `Compound`, or if bytes remain after it.
+    #[inline]
+    pub fn from_slice(slice: &'de [u8]) -> Result<Self> {
+        Self::from_deserializer(Deserializer::new(SliceRead::new(slice)))
+    }
+
+    /// Indexes the root tag that `de` is about to read, which must be a
+    /// `Compound`, keeping the settings of `de` to decode entries with.
+    ///
+    /// This is how a compound is indexed with [`Options`](crate::de::Options),
+    /// by passing it the result of
+    /// [`Options::deserializer`](crate::de::Options::deserializer). The
+    /// quota of `de` is charged for the names of the entries, and its root
+    /// name handling applies to the root tag.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input is not valid binary NBT, if the root tag
+    /// is not a `Compound`, or if bytes remain after it.
+    pub fn from_deserializer(mut de: Deserializer<SliceRead<'de>, E>) -> Result<Self> {
+        let _guard = BacktraceGuard::new(de.backtraces);
+        let depth = de.depth;
+        let quota = de.quota;
+        let (ty, offset, name) = de.parse_root(true)?;
+
+        if ty != Type::Compound {
+            let message = format!("expected root tag to be a Compound, found {ty}");
+            return Err(de.read.error(Category::InvalidData, message, offset));
+        }
+
+        let entries = Self::index(&mut de)?;
+        de.end()?;
+
+        Ok(LazyCompound {
+            input: de.read.slice(),
+            name,
+            entries,
+            path: Vec::new(),
+            depth,
+            quota,
+            backtraces: de.backtraces,
+            lossy_strings: de.lossy_strings,
+            endec: PhantomData,
+        })
+    }
+
+    /// Walks the entries of a compound whose payload starts at the current
+    /// position, up to and including its `End` tag.
+    fn index(de: &mut Deserializer<SliceRead<'de>, E>) -> Result<Map<Cow<'de, str>, LazyEntry>> {
+        let mut entries = Map::new();
+
+        loop {
+            let offset = de.read.byte_offset();
+            let id = de.read.next()?;
+
+            if id == 0 {
+                return Ok(entries);
+            }
+
+            let ty = de.parse_type(id, offset)?;
+            let name = de.parse_str()?;
+            #[allow(clippy::cast_possible_truncation)]
+            let start = de.read.byte_offset() as usize;
+            de.skip_payload(ty)
+                .map_err(|error| error.prepend_path(&*name))?;
+            #[allow(clippy::cast_possible_truncation)]
+            let end = de.read.byte_offset() as usize;
+
+            entries.insert(name, LazyEntry { ty, start, end });
+        }
+    }
+
+    /// Returns the name of the root tag, or the key of the entry this
+    /// compound was found under.
+    #[must_use]
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the number of entries in the compound.
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the compound has no entries.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns `true` if the compound has an entry for `key`.
+    #[must_use]
+    #[inline]
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    /// Returns an iterator over the keys of the compound, along with the
+    /// type of each entry.
+    pub fn keys(&self) -> impl Iterator<Item = (&str, Type)> + '_ {
+        self.entries.iter().map(|(key, entry)| (&**key, entry.ty))
+    }
+
+    /// Returns the type of the entry for `key`, or `None` if there is none.
+    #[must_use]
+    #[inline]
+    pub fn ty(&self, key: &str) -> Option<Type> {
+        self.entries.get(key).map(|entry| entry.ty)
+    }
+
+    /// Returns the encoded payload of the entry for `key`, or `None` if there
+    /// is none.
+    #[must_use]
+    #[inline]
+    pub fn raw(&self, key: &str) -> Option<&'de [u8]> {
+        self.entries
+            .get(key)
+            .map(|entry| &self.input[entry.start..entry.end])
+    }
+
+    /// Decodes the entry for `key` as a `T`, or returns `None` if there is
+    /// none.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the structure of the entry does not match the
+    /// structure expected by `T`.
+    pub fn get<T>(&self, key: &str) -> Result<Option<T>>
+    where
+        T: Deserialize<'de>,
+    {
+        let Some(&entry) = self.entries.get(key) else {
+            return Ok(None);
+        };
+
+        let mut de = self.deserializer(entry)?;
+        let _guard = BacktraceGuard::new(de.backtraces);
+        let payload = PayloadDeserializer::new(&mut de, entry.ty, entry.start as u64);
+
+        T::deserialize(payload)
+            .map(Some)
+            .map_err(|error| self.fix_path(error, key))
+    }
+
+    /// Decodes the entry for `key` into a [`Value`](crate::Value), or
+    /// returns `None` if there is none.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the entry cannot be decoded.
+    #[inline]
+    pub fn get_value(&self, key: &str) -> Result<Option<crate::Value>> {
+        self.get(key)
+    }
+
+    /// Indexes the entry for `key`, which must be a `Compound`, or returns
+    /// `None` if there is none.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the entry is not a `Compound`.
+    pub fn get_lazy(&self, key: &str) -> Result<Option<LazyCompound<'de, E>>> {
+        let Some((name, &entry)) = self.entries.get_key_value(key) else {
+            return Ok(None);
+        };
+
+        let mut de = self.deserializer(entry)?;
+
+        if entry.ty != Type::Compound {
+            let message = format!("expected a Compound, found {}", entry.ty);
+            let error = de
+                .read
+                .error(Category::InvalidData, message, entry.start as u64);
+            return Err(self.fix_path(error, key));
+        }
+
+        let entries = Self::index(&mut de).map_err(|error| self.fix_path(error, key))?;
+        let mut path = self.path.clone();
+        path.push(name.clone());
+
+        Ok(Some(LazyCompound {
+            input: self.input,
+            name: name.clone(),
+            entries,
+            path,
+            depth: self.depth,
+            quota: self.quota,
+            backtraces: self.backtraces,
+            lossy_strings: self.lossy_strings,
+            endec: PhantomData,
+        }))
+    }
+
+    /// Prefixes the path of `error` with the keys leading from the root tag
+    /// to the entry for `key`.
+    fn fix_path(&self, error: Error, key: &str) -> Error {
+        self.path
+            .iter()
+            .rev()
+            .fold(error.prepend_path(key), |error, parent| {
+                error.prepend_path(&**parent)
+            })
+    }
+
+    /// Creates a deserializer positioned at the start of an entry's payload
+    /// that cannot read past its end.
+    fn deserializer(&self, entry: LazyEntry) -> Result<Deserializer<SliceRead<'de>, E>> {
+        let mut de = Deserializer::new(SliceRead::new(&self.input[..entry.end]));
+        de.depth = self.depth;
+        de.quota = self.quota;
+        de.lossy_strings = self.lossy_strings;
+
+        if !self.backtraces {
+            de.disable_backtraces();
+        }
+
+        de.read.skip(entry.start)?;
+        Ok(de)
+    }
+}
+
+impl<E> Clone for LazyCompound<'_, E> {
+    fn clone(&self) -> Self {
+        LazyCompound {
+            input: self.input,
+            name: self.name.clone(),
+            entries: self.entries.clone(),
+            path: self.path.clone(),
+            depth: self.depth,
+            quota: self.quota,
+            backtraces: self.backtraces,
+            lossy_strings: self.lossy_strings,
+            endec: PhantomData,
+        }
+    }
+}
+
+impl<E> fmt::Debug for LazyCompound<'_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.entries.iter().map(|(key, entry)| (key, entry.ty)))
+            .finish()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// A pull parser that walks binary NBT one [`Event`] at a time.
+///
+/// Each named tag, starting with the root tag, is announced by an
+/// [`Event::Field`] and followed by the events of its payload: a single
+/// event for numbers and `String`s, or a start event, the events of each
+/// element or entry, and a matching [`Event::End`] for `List`s, `Compound`s
+/// and arrays. Array elements are yielded one by one, so the parser never
+/// holds more than a single name or string in memory, however large the
+/// input is.
+///
+/// [`skip`](Self::skip) steps over a payload or the rest of a container by
+/// walking its bytes without decoding or allocating for them.
+///
+/// A parser is created with [`Parser::new`] or [`Deserializer::into_parser`].
+/// It keeps the recursion limit, memory quota and string handling of the
+/// deserializer it was made from. Every name and string parsed is charged to
+/// the quota, so a long enough input uses it up even though the parser frees
+/// each one as it goes; use [`Parser::disable_quota`] for trusted input of
+/// unbounded length. After an error, the parser is left in an unspecified
+/// state and should be dropped.
+pub struct Parser<'de, R, E> {
+    de: Deserializer<R, E>,
+    /// The containers entered and not yet left, innermost last.
+    stack: Vec<Container>,
+    /// The type of the payload to be parsed next, after its header.
+    pending: Option<Type>,
+    started: bool,
+    lifetime: PhantomData<&'de ()>,
+}
+
+/// A container a [`Parser`] is inside of.
+enum Container {
+    Compound,
+    List { ty: Option<Type>, remaining: usize },
+    Array { ty: Type, remaining: usize },
+}
+
+/// A step through binary NBT, as yielded by a [`Parser`].
+///
+/// Names are not part of the events that start a payload, such as
+/// [`CompoundStart`](Self::CompoundStart), but come in a preceding
+/// [`Field`](Self::Field). The elements of a `List` are payloads without a
+/// name, so they start with the same events as named tags do, and a named tag
+/// of any type can be skipped with [`Parser::skip`] right after its `Field`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event<'de> {
+    /// The header of a named tag, i.e. the root tag or an entry of a
+    /// `Compound`, with its name and type. The events of its payload follow.
+    Field(Cow<'de, str>, Type),
+    /// A `Byte`, or an element of a `ByteArray`.
+    Byte(i8),
+    /// A `Short`.
+    Short(i16),
+    /// An `Int`, or an element of an `IntArray`.
+    Int(i32),
+    /// A `Long`, or an element of a `LongArray`.
+    Long(i64),
+    /// A `Float`.
+    Float(f32),
+    /// A `Double`.
+    Double(f64),
+    /// A `String`.
+    String(Cow<'de, str>),
+    /// The start of a `Compound`, followed by the [`Field`](Self::Field) and
+    /// payload of each entry.
+    ///
+    /// The name of a `Compound` that is the root tag or an entry is in the
+    /// `Field` just before this.
+    CompoundStart,
+    /// The start of a `List` with the given element type and length,
+    /// followed by the payload of each element.
+    ///
+    /// The element type is `None` for an empty list that declares `End` as
+    /// its element type.
+    ListStart(Option<Type>, usize),
+    /// The start of a `ByteArray`, `IntArray` or `LongArray` with the given
+    /// length, followed by an event for each element.
+    ArrayStart(Type, usize),
+    /// The end of the innermost `Compound`, `List` or array.
+    End,
+}
+
+impl<'de, R, E> Parser<'de, R, E>
+where
+    R: Read<'de>,
+    E: Endec,
+{
+    /// Creates a pull parser from one of the possible `serde_nbt` input
+    /// sources, with the same defaults as [`Deserializer::new`].
+    #[must_use]
+    #[inline]
+    pub fn new(read: R) -> Self {
+        Self::from_deserializer(Deserializer::new(read))
+    }
+
+    fn from_deserializer(de: Deserializer<R, E>) -> Self {
+        Parser {
+            de,
+            stack: Vec::new(),
+            pending: None,
+            started: false,
+            lifetime: PhantomData,
+        }
+    }
+
+    /// Lets the parser read names and strings of any length, however many it
+    /// has parsed already.
+    ///
+    /// Only do this for input from a trusted source; a few bytes of input can
+    /// declare a string of gigabytes.
+    #[inline]
+    pub fn disable_quota(&mut self) {
+        self.de.disable_quota();
+    }
+
+    /// Returns the offset of the next byte to be parsed, counted from the
+    /// start of the input.
+    #[must_use]
+    #[inline]
+    pub fn byte_offset(&self) -> u64 {
+        self.de.read.byte_offset()
+    }
+
+    /// Returns how many `List`s, `Compound`s and arrays the parser is inside
+    /// of.
+    #[must_use]
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Parses the next event, or returns `None` once the root tag has been
+    /// parsed completely.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input is not valid binary NBT, or if it nests
+    /// deeper than the recursion limit allows.
+    pub fn next_event(&mut self) -> Result<Option<Event<'de>>> {
+        let _guard = BacktraceGuard::new(self.de.backtraces);
+
+        if let Some(ty) = self.pending.take() {
+            return self.parse_payload(ty).map(Some);
+        }
+
+        let event = match self.stack.last_mut() {
+            None if self.started => return Ok(None),
+            None => {
+                let (ty, _, name) = self.de.parse_root(true)?;
+                self.started = true;
+                self.pending = Some(ty);
+                Event::Field(name, ty)
+            }
+            Some(Container::Compound) => {
+                let offset = self.de.read.byte_offset();
+                let id = self.de.read.next()?;
+
+                if id == 0 {
+                    self.leave();
+                    Event::End
+                } else {
+                    let ty = self.de.parse_type(id, offset)?;
+                    let name = self.de.parse_str()?;
+                    self.pending = Some(ty);
+                    Event::Field(name, ty)
+                }
+            }
+            Some(Container::List { ty, remaining }) => {
+                if let (Some(ty), Some(left)) = (*ty, remaining.checked_sub(1)) {
+                    *remaining = left;
+                    return self.parse_payload(ty).map(Some);
+                }
+
+                self.leave();
+                Event::End
+            }
+            Some(Container::Array { ty, remaining }) => {
+                let Some(left) = remaining.checked_sub(1) else {
+                    self.stack.pop();
+                    return Ok(Some(Event::End));
+                };
+
+                *remaining = left;
+
+                match ty {
+                    #[allow(clippy::cast_possible_wrap)]
+                    Type::ByteArray => Event::Byte(self.de.read.next()? as i8),
+                    Type::IntArray => Event::Int(E::read_i32(&mut self.de.read)?),
+                    _ => Event::Long(E::read_i64(&mut self.de.read)?),
+                }
+            }
+        };
+
+        Ok(Some(event))
+    }
+
+    /// Skips the subtree the parser is at without decoding it.
+    ///
+    /// Right after an [`Event::Field`], this skips the payload of that tag,
+    /// so the next event belongs to whatever follows it. Anywhere else, it
+    /// skips the rest of the innermost `List`, `Compound` or array, including
+    /// its [`Event::End`]. Before the first event, it skips the whole root
+    /// tag.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the skipped bytes are not valid binary NBT, or if
+    /// they nest deeper than the recursion limit allows.
+    pub fn skip(&mut self) -> Result<()> {
+        let _guard = BacktraceGuard::new(self.de.backtraces);
+
+        if let Some(ty) = self.pending.take() {
+            return self.de.skip_payload(ty);
+        }
+
+        match self.stack.last_mut() {
+            None if self.started => Ok(()),
+            None => {
+                let (ty, _, _) = self.de.parse_root(false)?;
+                self.started = true;
+                self.de.skip_payload(ty)
+            }
+            Some(Container::Compound) => {
+                self.de.skip_nested(Type::Compound)?;
+                self.leave();
+                Ok(())
+            }
+            Some(Container::List { ty, remaining }) => {
+                if let Some(ty) = *ty {
+                    while *remaining > 0 {
+                        self.de.skip_payload(ty)?;
+                        *remaining -= 1;
+                    }
+                }
+
+                self.leave();
+                Ok(())
+            }
+            Some(Container::Array { ty, remaining }) => {
+                let len = *remaining;
+
+                match ty {
+                    Type::ByteArray => self.de.read.skip(len)?,
+                    Type::IntArray => E::skip_i32s(&mut self.de.read, len)?,
+                    _ => E::skip_i64s(&mut self.de.read, len)?,
+                }
+
+                self.stack.pop();
+                Ok(())
+            }
+        }
+    }
+
+    /// Validates that the input has been fully consumed, like
+    /// [`Deserializer::end`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of [`Category::InvalidInput`] if any bytes remain in
+    /// the input.
+    #[inline]
+    pub fn end(&mut self) -> Result<()> {
+        self.de.end()
+    }
+
+    /// Parses the payload of a tag of the given type.
+    fn parse_payload(&mut self, ty: Type) -> Result<Event<'de>> {
+        let read = &mut self.de.read;
+
+        let event = match ty {
+            #[allow(clippy::cast_possible_wrap)]
+            Type::Byte => Event::Byte(read.next()? as i8),
+            Type::Short => Event::Short(E::read_i16(read)?),
+            Type::Int => Event::Int(E::read_i32(read)?),
+            Type::Long => Event::Long(E::read_i64(read)?),
+            Type::Float => Event::Float(E::read_f32(read)?),
+            Type::Double => Event::Double(E::read_f64(read)?),
+            Type::String => Event::String(self.de.parse_str()?),
+            Type::Compound => {
+                let offset = self.de.read.byte_offset();
+                self.de.enter(offset)?;
+                self.stack.push(Container::Compound);
+                Event::CompoundStart
+            }
+            Type::List => {
+                let offset = self.de.read.byte_offset();
+                self.de.enter(offset)?;
+                let (ty, len) = self.de.parse_list_header()?;
+                self.stack.push(Container::List { ty, remaining: len });
+                Event::ListStart(ty, len)
+            }
+            Type::ByteArray | Type::IntArray | Type::LongArray => {
+                let len = self.de.parse_len()?;
+                self.stack.push(Container::Array { ty, remaining: len });
+                Event::ArrayStart(ty, len)
+            }
+        };
+
+        Ok(event)
+    }
+
+    /// Leaves the innermost `List` or `Compound`.
+    fn leave(&mut self) {
+        self.stack.pop();
+        self.de.depth.leave();
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Hands a [`Named`](crate::Named) the name of the root tag followed by the
+/// root tag itself.
+struct NamedAccess<'de, 'a, R, E> {
+    name: Option<Cow<'de, str>>,
+    payload: Option<PayloadDeserializer<'a, R, E>>,
+}
+
+impl<'de, R, E> de::SeqAccess<'de> for NamedAccess<'de, '_, R, E>
+where
+    R: Read<'de>,
+    E: Endec,
+{
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        if let Some(name) = self.name.take() {
+            return match name {
+                Cow::Borrowed(name) => seed.deserialize(BorrowedStrDeserializer::new(name)),
+                Cow::Owned(name) => seed.deserialize(name.into_deserializer()),
+            }
+            .map(Some);
+        }
+
+        match self.payload.take() {
+            Some(payload) => seed.deserialize(payload).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        Some(usize::from(self.name.is_some()) + usize::from(self.payload.is_some()))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Deserializes the payload of a single tag whose type is already known.
+struct PayloadDeserializer<'a, R, E> {
+    de: &'a mut Deserializer<R, E>,
+    ty: Type,
+    /// The offset of the tag, including its header if it has one.
+    offset: u64,
+}
+
+impl<'de, 'a, R, E> PayloadDeserializer<'a, R, E>
+where
+    R: Read<'de>,
+    E: Endec,
+{
+    #[inline]
+    fn new(de: &'a mut Deserializer<R, E>, ty: Type, offset: u64) -> Self {
+        PayloadDeserializer { de, ty, offset }
+    }
+
+    #[cold]
+    fn fix_position(&self, error: Error) -> Error {
+        self.de.read.fix_position(error, self.offset)
+    }
+
+    fn deserialize_list<V>(mut self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.de.enter(self.offset)?;
+        let result = self.visit_list(visitor);
+        self.de.depth.leave();
+        result
+    }
+
+    fn visit_list<V>(&mut self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let (ty, len) = self.de.parse_list_header()?;
+        self.de
+            .charge(self.offset, |quota| quota.charge_list(len))?;
+
+        let mut access = ListAccess {
+            de: &mut *self.de,
+            ty,
+            index: 0,
+            remaining: len,
+        };
+
+        let value = visitor.visit_seq(&mut access)?;
+
+        if access.remaining == 0 {
+            Ok(value)
+        } else {
+            let error: Error = de::Error::invalid_length(len, &"fewer elements in list");
+            Err(error.with_detail(Detail::InvalidLength {
+                declared: len,
+                actual: len - access.remaining,
+            }))
+        }
+    }
+
+    fn deserialize_compound<V>(mut self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.de.enter(self.offset)?;
+        let result = self.visit_compound(visitor);
+        self.de.depth.leave();
+        result
+    }
+
+    fn visit_compound<V>(&mut self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.de
+            .charge(self.offset, |quota| quota.charge(Quota::COMPOUND))?;
+
+        let mut access = CompoundAccess {
+            de: &mut *self.de,
+            pending: None,
+            done: false,
+        };
+
+        let value = visitor.visit_map(&mut access)?;
+
+        // Drain whatever entries the visitor chose not to look at so the
+        // input stays in sync.
+        if !access.done {
+            if let Some((ty, ..)) = access.pending.take() {
+                self.de.skip_payload(ty)?;
+            }
+
+            self.de.skip_nested(Type::Compound)?;
+        }
+
+        Ok(value)
+    }
+
+    fn deserialize_variant<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.ty {
+            Type::String => visitor.visit_enum(self.de.parse_str()?.into_deserializer()),
+            Type::Compound => {
+                self.de.enter(self.offset)?;
+                self.de
+                    .charge(self.offset, |quota| quota.charge(Quota::COMPOUND))?;
+                let result = visitor.visit_enum(VariantAccess { de: &mut *self.de });
+                self.de.depth.leave();
+                result
+            }
+            _ => {
+                let unexp = Unexpected::Other(self.ty.as_str());
+                Err(de::Error::invalid_type(unexp, &"a String or Compound"))
+            }
+        }
+    }
+}
+
+macro_rules! deserialize_payload {
+    ($($method:ident$(($($arg:ident: $ty:ty),*))? => $deserialize:ident,)*) => {
+        $(
+            #[inline]
+            fn $method<V>(self, $($(_: $ty,)*)? visitor: V) -> Result<V::Value>
+            where
+                V: Visitor<'de>,
+            {
+                self.$deserialize(visitor)
+            }
+        )*
+    };
+}
+
+/// Forwards to `deserialize_any`, recording the type of tag that `$method`
+/// corresponds to should the visitor reject the tag's type.
+///
+/// Unsigned integers correspond to the next widest signed type, as that is
+/// how they are serialized.
+macro_rules! deserialize_expecting {
+    ($($method:ident$(($($arg:ident: $ty:ty),*))? => $expected:ident,)*) => {
+        $(
+            #[inline]
+            fn $method<V>(self, $($(_: $ty,)*)? visitor: V) -> Result<V::Value>
+            where
+                V: Visitor<'de>,
+            {
+                self.deserialize_any(visitor)
+                    .map_err(|error| error.with_expected_type(Type::$expected))
+            }
+        )*
+    };
+}
+
+impl<'de, R, E> de::Deserializer<'de> for PayloadDeserializer<'_, R, E>
+where
+    R: Read<'de>,
+    E: Endec,
+{
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let offset = self.offset;
+        let ty = self.ty;
+        let de = self.de;
+
+        let result = match ty {
+            #[allow(clippy::cast_possible_wrap)]
+            Type::Byte => visitor.visit_i8(de.read.next()? as i8),
+            Type::Short => visitor.visit_i16(E::read_i16(&mut de.read)?),
+            Type::Int => visitor.visit_i32(E::read_i32(&mut de.read)?),
+            Type::Long => visitor.visit_i64(E::read_i64(&mut de.read)?),
+            Type::Float => visitor.visit_f32(E::read_f32(&mut de.read)?),
+            Type::Double => visitor.visit_f64(E::read_f64(&mut de.read)?),
+            Type::String => match de.parse_str()? {
+                Cow::Borrowed(s) => visitor.visit_borrowed_str(s),
+                Cow::Owned(s) => visitor.visit_string(s),
+            },
+            Type::List => {
+                PayloadDeserializer::new(de, Type::List, offset).deserialize_list(visitor)
+            }
+            Type::Compound => {
+                PayloadDeserializer::new(de, Type::Compound, offset).deserialize_compound(visitor)
+            }
+            ty @ (Type::ByteArray | Type::IntArray | Type::LongArray) => {
+                visitor.visit_map(ArrayAccess {
+                    de: &mut *de,
+                    ty,
+                    offset,
+                    state: ArrayState::Key,
+                })
+            }
+        };
+
+        result.map_err(|error| de.read.fix_position(error.with_found_type(ty), offset))
+    }
+
+    #[inline]
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if self.ty == Type::Byte {
+            let byte = self.de.read.next()?;
+            visitor
+                .visit_bool(byte != 0)
+                .map_err(|error: Error| self.fix_position(error.with_found_type(Type::Byte)))
+        } else {
+            self.deserialize_any(visitor)
+                .map_err(|error| error.with_expected_type(Type::Byte))
+        }
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if self.ty == Type::ByteArray {
+            ArrayDeserializer::new(self.de, self.ty, self.offset).deserialize_bytes(visitor)
+        } else {
+            self.deserialize_any(visitor)
+                .map_err(|error| error.with_expected_type(Type::ByteArray))
+        }
+    }
+
+    #[inline]
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_bytes(visitor)
+    }
+
+    #[inline]
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match (name, self.ty) {
+            (INT_ARRAY_REF_TOKEN, Type::IntArray) | (LONG_ARRAY_REF_TOKEN, Type::LongArray) => {
+                ArrayDeserializer::new(self.de, self.ty, self.offset)
+                    .deserialize_newtype_struct(name, visitor)
+            }
+            (RAW_NBT_TOKEN, _) => {
+                let bytes = self.de.read_payload(self.ty)?;
+                let access = RawAccess {
+                    variant: self.ty.id(),
+                    bytes,
+                };
+
+                visitor
+                    .visit_enum(access)
+                    .map_err(|error| self.fix_position(error))
+            }
+            _ => visitor.visit_newtype_struct(self),
+        }
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.ty {
+            Type::ByteArray | Type::IntArray | Type::LongArray => {
+                ArrayDeserializer::new(self.de, self.ty, self.offset).deserialize_any(visitor)
+            }
+            _ => self
+                .deserialize_any(visitor)
+                .map_err(|error| error.with_expected_type(Type::List)),
+        }
+    }
+
+    deserialize_payload! {
+        deserialize_tuple(len: usize) => deserialize_seq,
+        deserialize_tuple_struct(name: &'static str, len: usize) => deserialize_seq,
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let offset = self.offset;
+        let ty = self.ty;
+        let de = &mut *self.de;
+
+        PayloadDeserializer::new(de, ty, offset)
+            .deserialize_variant(visitor)
+            .map_err(|error| de.read.fix_position(error.with_found_type(ty), offset))
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.de.skip_payload(self.ty)?;
+        visitor.visit_unit()
+    }
+
+    deserialize_expecting! {
+        deserialize_i8 => Byte,
+        deserialize_i16 => Short,
+        deserialize_i32 => Int,
+        deserialize_i64 => Long,
+        deserialize_u8 => Short,
+        deserialize_u16 => Int,
+        deserialize_u32 => Long,
+        deserialize_u64 => Long,
+        deserialize_f32 => Float,
+        deserialize_f64 => Double,
+        deserialize_char => String,
+        deserialize_str => String,
+        deserialize_string => String,
+        deserialize_map => Compound,
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]) => Compound,
+        deserialize_identifier => String,
+    }
+
+    serde::forward_to_deserialize_any! {
+        i128 u128 unit unit_struct
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+struct ListAccess<'a, R, E> {
+    de: &'a mut Deserializer<R, E>,
+    ty: Option<Type>,
+    /// The index of the next element, used to build error paths.
+    index: usize,
+    remaining: usize,
+}
+
+impl<'de, R, E> de::SeqAccess<'de> for ListAccess<'_, R, E>
+where
+    R: Read<'de>,
+    E: Endec,
+{
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let Some(ty) = self.ty.filter(|_| self.remaining > 0) else {
+            return Ok(None);
+        };
+        self.remaining -= 1;
+
+        let index = self.index;
+        self.index += 1;
+
+        let offset = self.de.read.byte_offset();
+        let de = PayloadDeserializer::new(&mut *self.de, ty, offset);
+        seed.deserialize(de)
+            .map(Some)
+            .map_err(|error| error.prepend_path(index))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        match self.de.read.remaining() {
+            Some(bytes) => Some(self.remaining.min(bytes)),
+            None => Some(self.remaining),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+struct CompoundAccess<'de, 'a, R, E> {
+    de: &'a mut Deserializer<R, E>,
+    /// The type, offset and name of the entry whose name was just
+    /// deserialized.
+    pending: Option<(Type, u64, Cow<'de, str>)>,
+    done: bool,
+}
+
+impl<'de, R, E> de::MapAccess<'de> for CompoundAccess<'de, '_, R, E>
+where
+    R: Read<'de>,
+    E: Endec,
+{
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        if self.done {
+            return Ok(None);
+        }
+
+        let offset = self.de.read.byte_offset();
+        let id = self.de.read.next()?;
+
+        if id == 0 {
+            self.done = true;
+            return Ok(None);
+        }
+
+        let ty = self.de.parse_type(id, offset)?;
+        self.de.charge(offset, |quota| quota.charge(Quota::ENTRY))?;
+        let name = self.de.parse_str()?;
+
+        let key = seed
+            .deserialize(KeyDeserializer::new(Reference::from(&name)))
+            .map_err(|error| {
+                let error = error.with_found_type(Type::String);
+                self.de.read.fix_position(error, offset)
+            })?;
+
+        self.pending = Some((ty, offset, name));
+        Ok(Some(key))
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let (ty, offset, name) = self
+            .pending
+            .take()
+            .expect("`next_value_seed` called before `next_key_seed`");
+
+        seed.deserialize(PayloadDeserializer::new(&mut *self.de, ty, offset))
+            .map_err(|error| error.prepend_path(&*name))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+enum ArrayState {
+    Key,
+    Value,
+    Done,
+}
+
+/// Exposes an array as a single-entry map keyed by one of the private array
+/// tokens so that self-describing consumers can tell it apart from a `List`.
+struct ArrayAccess<'a, R, E> {
+    de: &'a mut Deserializer<R, E>,
+    ty: Type,
+    offset: u64,
+    state: ArrayState,
+}
+
+impl<'de, R, E> de::MapAccess<'de> for ArrayAccess<'_, R, E>
+where
+    R: Read<'de>,
+    E: Endec,
+{
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let ArrayState::Key = self.state else {
+            return Ok(None);
+        };
+        self.state = ArrayState::Value;
+
+        let token = match self.ty {
+            Type::ByteArray => BYTE_ARRAY_TOKEN,
+            Type::IntArray => INT_ARRAY_TOKEN,
+            Type::LongArray => LONG_ARRAY_TOKEN,
+            _ => unreachable!(),
+        };
+
+        seed.deserialize(BorrowedStrDeserializer::new(token))
+            .map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        self.state = ArrayState::Done;
+        seed.deserialize(ArrayDeserializer::new(&mut *self.de, self.ty, self.offset))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        Some(1)
+    }
+}
+
+/// Deserializes the payload of one of the array types as a sequence.
+struct ArrayDeserializer<'a, R, E> {
+    de: &'a mut Deserializer<R, E>,
+    ty: Type,
+    offset: u64,
+}
+
+impl<'de, 'a, R, E> ArrayDeserializer<'a, R, E>
+where
+    R: Read<'de>,
+    E: Endec,
+{
+    #[inline]
+    fn new(de: &'a mut Deserializer<R, E>, ty: Type, offset: u64) -> Self {
+        ArrayDeserializer { de, ty, offset }
+    }
+}
+
+impl<'de, R, E> de::Deserializer<'de> for ArrayDeserializer<'_, R, E>
+where
+    R: Read<'de>,
+    E: Endec,
+{
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let offset = self.offset;
+        let len = self.de.parse_len()?;
+        let (ty, width) = match self.ty {
+            Type::ByteArray => (Type::Byte, 1),
+            Type::IntArray => (Type::Int, 4),
+            Type::LongArray => (Type::Long, 8),
+            _ => unreachable!(),
+        };
+
+        self.de
+            .charge(offset, |quota| quota.charge_array(len, width))?;
+
+        let mut access = ListAccess {
+            de: &mut *self.de,
+            ty: Some(ty),
+            index: 0,
+            remaining: len,
+        };
+
+        let value = visitor
+            .visit_seq(&mut access)
+            .map_err(|error| access.de.read.fix_position(error, offset))?;
+
+        if access.remaining == 0 {
+            Ok(value)
+        } else {
+            let error: Error = de::Error::invalid_length(len, &"fewer elements in array");
+            let error = error.with_detail(Detail::InvalidLength {
+                declared: len,
+                actual: len - access.remaining,
+            });
+            Err(self.de.read.fix_position(error, offset))
+        }
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if self.ty != Type::ByteArray {
+            return self.deserialize_any(visitor);
+        }
+
+        let offset = self.offset;
+        let len = self.de.parse_len()?;
+        self.de.charge(offset, |quota| quota.charge_array(len, 1))?;
+        let bytes = self.de.read.read_slice(len)?;
+
+        let result = match bytes {
+            Cow::Borrowed(bytes) => visitor.visit_borrowed_bytes(bytes),
+            Cow::Owned(bytes) => visitor.visit_byte_buf(bytes),
+        };
+
+        result.map_err(|error| self.de.read.fix_position(error, offset))
+    }
+
+    #[inline]
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_bytes(visitor)
+    }
+
+    /// Hands the undecoded payload of an `IntArray` or `LongArray` over to
+    /// [`IntArrayRef`](crate::IntArrayRef) or
+    /// [`LongArrayRef`](crate::LongArrayRef) when the dialect stores its
+    /// elements at a fixed width.
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let width = match (name, self.ty) {
+            (INT_ARRAY_REF_TOKEN, Type::IntArray) => 4,
+            (LONG_ARRAY_REF_TOKEN, Type::LongArray) => 8,
+            _ => return visitor.visit_newtype_struct(self),
+        };
+
+        let Some(byte_order) = E::array_byte_order() else {
+            return visitor.visit_newtype_struct(self);
+        };
+
+        let offset = self.offset;
+        let len = self.de.parse_len()?;
+        self.de
+            .charge(offset, |quota| quota.charge_array(len, width))?;
+        let bytes = self.de.read.read_slice(len.saturating_mul(width))?;
+
+        let access = RawAccess {
+            variant: byte_order == ByteOrder::Big,
+            bytes,
+        };
+
+        visitor
+            .visit_enum(access)
+            .map_err(|error| self.de.read.fix_position(error, offset))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        option unit unit_struct seq tuple tuple_struct map struct enum
+        identifier ignored_any
+    }
+}
+
+/// Hands the encoded payload of a tag to a visitor as an enum, whose variant
+/// identifies how to interpret the bytes that make up its content.
+///
+/// The variant is whether the elements are big-endian for the payload of an
+/// `IntArray` or `LongArray`, and the tag's ID for a
+/// [`RawNbt`](crate::binary::RawNbt).
+struct RawAccess<'de, T> {
+    variant: T,
+    bytes: Cow<'de, [u8]>,
+}
+
+impl<'de, T> de::EnumAccess<'de> for RawAccess<'de, T>
+where
+    T: IntoDeserializer<'de, Error>,
+{
+    type Error = Error;
+    type Variant = RawContent<'de>;
+
+    #[inline]
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant)>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(self.variant.into_deserializer())?;
+        Ok((variant, RawContent { bytes: self.bytes }))
+    }
+}
+
+/// The content of a [`RawAccess`].
+struct RawContent<'de> {
+    bytes: Cow<'de, [u8]>,
+}
+
+impl<'de> de::VariantAccess<'de> for RawContent<'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        Err(de::Error::invalid_type(
+            Unexpected::NewtypeVariant,
+            &"unit variant",
+        ))
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.bytes {
+            Cow::Borrowed(bytes) => seed.deserialize(BorrowedBytesDeserializer::new(bytes)),
+            Cow::Owned(bytes) => seed.deserialize(BytesDeserializer::new(&bytes)),
+        }
+    }
+
+    fn tuple_variant<V>(self, _len: usize, _visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        Err(de::Error::invalid_type(
+            Unexpected::NewtypeVariant,
+            &"tuple variant",
+        ))
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], _visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        Err(de::Error::invalid_type(
+            Unexpected::NewtypeVariant,
+            &"struct variant",
+        ))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Deserializes an enum variant from a single-entry compound, where the name
+/// of the entry selects the variant and its payload holds the variant's data.
+struct VariantAccess<'a, R, E> {
+    de: &'a mut Deserializer<R, E>,
+}
+
+impl<'de, 'a, R, E> de::EnumAccess<'de> for VariantAccess<'a, R, E>
+where
+    R: Read<'de>,
+    E: Endec,
+{
+    type Error = Error;
+    type Variant = VariantPayload<'de, 'a, R, E>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant)>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let offset = self.de.read.byte_offset();
+        let id = self.de.read.next()?;
+        let ty = self.de.parse_type(id, offset)?;
+        self.de.charge(offset, |quota| quota.charge(Quota::ENTRY))?;
+        let name = self.de.parse_str()?;
+
+        let value = seed
+            .deserialize(KeyDeserializer::new(Reference::from(&name)))
+            .map_err(|error| self.de.read.fix_position(error, offset))?;
+
+        let variant = VariantPayload {
+            de: self.de,
+            ty,
+            offset,
+            name,
+        };
+
+        Ok((value, variant))
+    }
+}
+
+struct VariantPayload<'de, 'a, R, E> {
+    de: &'a mut Deserializer<R, E>,
+    ty: Type,
+    offset: u64,
+    name: Cow<'de, str>,
+}
+
+impl<'de, R, E> VariantPayload<'de, '_, R, E>
+where
+    R: Read<'de>,
+    E: Endec,
+{
+    /// Deserializes the payload of the variant with `f`, then expects the
+    /// enclosing compound to end.
+    fn payload<F, T>(self, f: F) -> Result<T>
+    where
+        F: FnOnce(PayloadDeserializer<'_, R, E>) -> Result<T>,
+    {
+        let value = f(PayloadDeserializer::new(
+            &mut *self.de,
+            self.ty,
+            self.offset,
+        ))
+        .map_err(|error| error.prepend_path(&*self.name))?;
+
+        let offset = self.de.read.byte_offset();
+
+        if self.de.read.next()? == 0 {
+            Ok(value)
+        } else {
+            let message = "expected enum variant to be the only entry in its compound";
+            Err(self.de.read.error(Category::InvalidData, message, offset))
+        }
+    }
+}
+
+impl<'de, R, E> de::VariantAccess<'de> for VariantPayload<'de, '_, R, E>
+where
+    R: Read<'de>,
+    E: Endec,
+{
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        self.payload(|de| de::IgnoredAny::deserialize(de).map(drop))
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.payload(|de| seed.deserialize(de))
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.payload(|de| de::Deserializer::deserialize_seq(de, visitor))
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.payload(|de| de::Deserializer::deserialize_map(de, visitor))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Wraps an input source and keeps a copy of every byte read through it.
+///
+/// Every byte is charged against the memory quota before it is read, so that
+/// a payload too large to keep fails before it has all been copied.
+struct Recorder<'a, R> {
+    inner: &'a mut R,
+    bytes: Vec<u8>,
+    quota: Quota,
+}
+
+impl<'de, R> Recorder<'_, R>
+where
+    R: Read<'de>,
+{
+    /// Charges for `len` more bytes of the recording.
+    #[inline]
+    fn charge(&mut self, len: usize) -> Result<()> {
+        self.quota
+            .charge(len)
+            .map_err(|error| self.inner.fix_position(error, self.inner.byte_offset()))
+    }
+}
+
+impl<'de, R> Read<'de> for Recorder<'_, R>
+where
+    R: Read<'de>,
+{
+    #[inline]
+    fn next(&mut self) -> Result<u8> {
+        self.charge(1)?;
+        let byte = self.inner.next()?;
+        self.bytes.push(byte);
+        Ok(byte)
+    }
+
+    #[inline]
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        self.charge(N)?;
+        let array = self.inner.read_array()?;
+        self.bytes.extend_from_slice(&array);
+        Ok(array)
+    }
+
+    #[inline]
+    fn read_slice(&mut self, len: usize) -> Result<Cow<'de, [u8]>> {
+        self.charge(len)?;
+        let bytes = self.inner.read_slice(len)?;
+        self.bytes.extend_from_slice(&bytes);
+        Ok(bytes)
+    }
+
+    #[inline]
+    fn skip(&mut self, len: usize) -> Result<()> {
+        self.read_slice(len).map(drop)
+    }
+
+    #[inline]
+    fn remaining(&self) -> Option<usize> {
+        self.inner.remaining()
+    }
+
+    #[inline]
+    fn byte_offset(&self) -> u64 {
+        self.inner.byte_offset()
+    }
+
+    #[inline]
+    fn fix_position(&self, error: Error, offset: u64) -> Error {
+        self.inner.fix_position(error, offset)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(all(test, feature = "be"))]
+mod tests {
+    use alloc::{
+        borrow::Cow,
+        collections::BTreeMap,
+        string::{String, ToString},
+        vec,
+        vec::Vec,
+    };
+
+    use serde::{de::IgnoredAny, Deserialize, Serialize};
+
+    use super::{Deserializer, Event, LazyCompound, Progress, Scanner};
+    use crate::{
+        binary::BigEndian,
+        de::read::{IoRead, Read as _, SliceRead},
+        error::Category,
+        ser::to_be_vec,
+        Type, Value,
+    };
+
+    /// A root `Compound` holding an `Int` named `x` that is cut off after its
+    /// first byte.
+    const TRUNCATED: &[u8] = &[10, 0, 0, 3, 0, 1, b'x', 0];
+
+    #[test]
+    fn slice_errors_carry_a_hexdump() {
+        let mut de = Deserializer::<_, BigEndian>::new(SliceRead::new(TRUNCATED));
+        let error = IgnoredAny::deserialize(&mut de).unwrap_err();
+
+        assert_eq!(error.byte_offset(), Some(8));
+        assert!(error.hexdump().is_some());
+    }
+
+    #[test]
+    fn disabling_backtraces_skips_the_hexdump() {
+        let mut de = Deserializer::<_, BigEndian>::new(SliceRead::new(TRUNCATED));
+        de.disable_backtraces();
+        let error = IgnoredAny::deserialize(&mut de).unwrap_err();
+
+        assert_eq!(error.byte_offset(), Some(8));
+        assert!(error.hexdump().is_none());
+    }
+
+    /// Two root `Compound`s, each holding a `String` named `s` with three
+    /// bytes.
+    const STREAM: &[u8] = &[
+        10, 0, 0, 8, 0, 1, b's', 0, 3, b'a', b'b', b'c', 0, //
+        10, 0, 0, 8, 0, 1, b's', 0, 3, b'd', b'e', b'f', 0,
+    ];
+
+    #[test]
+    fn stream_tracks_the_offset_of_each_root() {
+        let de = Deserializer::<_, BigEndian>::new(SliceRead::new(STREAM));
+        let mut stream = de.into_iter::<IgnoredAny>();
+
+        assert_eq!(stream.byte_offset(), 0);
+        stream.next().unwrap().unwrap();
+        assert_eq!(stream.byte_offset(), 13);
+        stream.next().unwrap().unwrap();
+        assert_eq!(stream.byte_offset(), 26);
+        assert!(stream.next().is_none());
+    }
+
+    #[test]
+    fn stream_from_a_reader_ends_after_the_last_root() {
+        let de = Deserializer::<_, BigEndian>::new(IoRead::new(STREAM));
+        let roots = de
+            .into_iter::<Root>()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(roots.len(), 2);
+        assert_eq!(roots[1].s, "def");
+    }
+
+    #[test]
+    fn stream_stops_at_a_truncated_root() {
+        let de = Deserializer::<_, BigEndian>::new(SliceRead::new(&STREAM[..20]));
+        let mut stream = de.into_iter::<IgnoredAny>();
+
+        stream.next().unwrap().unwrap();
+        assert!(stream.next().unwrap().unwrap_err().is_eof());
+        assert!(stream.next().is_none());
+        assert_eq!(stream.byte_offset(), 13);
+    }
+
+    #[test]
+    fn parser_skips_the_payload_after_a_field() {
+        let de = Deserializer::<_, BigEndian>::new(SliceRead::new(&STREAM[..13]));
+        let mut parser = de.into_parser();
+
+        assert_eq!(
+            parser.next_event().unwrap(),
+            Some(Event::Field("".into(), Type::Compound))
+        );
+        assert_eq!(parser.next_event().unwrap(), Some(Event::CompoundStart));
+        assert_eq!(
+            parser.next_event().unwrap(),
+            Some(Event::Field("s".into(), Type::String))
+        );
+        parser.skip().unwrap();
+        assert_eq!(parser.next_event().unwrap(), Some(Event::End));
+        assert_eq!(parser.next_event().unwrap(), None);
+    }
+
+    #[test]
+    fn parser_skips_the_rest_of_a_container() {
+        let de = Deserializer::<_, BigEndian>::new(SliceRead::new(&STREAM[..13]));
+        let mut parser = de.into_parser();
+
+        parser.next_event().unwrap();
+        parser.next_event().unwrap();
+        assert_eq!(parser.depth(), 1);
+        parser.skip().unwrap();
+        assert_eq!(parser.depth(), 0);
+        assert_eq!(parser.next_event().unwrap(), None);
+    }
+
+    #[test]
+    fn parser_skips_a_whole_io_stream() {
+        let de = Deserializer::<_, BigEndian>::new(IoRead::new(STREAM));
+        let mut parser = de.into_parser();
+
+        parser.skip().unwrap();
+        assert_eq!(parser.next_event().unwrap(), None);
+    }
+
+    /// A root `String` that declares the longest length there is, but holds
+    /// nothing.
+    const HUGE_STRING: &[u8] = &[8, 0, 0, 0xFF, 0xFF];
+
+    #[test]
+    fn parser_keeps_the_quota_of_its_deserializer() {
+        let mut de = Deserializer::<_, BigEndian>::new(IoRead::new(HUGE_STRING));
+        de.set_quota(1000);
+        let mut parser = de.into_parser();
+
+        parser.next_event().unwrap();
+        let error = parser.next_event().unwrap_err();
+        assert_eq!(error.category(), Category::QuotaExceeded);
+        assert_eq!(error.byte_offset(), Some(3));
+
+        let mut de = Deserializer::<_, BigEndian>::new(IoRead::new(HUGE_STRING));
+        de.set_quota(1000);
+        let mut parser = de.into_parser();
+        parser.disable_quota();
+
+        parser.next_event().unwrap();
+        assert!(parser.next_event().unwrap_err().is_eof());
+    }
+
+    /// A root `Compound` with `depth - 1` more `Compound`s nested inside it.
+    fn nested(depth: usize) -> Vec<u8> {
+        let mut bytes = vec![10, 0, 0];
+        for _ in 1..depth {
+            bytes.extend_from_slice(&[10, 0, 1, b'a']);
+        }
+        bytes.resize(bytes.len() + depth, 0);
+        bytes
+    }
+
+    #[test]
+    fn recursion_limit_is_enforced() {
+        let input = nested(3);
+
+        let mut de = Deserializer::<_, BigEndian>::new(SliceRead::new(&input));
+        de.set_recursion_limit(3);
+        IgnoredAny::deserialize(&mut de).unwrap();
+
+        let mut de = Deserializer::<_, BigEndian>::new(SliceRead::new(&input));
+        de.set_recursion_limit(2);
+        let error = IgnoredAny::deserialize(&mut de).unwrap_err();
+        assert_eq!(error.category(), Category::RecursionLimitExceeded);
+        assert_eq!(error.byte_offset(), Some(11));
+    }
+
+    #[test]
+    fn recursion_limit_can_be_disabled() {
+        let input = nested(600);
+
+        let mut de = Deserializer::<_, BigEndian>::new(SliceRead::new(&input));
+        de.disable_recursion_limit();
+        IgnoredAny::deserialize(&mut de).unwrap();
+
+        let mut de = Deserializer::<_, BigEndian>::new(SliceRead::new(&input));
+        let error = IgnoredAny::deserialize(&mut de).unwrap_err();
+        assert_eq!(error.category(), Category::RecursionLimitExceeded);
+    }
+
+    /// A root `IntArray` that declares `i32::MAX` elements but has none.
+    const HUGE_ARRAY: &[u8] = &[11, 0, 0, 0x7F, 0xFF, 0xFF, 0xFF];
+
+    #[test]
+    fn huge_lengths_fail_before_allocating_from_a_slice() {
+        let mut de = Deserializer::<_, BigEndian>::new(SliceRead::new(HUGE_ARRAY));
+        de.disable_quota();
+        let error = Vec::<i32>::deserialize(&mut de).unwrap_err();
+
+        assert_eq!(error.category(), Category::UnexpectedEof);
+    }
+
+    #[test]
+    fn huge_lengths_fail_before_allocating_from_a_reader() {
+        let mut de = Deserializer::<_, BigEndian>::new(IoRead::new(HUGE_ARRAY));
+        de.disable_quota();
+        let error = Vec::<i32>::deserialize(&mut de).unwrap_err();
+
+        assert_eq!(error.category(), Category::UnexpectedEof);
+    }
+
+    #[test]
+    fn huge_lengths_exceed_the_quota() {
+        let mut de = Deserializer::<_, BigEndian>::new(IoRead::new(HUGE_ARRAY));
+        let error = Vec::<i32>::deserialize(&mut de).unwrap_err();
+
+        assert_eq!(error.category(), Category::QuotaExceeded);
+        assert_eq!(error.byte_offset(), Some(0));
+    }
+
+    #[test]
+    fn quota_counts_every_allocation() {
+        // The root `Compound` is charged 48, its entry 32, and the entry's
+        // name and value 2 * 1 + 36 and 2 * 3 + 36.
+        let root = &STREAM[..13];
+
+        let mut de = Deserializer::<_, BigEndian>::new(SliceRead::new(root));
+        de.set_quota(160);
+        Root::deserialize(&mut de).unwrap();
+
+        let mut de = Deserializer::<_, BigEndian>::new(SliceRead::new(root));
+        de.set_quota(159);
+        let error = Root::deserialize(&mut de).unwrap_err();
+        assert_eq!(error.category(), Category::QuotaExceeded);
+        assert_eq!(error.byte_offset(), Some(7));
+
+        let mut de = Deserializer::<_, BigEndian>::new(SliceRead::new(root));
+        de.set_quota(0);
+        de.disable_quota();
+        Root::deserialize(&mut de).unwrap();
+    }
+
+    #[test]
+    fn scanner_reports_how_many_bytes_it_needs() {
+        let root = &STREAM[..13];
+        let mut scanner = Scanner::<BigEndian>::new();
+
+        for (len, needed) in [(0, 1), (1, 2), (3, 1), (8, 1), (10, 2), (12, 1)] {
+            assert_eq!(
+                scanner.scan(&root[..len]).unwrap(),
+                Progress::Incomplete { needed },
+                "after {len} bytes",
+            );
+        }
+
+        assert_eq!(scanner.scan(root).unwrap(), Progress::Complete(13));
+    }
+
+    #[test]
+    fn scanner_never_asks_for_more_than_the_root_tag() {
+        let root = &STREAM[..13];
+        let mut scanner = Scanner::<BigEndian>::new();
+
+        for len in 0..root.len() {
+            let Progress::Incomplete { needed } = scanner.scan(&root[..len]).unwrap() else {
+                panic!("the root tag is complete after {len} bytes");
+            };
+            assert!(needed > 0 && len + needed <= root.len());
+        }
+
+        assert_eq!(scanner.scan(STREAM).unwrap(), Progress::Complete(13));
+    }
+
+    #[test]
+    fn scanner_rejects_invalid_input() {
+        let error = Scanner::<BigEndian>::new().scan(&[99, 0]).unwrap_err();
+
+        assert_eq!(error.category(), Category::InvalidInput);
+    }
+
+    #[test]
+    fn truncated_slices_report_the_bytes_needed() {
+        let mut de = Deserializer::<_, BigEndian>::new(SliceRead::new(&STREAM[..10]));
+        let error = Root::deserialize(&mut de).unwrap_err();
+
+        assert!(error.is_eof());
+        assert_eq!(error.bytes_needed(), Some(2));
+
+        let mut de = Deserializer::<_, BigEndian>::new(IoRead::new(&STREAM[..10]));
+        let error = Root::deserialize(&mut de).unwrap_err();
+
+        assert!(error.is_eof());
+        assert_eq!(error.bytes_needed(), Some(1));
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Borrowed<'a> {
+        #[serde(borrow)]
+        s: Cow<'a, str>,
+    }
+
+    /// A root `Compound` holding a `String` named `s` that contains a NUL,
+    /// which Modified UTF-8 encodes in two bytes.
+    const NUL: &[u8] = &[10, 0, 0, 8, 0, 1, b's', 0, 4, b'a', 0xC0, 0x80, b'b', 0];
+
+    #[test]
+    fn strings_borrow_from_a_slice() {
+        let mut de = Deserializer::<_, BigEndian>::new(SliceRead::new(&STREAM[..13]));
+        let root = Borrowed::deserialize(&mut de).unwrap();
+
+        assert!(matches!(root.s, Cow::Borrowed("abc")));
+    }
+
+    #[test]
+    fn keys_borrow_from_a_slice() {
+        let mut de = Deserializer::<_, BigEndian>::new(SliceRead::new(&STREAM[..13]));
+        let root = BTreeMap::<&str, &str>::deserialize(&mut de).unwrap();
+
+        assert_eq!(root.into_iter().collect::<Vec<_>>(), [("s", "abc")]);
+    }
+
+    #[test]
+    fn strings_are_owned_when_they_need_converting() {
+        #[derive(Debug, Deserialize)]
+        struct Str<'a> {
+            #[allow(dead_code)]
+            s: &'a str,
+        }
+
+        let mut de = Deserializer::<_, BigEndian>::new(SliceRead::new(NUL));
+        let root = Borrowed::deserialize(&mut de).unwrap();
+        assert!(matches!(root.s, Cow::Owned(ref s) if s == "a\0b"));
+
+        let mut de = Deserializer::<_, BigEndian>::new(SliceRead::new(NUL));
+        assert!(Str::deserialize(&mut de).is_err());
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Root {
+        s: String,
+    }
+
+    #[test]
+    fn stream_gives_each_root_a_fresh_quota() {
+        // Each root is charged 48 for the `Compound`, 32 for its entry and
+        // 2 * 1 + 36 and 2 * 3 + 36 for the entry's name and value.
+        let mut de = Deserializer::<_, BigEndian>::new(SliceRead::new(STREAM));
+        de.set_quota(160);
+        let roots = de.into_iter::<Root>().collect::<Result<Vec<_>, _>>();
+        assert_eq!(roots.unwrap().len(), 2);
+
+        let mut de = Deserializer::<_, BigEndian>::new(SliceRead::new(STREAM));
+        de.set_quota(159);
+        let error = de.into_iter::<Root>().next().unwrap().unwrap_err();
+        assert_eq!(error.category(), Category::QuotaExceeded);
+    }
+
+    #[test]
+    fn locating_an_entry_charges_nothing_to_the_quota() {
+        let mut de = Deserializer::<_, BigEndian>::new(SliceRead::new(&STREAM[..13]));
+        de.set_quota(0);
+        de.read.skip(3).unwrap();
+
+        assert_eq!(de.locate_entry("s").unwrap(), Type::String);
+        assert_eq!(de.byte_offset(), 7);
+    }
+
+    #[derive(Serialize)]
+    struct Level {
+        name: String,
+        pos: Vec<i32>,
+        data: Data,
+    }
+
+    #[derive(Serialize)]
+    struct Data {
+        x: i32,
+    }
+
+    fn level() -> Vec<u8> {
+        let level = Level {
+            name: "abc".into(),
+            pos: vec![1, 2, 3],
+            data: Data { x: 7 },
+        };
+        to_be_vec(&level).unwrap()
+    }
+
+    #[test]
+    fn lazy_compound_decodes_entries_on_demand() {
+        let bytes = level();
+        let level = LazyCompound::<BigEndian>::from_slice(&bytes).unwrap();
+
+        assert_eq!(level.len(), 3);
+        assert_eq!(level.ty("pos"), Some(Type::List));
+        assert_eq!(level.get::<String>("name").unwrap().unwrap(), "abc");
+        assert_eq!(level.get::<Vec<i32>>("pos").unwrap().unwrap(), [1, 2, 3]);
+        assert_eq!(level.raw("name"), Some(&[0, 3, b'a', b'b', b'c'][..]));
+
+        let data = level.get_value("data").unwrap().unwrap();
+        assert_eq!(data.as_compound().unwrap().get("x"), Some(&Value::Int(7)));
+
+        let data = level.get_lazy("data").unwrap().unwrap();
+        assert_eq!(data.name(), "data");
+        assert_eq!(data.get::<i32>("x").unwrap(), Some(7));
+    }
+
+    #[test]
+    fn lazy_compound_returns_none_for_missing_keys() {
+        let bytes = level();
+        let level = LazyCompound::<BigEndian>::from_slice(&bytes).unwrap();
+
+        assert!(!level.contains_key("missing"));
+        assert_eq!(level.ty("missing"), None);
+        assert_eq!(level.raw("missing"), None);
+        assert_eq!(level.get::<i32>("missing").unwrap(), None);
+        assert_eq!(level.get_value("missing").unwrap(), None);
+        assert!(level.get_lazy("missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn lazy_compound_keeps_the_last_of_duplicate_keys() {
+        let bytes = [
+            10, 0, 0, //
+            3, 0, 1, b'x', 0, 0, 0, 1, //
+            3, 0, 1, b'x', 0, 0, 0, 2, //
+            0,
+        ];
+        let root = LazyCompound::<BigEndian>::from_slice(&bytes).unwrap();
+
+        assert_eq!(root.len(), 1);
+        assert_eq!(root.get::<i32>("x").unwrap(), Some(2));
+    }
+
+    #[test]
+    fn lazy_compound_errors_carry_the_key_path() {
+        let bytes = level();
+        let level = LazyCompound::<BigEndian>::from_slice(&bytes).unwrap();
+
+        let error = level.get::<i32>("name").unwrap_err();
+        assert_eq!(error.category(), Category::InvalidData);
+        assert_eq!(error.path().unwrap().to_string(), "name");
+
+        let error = level.get_lazy("pos").unwrap_err();
+        assert_eq!(error.category(), Category::InvalidData);
+        assert_eq!(error.path().unwrap().to_string(), "pos");
+
+        let data = level.get_lazy("data").unwrap().unwrap();
+        let error = data.get::<String>("x").unwrap_err();
+        assert_eq!(error.path().unwrap().to_string(), "data.x");
+    }
+
+    #[test]
+    fn lazy_compound_rejects_truncated_input() {
+        let bytes = level();
+
+        for len in 0..bytes.len() {
+            let error = LazyCompound::<BigEndian>::from_slice(&bytes[..len]).unwrap_err();
+            assert!(error.is_eof(), "after {len} bytes");
+        }
+
+        let error = LazyCompound::<BigEndian>::from_slice(TRUNCATED).unwrap_err();
+        assert_eq!(error.path().unwrap().to_string(), "x");
+    }
+
+    #[test]
+    fn lazy_compound_keeps_the_settings_of_its_deserializer() {
+        #[derive(Serialize)]
+        struct Text {
+            s: String,
+        }
+
+        // Indexing charges 2 * 0 + 36 for the root name and 2 * 1 + 36 for the
+        // name of `s`, and decoding `s` charges 2 * 100 + 36 for its value on
+        // a fresh quota.
+        let text = to_be_vec(&Text { s: "a".repeat(100) }).unwrap();
+
+        let mut de = Deserializer::<_, BigEndian>::new(SliceRead::new(&text));
+        de.set_quota(235);
+        let lazy = LazyCompound::from_deserializer(de).unwrap();
+        let error = lazy.get::<String>("s").unwrap_err();
+        assert_eq!(error.category(), Category::QuotaExceeded);
+        assert_eq!(error.path().unwrap().to_string(), "s");
+
+        let mut de = Deserializer::<_, BigEndian>::new(SliceRead::new(&text));
+        de.set_quota(236);
+        let lazy = LazyCompound::from_deserializer(de).unwrap();
+        assert_eq!(lazy.get::<String>("s").unwrap().unwrap().len(), 100);
+        assert_eq!(lazy.get::<String>("s").unwrap().unwrap().len(), 100);
+
+        let root = &STREAM[..13];
+        let mut de = Deserializer::<_, BigEndian>::new(SliceRead::new(root));
+        de.require_root_name("level");
+        let error = LazyCompound::from_deserializer(de).unwrap_err();
+        assert_eq!(error.category(), Category::InvalidData);
+    }
+}
@@ -0,0 +1,137 @@
+//! TODO
+
+use alloc::borrow::Cow;
+
+use serde::de::{self, value::BorrowedStrDeserializer, IntoDeserializer, Unexpected, Visitor};
+
+use crate::error::{Error, Result};
+
+/// Deserializes the name of a compound entry.
+///
+/// Names are always strings in NBT, but map keys in Rust frequently aren't;
+/// numbers and booleans are parsed out of the name when they are requested.
+///
+/// The deserializer only borrows the name so the caller can hold on to it for
+/// error paths without having to clone it. Names that live as long as the
+/// input are handed to the visitor as borrowed strings.
+pub(crate) struct KeyDeserializer<'de, 'a> {
+    key: Reference<'de, 'a>,
+}
+
+impl<'de, 'a> KeyDeserializer<'de, 'a> {
+    #[inline]
+    pub(crate) fn new(key: Reference<'de, 'a>) -> Self {
+        KeyDeserializer { key }
+    }
+
+    #[inline]
+    fn as_str(&self) -> &str {
+        match self.key {
+            Reference::Borrowed(key) | Reference::Copied(key) => key,
+        }
+    }
+}
+
+/// A name that either lives as long as the input, or was copied out of it
+/// into a buffer owned by the caller.
+pub(crate) enum Reference<'de, 'a> {
+    Borrowed(&'de str),
+    Copied(&'a str),
+}
+
+impl<'de, 'a> From<&'a Cow<'de, str>> for Reference<'de, 'a> {
+    #[inline]
+    fn from(key: &'a Cow<'de, str>) -> Self {
+        match key {
+            Cow::Borrowed(key) => Reference::Borrowed(key),
+            Cow::Owned(key) => Reference::Copied(key),
+        }
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V>(self, visitor: V) -> Result<V::Value>
+            where
+                V: Visitor<'de>,
+            {
+                match self.as_str().parse() {
+                    Ok(value) => visitor.$visit(value),
+                    Err(_) => Err(de::Error::invalid_value(
+                        Unexpected::Str(self.as_str()),
+                        &visitor,
+                    )),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for KeyDeserializer<'de, '_> {
+    type Error = Error;
+
+    #[inline]
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.key {
+            Reference::Borrowed(key) => visitor.visit_borrowed_str(key),
+            Reference::Copied(key) => visitor.visit_str(key),
+        }
+    }
+
+    deserialize_parsed! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+    }
+
+    #[inline]
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    #[inline]
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    #[inline]
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.key {
+            Reference::Borrowed(key) => visitor.visit_enum(BorrowedStrDeserializer::new(key)),
+            Reference::Copied(key) => visitor.visit_enum(key.into_deserializer()),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        char str string bytes byte_buf unit unit_struct seq tuple tuple_struct
+        map struct identifier ignored_any
+    }
+}
@@ -0,0 +1,217 @@
+//! TODO
+
+#[cfg(feature = "binary")]
+pub mod binary;
+mod key;
+#[cfg(feature = "binary")]
+mod options;
+pub mod read;
+#[cfg(feature = "snbt")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "snbt")))]
+pub mod snbt;
+#[cfg(any(feature = "be", feature = "le"))]
+mod sniff;
+pub mod value;
+
+#[cfg(feature = "binary")]
+pub use self::options::Options;
+#[cfg(any(feature = "be", feature = "le"))]
+pub use self::sniff::{from_slice_auto, sniff, Detected, Format};
+
+use serde::de::DeserializeOwned;
+#[cfg(any(feature = "be", feature = "le", feature = "snbt"))]
+use serde::Deserialize;
+#[cfg(all(any(feature = "be", feature = "le"), feature = "std"))]
+use std::io;
+
+#[cfg(any(feature = "be", feature = "le"))]
+use crate::binary::Endec;
+use crate::{error::Result, Value};
+
+////////////////////////////////////////////////////////////////////////////////
+// Minecraft: Java Edition
+////////////////////////////////////////////////////////////////////////////////
+
+/// Deserializes an instance of type `T` from bytes of big-endian binary NBT,
+/// as used by Minecraft: Java Edition.
+///
+/// # Errors
+///
+/// This conversion can fail if the input is not valid binary NBT, if it holds
+/// trailing bytes after the root tag, or if the structure of the input does
+/// not match the structure expected by `T`.
+#[cfg(feature = "be")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "be")))]
+pub fn from_be_slice<'de, T>(slice: &'de [u8]) -> Result<T>
+where
+    T: Deserialize<'de>,
+{
+    from_slice::<T, crate::binary::BigEndian>(slice)
+}
+
+/// Deserializes an instance of type `T` from an I/O stream of big-endian
+/// binary NBT, as used by Minecraft: Java Edition.
+///
+/// The reader is not buffered internally; wrap it in a
+/// [`BufReader`](std::io::BufReader) if it performs a system call per read.
+///
+/// # Errors
+///
+/// This conversion can fail if the stream fails to read, if the input is not
+/// valid binary NBT, or if the structure of the input does not match the
+/// structure expected by `T`.
+#[cfg(all(feature = "be", feature = "std"))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "be", feature = "std"))))]
+pub fn from_be_reader<R, T>(reader: R) -> Result<T>
+where
+    R: io::Read,
+    T: DeserializeOwned,
+{
+    from_reader::<R, T, crate::binary::BigEndian>(reader)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Minecraft: Bedrock Edition
+////////////////////////////////////////////////////////////////////////////////
+
+/// Deserializes an instance of type `T` from bytes of little-endian binary
+/// NBT, as used by Minecraft: Bedrock Edition to store data on disk.
+///
+/// # Errors
+///
+/// This conversion can fail if the input is not valid binary NBT, if it holds
+/// trailing bytes after the root tag, or if the structure of the input does
+/// not match the structure expected by `T`.
+#[cfg(feature = "le")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "le")))]
+pub fn from_le_slice<'de, T>(slice: &'de [u8]) -> Result<T>
+where
+    T: Deserialize<'de>,
+{
+    from_slice::<T, crate::binary::LittleEndian>(slice)
+}
+
+/// Deserializes an instance of type `T` from an I/O stream of little-endian
+/// binary NBT, as used by Minecraft: Bedrock Edition to store data on disk.
+///
+/// The reader is not buffered internally; wrap it in a
+/// [`BufReader`](std::io::BufReader) if it performs a system call per read.
+///
+/// # Errors
+///
+/// This conversion can fail if the stream fails to read, if the input is not
+/// valid binary NBT, or if the structure of the input does not match the
+/// structure expected by `T`.
+#[cfg(all(feature = "le", feature = "std"))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "le", feature = "std"))))]
+pub fn from_le_reader<R, T>(reader: R) -> Result<T>
+where
+    R: io::Read,
+    T: DeserializeOwned,
+{
+    from_reader::<R, T, crate::binary::LittleEndian>(reader)
+}
+
+/// Deserializes an instance of type `T` from bytes of [`VarInt`] binary NBT, as
+/// used by Minecraft: Bedrock Edition to send data over the network.
+///
+/// # Errors
+///
+/// This conversion can fail if the input is not valid binary NBT, if it holds
+/// trailing bytes after the root tag, or if the structure of the input does
+/// not match the structure expected by `T`.
+///
+/// [`VarInt`]: crate::binary::VarInt
+#[cfg(feature = "varint")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "varint")))]
+pub fn from_varint_slice<'de, T>(slice: &'de [u8]) -> Result<T>
+where
+    T: Deserialize<'de>,
+{
+    from_slice::<T, crate::binary::VarInt>(slice)
+}
+
+/// Deserializes an instance of type `T` from an I/O stream of [`VarInt`] binary
+/// NBT, as used by Minecraft: Bedrock Edition to send data over the network.
+///
+/// The reader is not buffered internally; wrap it in a
+/// [`BufReader`](std::io::BufReader) if it performs a system call per read.
+///
+/// # Errors
+///
+/// This conversion can fail if the stream fails to read, if the input is not
+/// valid binary NBT, or if the structure of the input does not match the
+/// structure expected by `T`.
+///
+/// [`VarInt`]: crate::binary::VarInt
+#[cfg(all(feature = "varint", feature = "std"))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "varint", feature = "std"))))]
+pub fn from_varint_reader<R, T>(reader: R) -> Result<T>
+where
+    R: io::Read,
+    T: DeserializeOwned,
+{
+    from_reader::<R, T, crate::binary::VarInt>(reader)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// SNBT
+////////////////////////////////////////////////////////////////////////////////
+
+/// Deserializes an instance of type `T` from a string of SNBT, the textual
+/// form of NBT used in Minecraft's commands.
+///
+/// # Errors
+///
+/// This conversion can fail if the input is not valid SNBT, if anything other
+/// than whitespace follows the root tag, or if the structure of the input does
+/// not match the structure expected by `T`.
+#[cfg(feature = "snbt")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "snbt")))]
+pub fn from_snbt_str<'de, T>(s: &'de str) -> Result<T>
+where
+    T: Deserialize<'de>,
+{
+    let mut de = snbt::Deserializer::new(s);
+    let value = T::deserialize(&mut de)?;
+    de.end()?;
+    Ok(value)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// `Value`
+////////////////////////////////////////////////////////////////////////////////
+
+/// Deserializes an instance of type `T` out of a [`Value`].
+///
+/// # Errors
+///
+/// This conversion can fail if the structure of `value` does not match the
+/// structure expected by `T`, or if `value` nests too deeply.
+pub fn from_value<T>(value: Value) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    T::deserialize(value::Deserializer::new(value))
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(any(feature = "be", feature = "le"))]
+fn from_slice<'de, T, E>(slice: &'de [u8]) -> Result<T>
+where
+    T: Deserialize<'de>,
+    E: Endec,
+{
+    Options::<E>::new().from_slice(slice)
+}
+
+#[cfg(all(any(feature = "be", feature = "le"), feature = "std"))]
+fn from_reader<R, T, E>(reader: R) -> Result<T>
+where
+    R: io::Read,
+    T: DeserializeOwned,
+    E: Endec,
+{
+    Options::<E>::new().from_reader(reader)
+}
@@ -0,0 +1,466 @@
+//! TODO
+
+use alloc::string::String;
+use core::marker::PhantomData;
+#[cfg(feature = "std")]
+use std::io;
+
+#[cfg(feature = "std")]
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+#[cfg(feature = "std")]
+use super::read::IoRead;
+use super::{
+    binary::Deserializer,
+    read::{Read, SliceRead},
+};
+#[cfg(feature = "compression")]
+use crate::binary::Compression;
+use crate::{
+    binary::Endec,
+    error::Result,
+    util::{DEFAULT_RECURSION_LIMIT, DISK_QUOTA},
+};
+
+/// A builder for configuring how binary NBT is decoded.
+///
+/// The dialect is part of the type and is chosen by the constructor, e.g.
+/// [`Options::java`]. Everything else can be changed with the `with_*` and
+/// `without_*` methods, after which the options either decode input directly
+/// or create a configured [`Deserializer`].
+///
+/// The defaults match those of [`Deserializer::new`].
+#[derive(Debug, Clone)]
+pub struct Options<E> {
+    recursion_limit: Option<usize>,
+    quota: Option<usize>,
+    lossy_strings: bool,
+    backtraces: bool,
+    root_name: RootName,
+    #[cfg(feature = "compression")]
+    compression: Option<Compression>,
+    endec: PhantomData<E>,
+}
+
+#[cfg(feature = "be")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "be")))]
+impl Options<crate::binary::BigEndian> {
+    /// Creates the default options for Minecraft: Java Edition's big-endian
+    /// binary NBT.
+    #[must_use]
+    #[inline]
+    pub fn java() -> Self {
+        Options::new()
+    }
+}
+
+#[cfg(feature = "le")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "le")))]
+impl Options<crate::binary::LittleEndian> {
+    /// Creates the default options for the little-endian binary NBT
+    /// Minecraft: Bedrock Edition stores on disk.
+    #[must_use]
+    #[inline]
+    pub fn bedrock() -> Self {
+        Options::new()
+    }
+}
+
+#[cfg(feature = "varint")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "varint")))]
+impl Options<crate::binary::VarInt> {
+    /// Creates the default options for the [`VarInt`] binary NBT Minecraft:
+    /// Bedrock Edition sends over the network.
+    ///
+    /// [`VarInt`]: crate::binary::VarInt
+    #[must_use]
+    #[inline]
+    pub fn bedrock_network() -> Self {
+        Options::new()
+    }
+}
+
+impl<E> Options<E>
+where
+    E: Endec,
+{
+    /// Creates the default options for the dialect `E`.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Options {
+            recursion_limit: Some(DEFAULT_RECURSION_LIMIT),
+            quota: Some(DISK_QUOTA),
+            lossy_strings: false,
+            backtraces: true,
+            root_name: RootName::Keep,
+            #[cfg(feature = "compression")]
+            compression: None,
+            endec: PhantomData,
+        }
+    }
+
+    /// Sets how deeply `List`s and `Compound`s may nest.
+    ///
+    /// See [`Deserializer::set_recursion_limit`].
+    #[must_use]
+    #[inline]
+    pub fn with_recursion_limit(mut self, limit: usize) -> Self {
+        self.recursion_limit = Some(limit);
+        self
+    }
+
+    /// Lets `List`s and `Compound`s nest arbitrarily deeply.
+    ///
+    /// See [`Deserializer::disable_recursion_limit`].
+    #[must_use]
+    #[inline]
+    pub fn without_recursion_limit(mut self) -> Self {
+        self.recursion_limit = None;
+        self
+    }
+
+    /// Sets how many bytes of memory decoding may allocate.
+    ///
+    /// See [`Deserializer::set_quota`].
+    #[must_use]
+    #[inline]
+    pub fn with_quota(mut self, bytes: usize) -> Self {
+        self.quota = Some(bytes);
+        self
+    }
+
+    /// Lets decoding allocate as much memory as the input asks for.
+    ///
+    /// See [`Deserializer::disable_quota`].
+    #[must_use]
+    #[inline]
+    pub fn without_quota(mut self) -> Self {
+        self.quota = None;
+        self
+    }
+
+    /// Replaces malformed sequences in strings instead of failing.
+    ///
+    /// See [`Deserializer::enable_lossy_strings`].
+    #[must_use]
+    #[inline]
+    pub fn with_lossy_strings(mut self) -> Self {
+        self.lossy_strings = true;
+        self
+    }
+
+    /// Requires the root tag to be named `name`.
+    ///
+    /// See [`Deserializer::require_root_name`].
+    #[must_use]
+    #[inline]
+    pub fn with_root_name<S>(mut self, name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.root_name = RootName::Require(name.into());
+        self
+    }
+
+    /// Skips over the name of the root tag.
+    ///
+    /// See [`Deserializer::ignore_root_name`].
+    #[must_use]
+    #[inline]
+    pub fn without_root_name(mut self) -> Self {
+        self.root_name = RootName::Ignore;
+        self
+    }
+
+    /// Reads the root tag without a name, as Java Edition sends it over the
+    /// network since 1.20.2.
+    ///
+    /// See [`Deserializer::expect_unnamed_root`].
+    #[must_use]
+    #[inline]
+    pub fn with_unnamed_root(mut self) -> Self {
+        self.root_name = RootName::Absent;
+        self
+    }
+
+    /// Stops errors from capturing a backtrace or a hexdump of the input.
+    ///
+    /// See [`Deserializer::disable_backtraces`].
+    #[must_use]
+    #[inline]
+    pub fn without_backtraces(mut self) -> Self {
+        self.backtraces = false;
+        self
+    }
+
+    /// Decompresses the input before decoding it.
+    ///
+    /// Only [`from_slice`](Self::from_slice) and
+    /// [`from_reader`](Self::from_reader) decompress; a deserializer made by
+    /// [`deserializer`](Self::deserializer) reads its input as-is.
+    ///
+    /// Decompressed strings cannot borrow from the input, so types that
+    /// borrow `&str`s fail to deserialize from compressed input.
+    #[cfg(feature = "compression")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "compression")))]
+    #[must_use]
+    #[inline]
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    /// Creates a deserializer with these options that reads from `read`.
+    #[must_use]
+    pub fn deserializer<'de, R>(&self, read: R) -> Deserializer<R, E>
+    where
+        R: Read<'de>,
+    {
+        let mut de = Deserializer::new(read);
+
+        match self.recursion_limit {
+            Some(limit) => de.set_recursion_limit(limit),
+            None => de.disable_recursion_limit(),
+        }
+
+        match self.quota {
+            Some(bytes) => de.set_quota(bytes),
+            None => de.disable_quota(),
+        }
+
+        if self.lossy_strings {
+            de.enable_lossy_strings();
+        }
+
+        if !self.backtraces {
+            de.disable_backtraces();
+        }
+
+        match &self.root_name {
+            RootName::Keep => {}
+            RootName::Ignore => de.ignore_root_name(),
+            RootName::Require(name) => de.require_root_name(name.as_str()),
+            RootName::Absent => de.expect_unnamed_root(),
+        }
+
+        de
+    }
+
+    /// Deserializes an instance of type `T` from bytes of binary NBT.
+    ///
+    /// # Errors
+    ///
+    /// This conversion can fail if the input cannot be decompressed, if it is
+    /// not valid binary NBT, if it holds trailing bytes after the root tag,
+    /// or if the structure of the input does not match the structure expected
+    /// by `T`.
+    pub fn from_slice<'de, T>(&self, slice: &'de [u8]) -> Result<T>
+    where
+        T: Deserialize<'de>,
+    {
+        #[cfg(feature = "compression")]
+        if let Some(compression) = self.compression {
+            let read = IoRead::new(zc_io::IoReader::new(Decoder::new(slice, compression)));
+            let mut de = self.deserializer(read);
+            let value = T::deserialize(&mut de)?;
+            de.end_of_input()?;
+            return Ok(value);
+        }
+
+        let mut de = self.deserializer(SliceRead::new(slice));
+        let value = T::deserialize(&mut de)?;
+        de.end()?;
+        Ok(value)
+    }
+
+    /// Deserializes an instance of type `T` from the start of a slice of
+    /// binary NBT, returning it along with whatever bytes follow the root
+    /// tag.
+    ///
+    /// Unlike [`from_slice`](Self::from_slice), the input is never
+    /// decompressed, since the remainder has to be a part of `slice`.
+    ///
+    /// # Errors
+    ///
+    /// This conversion can fail if the input does not start with valid
+    /// binary NBT, or if the structure of the input does not match the
+    /// structure expected by `T`.
+    pub fn from_slice_partial<'de, T>(&self, slice: &'de [u8]) -> Result<(T, &'de [u8])>
+    where
+        T: Deserialize<'de>,
+    {
+        let mut de = self.deserializer(SliceRead::new(slice));
+        let value = T::deserialize(&mut de)?;
+        #[allow(clippy::cast_possible_truncation)]
+        let consumed = de.byte_offset() as usize;
+        Ok((value, &slice[consumed..]))
+    }
+
+    /// Deserializes an instance of type `T` from an I/O stream of binary NBT.
+    ///
+    /// The reader is not buffered internally; wrap it in a
+    /// [`BufReader`](std::io::BufReader) if it performs a system call per
+    /// read.
+    ///
+    /// Unlike [`from_slice`](Self::from_slice), nothing after the root tag is
+    /// read, so trailing bytes are left in the stream rather than rejected.
+    /// This lets a stream carry more data after the NBT.
+    ///
+    /// # Errors
+    ///
+    /// This conversion can fail if the stream fails to read or decompress, if
+    /// the input is not valid binary NBT, or if the structure of the input
+    /// does not match the structure expected by `T`.
+    #[cfg(feature = "std")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+    pub fn from_reader<R, T>(&self, reader: R) -> Result<T>
+    where
+        R: io::Read,
+        T: DeserializeOwned,
+    {
+        #[cfg(feature = "compression")]
+        if let Some(compression) = self.compression {
+            let read = IoRead::new(zc_io::IoReader::new(Decoder::new(reader, compression)));
+            let mut de = self.deserializer(read);
+            let value = T::deserialize(&mut de)?;
+            de.end()?;
+            return Ok(value);
+        }
+
+        let read = IoRead::new(zc_io::IoReader::new(reader));
+        let mut de = self.deserializer(read);
+        let value = T::deserialize(&mut de)?;
+        de.end()?;
+        Ok(value)
+    }
+}
+
+impl<E> Default for Options<E>
+where
+    E: Endec,
+{
+    #[inline]
+    fn default() -> Self {
+        Options::new()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// What the options tell a [`Deserializer`] to do with the name of the root
+/// tag.
+#[derive(Debug, Clone)]
+enum RootName {
+    Keep,
+    Ignore,
+    Require(String),
+    Absent,
+}
+
+/// Decompresses a stream in whichever format the options asked for.
+#[cfg(feature = "compression")]
+enum Decoder<R> {
+    Gzip(flate2::read::GzDecoder<R>),
+    Zlib(flate2::read::ZlibDecoder<R>),
+}
+
+#[cfg(feature = "compression")]
+impl<R> Decoder<R>
+where
+    R: io::Read,
+{
+    fn new(reader: R, compression: Compression) -> Self {
+        match compression {
+            Compression::Gzip => Decoder::Gzip(flate2::read::GzDecoder::new(reader)),
+            Compression::Zlib => Decoder::Zlib(flate2::read::ZlibDecoder::new(reader)),
+        }
+    }
+}
+
+#[cfg(feature = "compression")]
+impl<R> io::Read for Decoder<R>
+where
+    R: io::Read,
+{
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Decoder::Gzip(decoder) => decoder.read(buf),
+            Decoder::Zlib(decoder) => decoder.read(buf),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(all(test, feature = "be"))]
+mod tests {
+    use serde::de::IgnoredAny;
+
+    use super::Options;
+    use crate::error::Category;
+
+    /// An empty root `Compound` followed by a byte that does not belong to it.
+    const TRAILING: &[u8] = &[10, 0, 0, 0, 0xFF];
+
+    #[test]
+    fn from_slice_rejects_trailing_bytes() {
+        let error = Options::java()
+            .from_slice::<IgnoredAny>(TRAILING)
+            .unwrap_err();
+
+        assert_eq!(error.category(), Category::InvalidInput);
+        assert_eq!(error.byte_offset(), Some(4));
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn compressed_from_slice_rejects_trailing_bytes() {
+        use std::io::Write;
+
+        use flate2::write::GzEncoder;
+
+        use crate::binary::Compression;
+
+        fn gzip(bytes: &[u8]) -> Vec<u8> {
+            let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(bytes).unwrap();
+            encoder.finish().unwrap()
+        }
+
+        let error = Options::java()
+            .with_compression(Compression::Gzip)
+            .from_slice::<IgnoredAny>(&gzip(TRAILING))
+            .unwrap_err();
+
+        assert_eq!(error.category(), Category::InvalidInput);
+        assert_eq!(error.byte_offset(), Some(4));
+
+        Options::java()
+            .with_compression(Compression::Gzip)
+            .from_slice::<IgnoredAny>(&gzip(&TRAILING[..4]))
+            .unwrap();
+    }
+
+    #[test]
+    fn from_slice_partial_returns_the_remainder() {
+        let (_, rest) = Options::java()
+            .from_slice_partial::<IgnoredAny>(TRAILING)
+            .unwrap();
+
+        assert_eq!(rest, &[0xFF]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn from_reader_leaves_trailing_bytes_in_the_stream() {
+        let mut reader = TRAILING;
+        Options::java()
+            .from_reader::<_, IgnoredAny>(&mut reader)
+            .unwrap();
+
+        assert_eq!(reader, &[0xFF]);
+    }
+}
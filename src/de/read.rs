@@ -0,0 +1,436 @@
+//! TODO
+
+#[cfg(not(feature = "std"))]
+use alloc::string::ToString;
+use alloc::{borrow::Cow, format, vec::Vec};
+use core::cmp;
+
+use crate::error::{Category, Detail, Error, Position, Result};
+
+/// The most bytes a reader will buffer at once before it has seen them.
+///
+/// Length prefixes come from untrusted input, so larger reads grow their
+/// buffer a chunk at a time rather than allocating the declared length up
+/// front.
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// How many bytes [`IoRead`] discards at a time when skipping, in a buffer on
+/// the stack.
+const SKIP_SIZE: usize = 256;
+
+/// Trait used by the binary deserializers for iterating over input.
+///
+/// Every implementation tracks the absolute offset of the next byte it will
+/// yield so that errors can point at exactly where decoding went wrong.
+///
+/// [`Endec`](crate::binary::Endec) implementations read the primitives of
+/// their dialect through these methods.
+///
+/// [`SliceRead`] and [`IoRead`] cover most input sources; the latter adapts
+/// any [`zc_io::Read`], which is usually the simpler trait to implement for a
+/// custom source. Implementing this trait directly is worthwhile when the
+/// source knows how many bytes it has left, which lets the deserializer
+/// reject length prefixes that run past the end of the input up front.
+pub trait Read<'de> {
+    /// Reads the next byte.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the input has ended or cannot be read.
+    fn next(&mut self) -> Result<u8>;
+
+    /// Reads the next `N` bytes.
+    ///
+    /// # Errors
+    ///
+    /// Fails if fewer than `N` bytes are left or the input cannot be read.
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N]>;
+
+    /// Reads the next `len` bytes, borrowing them from the input if
+    /// possible.
+    ///
+    /// # Errors
+    ///
+    /// Fails if fewer than `len` bytes are left or the input cannot be read.
+    fn read_slice(&mut self, len: usize) -> Result<Cow<'de, [u8]>>;
+
+    /// Skips over the next `len` bytes.
+    ///
+    /// # Errors
+    ///
+    /// Fails if fewer than `len` bytes are left or the input cannot be read.
+    fn skip(&mut self, len: usize) -> Result<()>;
+
+    /// Returns the number of bytes left in the input, if known.
+    ///
+    /// The default implementation returns `None`.
+    #[inline]
+    fn remaining(&self) -> Option<usize> {
+        None
+    }
+
+    /// Returns the absolute offset of the next byte to be read.
+    fn byte_offset(&self) -> u64;
+
+    /// Returns the bytes read since `offset` if they can be borrowed from
+    /// the input, which lets [`RawNbt`](crate::binary::RawNbt) capture a
+    /// payload without copying it.
+    ///
+    /// The default implementation returns `None`, in which case the bytes
+    /// are recorded as they are read instead.
+    #[inline]
+    fn consumed_since(&self, offset: u64) -> Option<&'de [u8]> {
+        let _ = offset;
+        None
+    }
+
+    /// Positions `error` at `offset` unless it already has a position.
+    ///
+    /// Sources can override this to attach more context to errors, as
+    /// [`SliceRead`] does with a [`Hexdump`](crate::error::Hexdump).
+    fn fix_position(&self, error: Error, offset: u64) -> Error {
+        error.fix_position(|| Position::Byte(offset))
+    }
+
+    /// Stops [`Read::fix_position`] from attaching any context to errors
+    /// beyond their position.
+    ///
+    /// The binary deserializer calls this when backtraces are disabled, so
+    /// that rejecting bad input stays cheap. The default implementation does
+    /// nothing.
+    #[inline]
+    fn disable_error_context(&mut self) {}
+
+    /// Creates a new error positioned at `offset`.
+    #[cold]
+    #[track_caller]
+    fn error<T>(&self, category: Category, message: T, offset: u64) -> Error
+    where
+        T: Into<Cow<'static, str>>,
+    {
+        self.fix_position(Error::new(category, message), offset)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Binary NBT input source that reads from a slice of bytes.
+///
+/// Errors produced while reading from a slice carry a [`Hexdump`] of the
+/// bytes surrounding the offending offset, unless error context has been
+/// disabled.
+///
+/// [`Hexdump`]: crate::error::Hexdump
+pub struct SliceRead<'de> {
+    slice: &'de [u8],
+    index: usize,
+    hexdumps: bool,
+}
+
+impl<'de> SliceRead<'de> {
+    /// Creates a binary NBT input source to read from a slice of bytes.
+    #[must_use]
+    #[inline]
+    pub fn new(slice: &'de [u8]) -> Self {
+        SliceRead {
+            slice,
+            index: 0,
+            hexdumps: true,
+        }
+    }
+
+    /// Returns the whole slice, including the bytes read already.
+    #[cfg(feature = "binary")]
+    #[inline]
+    pub(crate) fn slice(&self) -> &'de [u8] {
+        self.slice
+    }
+
+    /// Moves back to `index`, which must have been read past already.
+    #[cfg(feature = "binary")]
+    #[inline]
+    pub(crate) fn seek(&mut self, index: usize) {
+        debug_assert!(index <= self.index);
+        self.index = index;
+    }
+
+    /// Creates the error for a read of `len` bytes that runs past the end of
+    /// the slice.
+    #[cold]
+    #[track_caller]
+    fn eof(&self, len: usize) -> Error {
+        let needed = len - (self.slice.len() - self.index);
+        let error = self.error(
+            Category::UnexpectedEof,
+            "unexpected end of input",
+            self.slice.len() as u64,
+        );
+        error.with_detail(Detail::Incomplete { needed })
+    }
+}
+
+impl<'de> Read<'de> for SliceRead<'de> {
+    #[inline]
+    fn next(&mut self) -> Result<u8> {
+        match self.slice.get(self.index) {
+            Some(&byte) => {
+                self.index += 1;
+                Ok(byte)
+            }
+            None => Err(self.eof(1)),
+        }
+    }
+
+    #[inline]
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let Some(bytes) = self.slice.get(self.index..self.index + N) else {
+            return Err(self.eof(N));
+        };
+
+        let mut array = [0; N];
+        array.copy_from_slice(bytes);
+        self.index += N;
+        Ok(array)
+    }
+
+    #[inline]
+    fn read_slice(&mut self, len: usize) -> Result<Cow<'de, [u8]>> {
+        if len > self.slice.len() - self.index {
+            return Err(self.eof(len));
+        }
+
+        let bytes = &self.slice[self.index..self.index + len];
+        self.index += len;
+        Ok(Cow::Borrowed(bytes))
+    }
+
+    #[inline]
+    fn skip(&mut self, len: usize) -> Result<()> {
+        if len > self.slice.len() - self.index {
+            return Err(self.eof(len));
+        }
+
+        self.index += len;
+        Ok(())
+    }
+
+    #[inline]
+    fn remaining(&self) -> Option<usize> {
+        Some(self.slice.len() - self.index)
+    }
+
+    #[inline]
+    fn byte_offset(&self) -> u64 {
+        self.index as u64
+    }
+
+    #[inline]
+    fn consumed_since(&self, offset: u64) -> Option<&'de [u8]> {
+        let start = usize::try_from(offset).ok()?;
+        self.slice.get(start..self.index)
+    }
+
+    fn fix_position(&self, error: Error, offset: u64) -> Error {
+        let error = error.fix_position(|| Position::Byte(offset));
+
+        if self.hexdumps {
+            error.attach_hexdump(self.slice)
+        } else {
+            error
+        }
+    }
+
+    #[inline]
+    fn disable_error_context(&mut self) {
+        self.hexdumps = false;
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Binary NBT input source that reads from any [`zc_io::Read`]
+/// implementation.
+///
+/// With the `std` feature enabled, wrap an [`io::Read`] in a
+/// [`zc_io::IoReader`] to decode from files, sockets and decompressors.
+///
+/// Without `std`, the errors of a [`zc_io::Read`] implementation do not say
+/// what went wrong, so every failed read is reported as the end of the input.
+///
+/// [`io::Read`]: std::io::Read
+pub struct IoRead<R> {
+    inner: R,
+    offset: u64,
+}
+
+impl<R> IoRead<R> {
+    /// Creates a binary NBT input source to read from a [`zc_io::Read`]
+    /// implementation.
+    #[must_use]
+    #[inline]
+    pub fn new(reader: R) -> Self {
+        IoRead {
+            inner: reader,
+            offset: 0,
+        }
+    }
+
+    /// Unwraps this `IoRead`, returning the underlying reader.
+    #[must_use]
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Creates the error for a failed read from the underlying reader.
+    ///
+    /// A reader that runs out of bytes cannot tell how many more it would
+    /// have needed, so short reads report the lower bound of one byte.
+    #[cold]
+    #[track_caller]
+    #[cfg_attr(not(feature = "std"), allow(clippy::needless_pass_by_value))]
+    fn io_error(&self, error: zc_io::Error) -> Error {
+        // Without `std`, `zc_io` errors carry nothing but a message, and its
+        // readers only ever fail by running out of bytes.
+        #[cfg(feature = "std")]
+        let error = Error::io(error);
+        #[cfg(not(feature = "std"))]
+        let error = Error::new(Category::UnexpectedEof, error.to_string());
+
+        let error = if error.is_eof() {
+            error.with_detail(Detail::Incomplete { needed: 1 })
+        } else {
+            error
+        };
+
+        error.fix_position(|| Position::Byte(self.offset))
+    }
+
+    /// Reads `len` bytes into a buffer that only grows as the bytes arrive,
+    /// so that a bogus length runs into the end of the input before it can
+    /// exhaust memory.
+    fn read_chunked<'de>(&mut self, len: usize) -> Result<Vec<u8>>
+    where
+        R: zc_io::Read<'de>,
+    {
+        let offset = self.offset;
+        let mut buf = Vec::new();
+
+        while buf.len() < len {
+            let chunk = cmp::min(len - buf.len(), CHUNK_SIZE);
+
+            if buf.try_reserve(chunk).is_err() {
+                let message = format!("failed to allocate {len} bytes");
+                let error = Error::new(Category::InvalidData, message);
+                return Err(error.fix_position(|| Position::Byte(offset)));
+            }
+
+            let bytes = self.inner.read_slice(chunk).map_err(|e| self.io_error(e))?;
+            buf.extend_from_slice(&bytes);
+            self.offset += chunk as u64;
+        }
+
+        Ok(buf)
+    }
+
+    /// Reads and throws away the next `N` bytes without allocating,
+    /// returning `N`.
+    #[inline]
+    fn discard<'de, const N: usize>(&mut self) -> Result<usize>
+    where
+        R: zc_io::Read<'de>,
+    {
+        self.inner.read_array::<N>().map_err(|e| self.io_error(e))?;
+        self.offset += N as u64;
+        Ok(N)
+    }
+}
+
+impl<'de, R> Read<'de> for IoRead<R>
+where
+    R: zc_io::Read<'de>,
+{
+    #[inline]
+    fn next(&mut self) -> Result<u8> {
+        let byte = self.inner.read_next().map_err(|e| self.io_error(e))?;
+        self.offset += 1;
+        Ok(byte)
+    }
+
+    #[inline]
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let array = self.inner.read_array().map_err(|e| self.io_error(e))?;
+        self.offset += N as u64;
+        Ok(array)
+    }
+
+    #[inline]
+    fn read_slice(&mut self, len: usize) -> Result<Cow<'de, [u8]>> {
+        if len > CHUNK_SIZE {
+            return self.read_chunked(len).map(Cow::Owned);
+        }
+
+        let bytes = self.inner.read_slice(len).map_err(|e| self.io_error(e))?;
+        self.offset += len as u64;
+        Ok(bytes)
+    }
+
+    fn skip(&mut self, mut len: usize) -> Result<()> {
+        while len > 0 {
+            // Once less than `SKIP_SIZE` is left, it is discarded in pieces
+            // of decreasing powers of two.
+            len -= match len {
+                SKIP_SIZE.. => self.discard::<SKIP_SIZE>()?,
+                128.. => self.discard::<128>()?,
+                64.. => self.discard::<64>()?,
+                32.. => self.discard::<32>()?,
+                16.. => self.discard::<16>()?,
+                8.. => self.discard::<8>()?,
+                4.. => self.discard::<4>()?,
+                2.. => self.discard::<2>()?,
+                _ => self.discard::<1>()?,
+            };
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn byte_offset(&self) -> u64 {
+        self.offset
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::{IoRead, Read};
+
+    #[test]
+    fn io_read_skips_any_length() {
+        let input = (0..=255).cycle().take(1000).collect::<Vec<u8>>();
+
+        for len in [0, 1, 7, 255, 256, 257, 999] {
+            let mut read = IoRead::new(input.as_slice());
+            read.skip(len).unwrap();
+
+            assert_eq!(read.byte_offset(), len as u64);
+            #[allow(clippy::cast_possible_truncation)]
+            let expected = len as u8;
+            assert_eq!(read.next().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn io_read_skip_fails_past_the_end() {
+        let mut read = IoRead::new(&[0; 300][..]);
+        let error = read.skip(301).unwrap_err();
+
+        assert!(error.is_eof());
+        assert_eq!(error.bytes_needed(), Some(1));
+        assert_eq!(error.byte_offset(), Some(300));
+    }
+}
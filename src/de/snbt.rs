@@ -0,0 +1,1245 @@
+//! TODO
+
+use alloc::{
+    borrow::Cow,
+    format,
+    string::String,
+    vec::{self, Vec},
+};
+
+use serde::de::{self, value::BorrowedStrDeserializer, DeserializeSeed, IntoDeserializer, Visitor};
+
+use super::key::{KeyDeserializer, Reference};
+use crate::{
+    error::{Category, Cursor, Detail, Error, Position, Result},
+    util::{Depth, BYTE_ARRAY_TOKEN, INT_ARRAY_TOKEN, LONG_ARRAY_TOKEN},
+    Type,
+};
+
+/// A structure that deserializes SNBT, the textual form of NBT used in
+/// Minecraft's commands, into Rust values.
+pub struct Deserializer<'de> {
+    input: &'de str,
+    index: usize,
+    depth: Depth,
+}
+
+impl<'de> Deserializer<'de> {
+    /// Creates an SNBT deserializer over `input`.
+    ///
+    /// Typically it is more convenient to use
+    /// [`from_snbt_str`](crate::de::from_snbt_str) instead.
+    #[must_use]
+    #[inline]
+    pub fn new(input: &'de str) -> Self {
+        Deserializer {
+            input,
+            index: 0,
+            depth: Depth::default(),
+        }
+    }
+
+    /// Sets how deeply `List`s and `Compound`s may nest before
+    /// deserialization fails with [`Category::RecursionLimitExceeded`].
+    ///
+    /// The default limit is 512, the same as Minecraft's.
+    #[inline]
+    pub fn set_recursion_limit(&mut self, limit: usize) {
+        self.depth = Depth::new(limit);
+    }
+
+    /// Lets `List`s and `Compound`s nest arbitrarily deeply.
+    ///
+    /// Only do this for input from a trusted source; a deep enough input will
+    /// overflow the stack.
+    #[inline]
+    pub fn disable_recursion_limit(&mut self) {
+        self.depth = Depth::unbounded();
+    }
+
+    /// The `Deserializer::end` method should be called after a value has
+    /// been fully deserialized. This allows the `Deserializer` to validate
+    /// that only whitespace remains in the input.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of [`Category::InvalidInput`] if anything other than
+    /// whitespace remains in the input.
+    pub fn end(&mut self) -> Result<()> {
+        match self.peek_non_whitespace() {
+            None => Ok(()),
+            Some(_) => Err(self.error(
+                Category::InvalidInput,
+                "trailing characters after the root tag",
+                self.index,
+            )),
+        }
+    }
+
+    ////////////////////////////////////////////////////////////////////////////
+    // Errors
+    ////////////////////////////////////////////////////////////////////////////
+
+    /// Returns the line and column of the character at `index`, both
+    /// starting from 1.
+    fn cursor(&self, index: usize) -> Cursor {
+        let before = &self.input[..index];
+        let line = before.matches('\n').count() + 1;
+        let start = before.rfind('\n').map_or(0, |i| i + 1);
+        let column = before[start..].chars().count() + 1;
+
+        Cursor {
+            line: line as u64,
+            column: column as u64,
+        }
+    }
+
+    #[cold]
+    fn error<M>(&self, category: Category, message: M, index: usize) -> Error
+    where
+        M: Into<Cow<'static, str>>,
+    {
+        self.fix_position(Error::new(category, message), index)
+    }
+
+    #[cold]
+    fn fix_position(&self, error: Error, index: usize) -> Error {
+        error.fix_position(|| Position::Cursor(self.cursor(index)))
+    }
+
+    #[cold]
+    fn eof(&self) -> Error {
+        self.error(
+            Category::UnexpectedEof,
+            "unexpected end of input",
+            self.input.len(),
+        )
+    }
+
+    ////////////////////////////////////////////////////////////////////////////
+    // Parsing Methods
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[inline]
+    fn peek(&self) -> Option<char> {
+        self.input[self.index..].chars().next()
+    }
+
+    fn peek_non_whitespace(&mut self) -> Option<char> {
+        let rest = &self.input[self.index..];
+        let trimmed = rest.trim_start();
+        self.index += rest.len() - trimmed.len();
+        trimmed.chars().next()
+    }
+
+    /// Consumes `expected`, skipping any whitespace before it.
+    fn expect(&mut self, expected: char) -> Result<()> {
+        match self.peek_non_whitespace() {
+            Some(c) if c == expected => {
+                self.index += c.len_utf8();
+                Ok(())
+            }
+            Some(c) => Err(self.error(
+                Category::InvalidInput,
+                format!("expected `{expected}`, found `{c}`"),
+                self.index,
+            )),
+            None => Err(self.eof()),
+        }
+    }
+
+    /// Consumes `c` if it is the next character after any whitespace.
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek_non_whitespace() == Some(c) {
+            self.index += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Enters a `List` or `Compound` starting at `index`.
+    fn enter(&mut self, index: usize) -> Result<()> {
+        self.depth
+            .enter()
+            .map_err(|error| self.fix_position(error, index))
+    }
+
+    /// Parses a quoted or unquoted string, such as a compound key.
+    fn parse_str(&mut self) -> Result<Cow<'de, str>> {
+        match self.peek_non_whitespace() {
+            Some(quote @ ('"' | '\'')) => self.parse_quoted(quote),
+            Some(_) => {
+                let start = self.index;
+                let token = self.parse_unquoted();
+
+                if token.is_empty() {
+                    Err(self.error(Category::InvalidInput, "expected a string", start))
+                } else {
+                    Ok(Cow::Borrowed(token))
+                }
+            }
+            None => Err(self.eof()),
+        }
+    }
+
+    /// Parses a string delimited by `quote`, borrowing from the input unless
+    /// it contains escape sequences.
+    fn parse_quoted(&mut self, quote: char) -> Result<Cow<'de, str>> {
+        let input = self.input;
+        self.index += 1;
+        let start = self.index;
+        let mut owned: Option<String> = None;
+        let mut chunk = start;
+
+        loop {
+            let Some(c) = self.peek() else {
+                return Err(self.eof());
+            };
+
+            if c == quote {
+                let s = match owned {
+                    Some(mut s) => {
+                        s.push_str(&input[chunk..self.index]);
+                        Cow::Owned(s)
+                    }
+                    None => Cow::Borrowed(&input[start..self.index]),
+                };
+
+                self.index += 1;
+                return Ok(s);
+            }
+
+            if c == '\\' {
+                let s = owned.get_or_insert_with(String::new);
+                s.push_str(&input[chunk..self.index]);
+                self.index += 1;
+
+                match self.peek() {
+                    Some(escaped @ ('\\' | '"' | '\'')) => {
+                        s.push(escaped);
+                        self.index += 1;
+                    }
+                    Some(escaped) => {
+                        return Err(self.error(
+                            Category::InvalidInput,
+                            format!("invalid escape sequence `\\{escaped}`"),
+                            self.index - 1,
+                        ))
+                    }
+                    None => return Err(self.eof()),
+                }
+
+                chunk = self.index;
+                continue;
+            }
+
+            self.index += c.len_utf8();
+        }
+    }
+
+    /// Parses a run of characters that may appear outside of quotes.
+    fn parse_unquoted(&mut self) -> &'de str {
+        let input = self.input;
+        let start = self.index;
+        let len = input[start..]
+            .find(|c: char| !is_unquoted(c))
+            .unwrap_or(input.len() - start);
+
+        self.index += len;
+        &input[start..start + len]
+    }
+
+    /// Parses a number, boolean or unquoted string.
+    fn parse_scalar(&mut self) -> Result<Scalar<'de>> {
+        let start = self.index;
+        let token = self.parse_unquoted();
+
+        if token.is_empty() {
+            return match self.peek() {
+                Some(c) => Err(self.error(
+                    Category::InvalidInput,
+                    format!("expected a value, found `{c}`"),
+                    start,
+                )),
+                None => Err(self.eof()),
+            };
+        }
+
+        Scalar::parse(token).map_err(|ty| {
+            let message = format!("`{token}` is out of range for {ty}");
+            self.error(Category::InvalidInput, message, start)
+        })
+    }
+
+    /// Fails if the `,` just consumed is followed by `close` rather than by
+    /// another element or entry.
+    fn reject_trailing_comma(&mut self, close: char) -> Result<()> {
+        let comma = self.index - 1;
+
+        if self.peek_non_whitespace() == Some(close) {
+            let message = format!("trailing comma before `{close}`");
+            Err(self.error(Category::InvalidInput, message, comma))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Parses the `B;`, `I;` or `L;` prefix of an array, if there is one,
+    /// just after its opening bracket.
+    fn parse_array_prefix(&mut self) -> Option<Type> {
+        let rest = self.input[self.index..].trim_start();
+        let skipped = self.input.len() - self.index - rest.len();
+
+        let ty = match rest.as_bytes() {
+            [b'B', b';', ..] => Type::ByteArray,
+            [b'I', b';', ..] => Type::IntArray,
+            [b'L', b';', ..] => Type::LongArray,
+            _ => return None,
+        };
+
+        self.index += skipped + 2;
+        Some(ty)
+    }
+
+    /// Parses the elements of an array up to its closing bracket.
+    fn parse_array(&mut self, ty: Type) -> Result<Vec<i64>> {
+        let (element, min, max) = match ty {
+            Type::ByteArray => (Type::Byte, i8::MIN.into(), i8::MAX.into()),
+            Type::IntArray => (Type::Int, i32::MIN.into(), i32::MAX.into()),
+            _ => (Type::Long, i64::MIN, i64::MAX),
+        };
+
+        let mut elements = Vec::new();
+
+        if self.eat(']') {
+            return Ok(elements);
+        }
+
+        loop {
+            self.peek_non_whitespace();
+            let start = self.index;
+
+            let value = match self.parse_scalar()? {
+                Scalar::Bool(v) => i64::from(v),
+                Scalar::Byte(v) => i64::from(v),
+                Scalar::Short(v) => i64::from(v),
+                Scalar::Int(v) => i64::from(v),
+                Scalar::Long(v) => v,
+                scalar => {
+                    let index = elements.len();
+                    let message = format!("expected {element} in {ty}, found {}", scalar.ty());
+                    let error = self.error(Category::InvalidInput, message, start);
+                    return Err(error
+                        .with_detail(Detail::InvalidType {
+                            expected: Some(element),
+                            found: Some(scalar.ty()),
+                        })
+                        .prepend_path(index));
+                }
+            };
+
+            if value < min || value > max {
+                let message = format!("{value} is out of range for {element}");
+                let error = self.error(Category::InvalidInput, message, start);
+                return Err(error.prepend_path(elements.len()));
+            }
+
+            elements.push(value);
+
+            if !self.eat(',') {
+                self.expect(']')?;
+                return Ok(elements);
+            }
+
+            self.reject_trailing_comma(']')?;
+        }
+    }
+}
+
+impl<'de> Deserializer<'de> {
+    fn visit_list<V>(&mut self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let mut access = ListAccess {
+            de: self,
+            index: 0,
+            done: false,
+        };
+
+        let value = visitor.visit_seq(&mut access)?;
+
+        // Count the elements the visitor left behind, if any, to report how
+        // many the list really had.
+        let actual = access.index;
+        while de::SeqAccess::next_element::<de::IgnoredAny>(&mut access)?.is_some() {}
+        let declared = access.index;
+
+        if declared == actual {
+            return Ok(value);
+        }
+
+        let error: Error = de::Error::invalid_length(declared, &"fewer elements in list");
+        Err(error.with_detail(Detail::InvalidLength { declared, actual }))
+    }
+
+    fn visit_compound<V>(&mut self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let mut access = CompoundAccess {
+            de: self,
+            pending: None,
+            first: true,
+            done: false,
+        };
+
+        let value = visitor.visit_map(&mut access)?;
+
+        // Drain whatever entries the visitor chose not to look at.
+        while de::MapAccess::next_entry::<de::IgnoredAny, de::IgnoredAny>(&mut access)?.is_some() {}
+
+        Ok(value)
+    }
+}
+
+/// Returns `true` if `c` may appear in an unquoted string.
+#[inline]
+fn is_unquoted(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '+')
+}
+
+/// A number, boolean or unquoted string.
+enum Scalar<'de> {
+    Bool(bool),
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    String(&'de str),
+}
+
+impl<'de> Scalar<'de> {
+    /// Interprets an unquoted token the way Minecraft does.
+    ///
+    /// An integer with a type suffix, such as `128b`, has to fit that type,
+    /// or the type is returned as the error. Any other token that is not a
+    /// number or boolean is a string, as in Minecraft's legacy parser. That
+    /// includes an integer without a suffix that does not fit an `Int`, such
+    /// as `9999999999`, and a malformed number such as `1.0e`.
+    fn parse(token: &'de str) -> core::result::Result<Self, Type> {
+        match token {
+            "true" => return Ok(Scalar::Bool(true)),
+            "false" => return Ok(Scalar::Bool(false)),
+            _ => {}
+        }
+
+        let (body, suffix) = token.split_at(token.len() - 1);
+        match suffix {
+            "b" | "B" if is_integer(body) => body.parse().map(Scalar::Byte).map_err(|_| Type::Byte),
+            "s" | "S" if is_integer(body) => {
+                body.parse().map(Scalar::Short).map_err(|_| Type::Short)
+            }
+            "l" | "L" if is_integer(body) => body.parse().map(Scalar::Long).map_err(|_| Type::Long),
+            "f" | "F" if is_float(body) => body.parse().map(Scalar::Float).map_err(|_| Type::Float),
+            "d" | "D" if is_float(body) => {
+                body.parse().map(Scalar::Double).map_err(|_| Type::Double)
+            }
+            _ => {
+                let parsed = match token {
+                    _ if is_integer(token) => token.parse().ok().map(Scalar::Int),
+                    _ if is_float(token) => token.parse().ok().map(Scalar::Double),
+                    _ => None,
+                };
+                Ok(parsed.unwrap_or(Scalar::String(token)))
+            }
+        }
+    }
+
+    fn ty(&self) -> Type {
+        match self {
+            Scalar::Bool(_) | Scalar::Byte(_) => Type::Byte,
+            Scalar::Short(_) => Type::Short,
+            Scalar::Int(_) => Type::Int,
+            Scalar::Long(_) => Type::Long,
+            Scalar::Float(_) => Type::Float,
+            Scalar::Double(_) => Type::Double,
+            Scalar::String(_) => Type::String,
+        }
+    }
+
+    fn visit<'a, V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'a>,
+        'de: 'a,
+    {
+        match self {
+            Scalar::Bool(v) => visitor.visit_bool(v),
+            Scalar::Byte(v) => visitor.visit_i8(v),
+            Scalar::Short(v) => visitor.visit_i16(v),
+            Scalar::Int(v) => visitor.visit_i32(v),
+            Scalar::Long(v) => visitor.visit_i64(v),
+            Scalar::Float(v) => visitor.visit_f32(v),
+            Scalar::Double(v) => visitor.visit_f64(v),
+            Scalar::String(v) => visitor.visit_borrowed_str(v),
+        }
+    }
+}
+
+/// Returns `true` if `s` is an optionally signed run of digits.
+fn is_integer(s: &str) -> bool {
+    let digits = s.strip_prefix(['+', '-']).unwrap_or(s);
+    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Returns `true` if `s` is an optionally signed decimal number, possibly
+/// with an exponent, such as `1`, `-.5` or `1.5e-3`. This keeps words like
+/// `inf` and `NaN` strings.
+fn is_float(s: &str) -> bool {
+    let s = s.strip_prefix(['+', '-']).unwrap_or(s);
+    let (mantissa, exponent) = match s.find(['e', 'E']) {
+        Some(i) => (&s[..i], Some(&s[i + 1..])),
+        None => (s, None),
+    };
+
+    let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let is_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+
+    (!whole.is_empty() || !fraction.is_empty())
+        && is_digits(whole)
+        && is_digits(fraction)
+        && exponent.is_none_or(|exponent| {
+            let digits = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+            !digits.is_empty() && is_digits(digits)
+        })
+}
+
+/// Forwards to `deserialize_any`, recording the type of tag that `$method`
+/// corresponds to should the visitor reject the tag's type.
+macro_rules! deserialize_expecting {
+    ($($method:ident$(($($arg:ident: $ty:ty),*))? => $expected:ident,)*) => {
+        $(
+            #[inline]
+            fn $method<V>(self, $($(_: $ty,)*)? visitor: V) -> Result<V::Value>
+            where
+                V: Visitor<'de>,
+            {
+                self.deserialize_any(visitor)
+                    .map_err(|error| error.with_expected_type(Type::$expected))
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let Some(c) = self.peek_non_whitespace() else {
+            return Err(self.eof());
+        };
+
+        let start = self.index;
+
+        let (ty, result) = match c {
+            '{' => {
+                self.enter(start)?;
+                self.index += 1;
+                let result = self.visit_compound(visitor);
+                self.depth.leave();
+                (Type::Compound, result)
+            }
+            '[' => {
+                self.index += 1;
+
+                if let Some(ty) = self.parse_array_prefix() {
+                    let elements = self.parse_array(ty)?;
+                    let result = visitor.visit_map(ArrayAccess {
+                        ty,
+                        elements: Some(elements),
+                    });
+                    (ty, result)
+                } else {
+                    self.enter(start)?;
+                    let result = self.visit_list(visitor);
+                    self.depth.leave();
+                    (Type::List, result)
+                }
+            }
+            '"' | '\'' => {
+                let result = match self.parse_quoted(c)? {
+                    Cow::Borrowed(s) => visitor.visit_borrowed_str(s),
+                    Cow::Owned(s) => visitor.visit_string(s),
+                };
+                (Type::String, result)
+            }
+            _ => {
+                let scalar = self.parse_scalar()?;
+                (scalar.ty(), scalar.visit(visitor))
+            }
+        };
+
+        result.map_err(|error| self.fix_position(error.with_found_type(ty), start))
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let Some(c) = self.peek_non_whitespace() else {
+            return Err(self.eof());
+        };
+
+        if !is_unquoted(c) {
+            return self
+                .deserialize_any(visitor)
+                .map_err(|error| error.with_expected_type(Type::Byte));
+        }
+
+        let start = self.index;
+
+        let result = match self.parse_scalar()? {
+            Scalar::Byte(v) => visitor.visit_bool(v != 0),
+            scalar => scalar
+                .visit(visitor)
+                .map_err(|error| error.with_expected_type(Type::Byte)),
+        };
+
+        result.map_err(|error| self.fix_position(error, start))
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if self.peek_non_whitespace() != Some('[') {
+            return self
+                .deserialize_any(visitor)
+                .map_err(|error| error.with_expected_type(Type::ByteArray));
+        }
+
+        let start = self.index;
+        self.index += 1;
+
+        let result = match self.parse_array_prefix() {
+            Some(Type::ByteArray) => {
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                let bytes = self
+                    .parse_array(Type::ByteArray)?
+                    .into_iter()
+                    .map(|v| v as u8)
+                    .collect();
+                visitor.visit_byte_buf(bytes)
+            }
+            Some(ty) => visitor.visit_seq(ArraySeq::new(ty, self.parse_array(ty)?)),
+            None => {
+                self.index = start;
+                return self
+                    .deserialize_any(visitor)
+                    .map_err(|error| error.with_expected_type(Type::ByteArray));
+            }
+        };
+
+        result.map_err(|error| self.fix_position(error, start))
+    }
+
+    #[inline]
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_bytes(visitor)
+    }
+
+    #[inline]
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    #[inline]
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if self.peek_non_whitespace() == Some('[') {
+            let start = self.index;
+            self.index += 1;
+
+            if let Some(ty) = self.parse_array_prefix() {
+                let elements = self.parse_array(ty)?;
+                return visitor
+                    .visit_seq(ArraySeq::new(ty, elements))
+                    .map_err(|error| self.fix_position(error, start));
+            }
+
+            self.index = start;
+        }
+
+        self.deserialize_any(visitor)
+            .map_err(|error| error.with_expected_type(Type::List))
+    }
+
+    #[inline]
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    #[inline]
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let Some(c) = self.peek_non_whitespace() else {
+            return Err(self.eof());
+        };
+
+        let start = self.index;
+
+        let result = if c == '{' {
+            self.enter(start)?;
+            self.index += 1;
+            let result = visitor.visit_enum(VariantAccess { de: &mut *self });
+            self.depth.leave();
+            result
+        } else {
+            match self.parse_str()? {
+                Cow::Borrowed(s) => visitor.visit_enum(BorrowedStrDeserializer::new(s)),
+                Cow::Owned(s) => visitor.visit_enum(s.into_deserializer()),
+            }
+        };
+
+        result.map_err(|error| self.fix_position(error, start))
+    }
+
+    deserialize_expecting! {
+        deserialize_i8 => Byte,
+        deserialize_i16 => Short,
+        deserialize_i32 => Int,
+        deserialize_i64 => Long,
+        deserialize_u8 => Short,
+        deserialize_u16 => Int,
+        deserialize_u32 => Long,
+        deserialize_u64 => Long,
+        deserialize_f32 => Float,
+        deserialize_f64 => Double,
+        deserialize_char => String,
+        deserialize_str => String,
+        deserialize_string => String,
+        deserialize_map => Compound,
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]) => Compound,
+        deserialize_identifier => String,
+    }
+
+    serde::forward_to_deserialize_any! {
+        i128 u128 unit unit_struct ignored_any
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+struct ListAccess<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    /// The index of the next element, used to build error paths.
+    index: usize,
+    done: bool,
+}
+
+impl<'de> de::SeqAccess<'de> for ListAccess<'_, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        if self.done {
+            return Ok(None);
+        }
+
+        if self.index == 0 {
+            if self.de.eat(']') {
+                self.done = true;
+                return Ok(None);
+            }
+        } else if self.de.eat(',') {
+            self.de.reject_trailing_comma(']')?;
+        } else {
+            self.de.expect(']')?;
+            self.done = true;
+            return Ok(None);
+        }
+
+        let index = self.index;
+        self.index += 1;
+
+        seed.deserialize(&mut *self.de)
+            .map(Some)
+            .map_err(|error| error.prepend_path(index))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+struct CompoundAccess<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    /// The name of the entry whose name was just deserialized.
+    pending: Option<Cow<'de, str>>,
+    first: bool,
+    done: bool,
+}
+
+impl<'de> de::MapAccess<'de> for CompoundAccess<'_, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        if self.done {
+            return Ok(None);
+        }
+
+        if self.first {
+            self.first = false;
+
+            if self.de.eat('}') {
+                self.done = true;
+                return Ok(None);
+            }
+        } else if self.de.eat(',') {
+            self.de.reject_trailing_comma('}')?;
+        } else {
+            self.de.expect('}')?;
+            self.done = true;
+            return Ok(None);
+        }
+
+        self.de.peek_non_whitespace();
+        let start = self.de.index;
+        let name = self.de.parse_str()?;
+
+        let key = seed
+            .deserialize(KeyDeserializer::new(Reference::from(&name)))
+            .map_err(|error| {
+                let error = error.with_found_type(Type::String);
+                self.de.fix_position(error, start)
+            })?;
+
+        self.de.expect(':')?;
+        self.pending = Some(name);
+        Ok(Some(key))
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let name = self
+            .pending
+            .take()
+            .expect("`next_value_seed` called before `next_key_seed`");
+
+        seed.deserialize(&mut *self.de)
+            .map_err(|error| error.prepend_path(&*name))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Exposes an array as a single-entry map keyed by one of the private array
+/// tokens so that self-describing consumers can tell it apart from a `List`.
+struct ArrayAccess {
+    ty: Type,
+    elements: Option<Vec<i64>>,
+}
+
+impl<'de> de::MapAccess<'de> for ArrayAccess {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        if self.elements.is_none() {
+            return Ok(None);
+        }
+
+        let token = match self.ty {
+            Type::ByteArray => BYTE_ARRAY_TOKEN,
+            Type::IntArray => INT_ARRAY_TOKEN,
+            _ => LONG_ARRAY_TOKEN,
+        };
+
+        seed.deserialize(BorrowedStrDeserializer::new(token))
+            .map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let elements = self
+            .elements
+            .take()
+            .expect("`next_value_seed` called before `next_key_seed`");
+
+        seed.deserialize(ArraySeq::new(self.ty, elements))
+    }
+}
+
+/// The already parsed elements of an array.
+struct ArraySeq {
+    ty: Type,
+    elements: vec::IntoIter<i64>,
+    index: usize,
+}
+
+impl ArraySeq {
+    #[inline]
+    fn new(ty: Type, elements: Vec<i64>) -> Self {
+        ArraySeq {
+            ty,
+            elements: elements.into_iter(),
+            index: 0,
+        }
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for ArraySeq {
+    type Error = Error;
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let Some(element) = self.elements.next() else {
+            return Ok(None);
+        };
+
+        let index = self.index;
+        self.index += 1;
+
+        // The elements were range checked while parsing.
+        let result = match self.ty {
+            Type::ByteArray => seed.deserialize((element as i8).into_deserializer()),
+            Type::IntArray => seed.deserialize((element as i32).into_deserializer()),
+            _ => seed.deserialize(element.into_deserializer()),
+        };
+
+        result
+            .map(Some)
+            .map_err(|error: Error| error.prepend_path(index))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.elements.len())
+    }
+}
+
+impl<'de> de::Deserializer<'de> for ArraySeq {
+    type Error = Error;
+
+    #[inline]
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Deserializes an enum variant from a single-entry compound, where the name
+/// of the entry selects the variant and its value holds the variant's data.
+struct VariantAccess<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+}
+
+impl<'a, 'de> de::EnumAccess<'de> for VariantAccess<'a, 'de> {
+    type Error = Error;
+    type Variant = VariantPayload<'a, 'de>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant)>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        self.de.peek_non_whitespace();
+        let start = self.de.index;
+        let name = self.de.parse_str()?;
+
+        let value = seed
+            .deserialize(KeyDeserializer::new(Reference::from(&name)))
+            .map_err(|error| self.de.fix_position(error, start))?;
+
+        self.de.expect(':')?;
+
+        let variant = VariantPayload { de: self.de, name };
+
+        Ok((value, variant))
+    }
+}
+
+struct VariantPayload<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    name: Cow<'de, str>,
+}
+
+impl<'de> VariantPayload<'_, 'de> {
+    /// Deserializes the payload of the variant with `f`, then expects the
+    /// enclosing compound to end.
+    fn payload<F, T>(self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut Deserializer<'de>) -> Result<T>,
+    {
+        let value = f(&mut *self.de).map_err(|error| error.prepend_path(&*self.name))?;
+
+        if self.de.peek_non_whitespace() == Some('}') {
+            self.de.index += 1;
+            Ok(value)
+        } else {
+            let message = "expected enum variant to be the only entry in its compound";
+            Err(self.de.error(Category::InvalidData, message, self.de.index))
+        }
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for VariantPayload<'_, 'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        self.payload(|de| de::Deserialize::deserialize(de).map(|de::IgnoredAny| ()))
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.payload(|de| seed.deserialize(de))
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.payload(|de| de::Deserializer::deserialize_seq(de, visitor))
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.payload(|de| de::Deserializer::deserialize_map(de, visitor))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use alloc::{
+        string::{String, ToString},
+        vec,
+    };
+
+    use serde::de::{Deserialize, IgnoredAny};
+
+    use super::Deserializer;
+    use crate::{
+        de::from_snbt_str,
+        error::Category,
+        value::{Byte, Value},
+    };
+
+    /// SNBT for `depth` `Compound`s nested inside each other.
+    fn nested(depth: usize) -> String {
+        let mut s = "{a:".repeat(depth - 1);
+        s.push_str("{}");
+        s.push_str(&"}".repeat(depth - 1));
+        s
+    }
+
+    #[test]
+    fn recursion_limit_is_enforced() {
+        let input = nested(3);
+
+        let mut de = Deserializer::new(&input);
+        de.set_recursion_limit(3);
+        IgnoredAny::deserialize(&mut de).unwrap();
+
+        let mut de = Deserializer::new(&input);
+        de.set_recursion_limit(2);
+        let error = IgnoredAny::deserialize(&mut de).unwrap_err();
+        assert_eq!(error.category(), Category::RecursionLimitExceeded);
+    }
+
+    #[test]
+    fn recursion_limit_can_be_disabled() {
+        let input = nested(600);
+
+        let mut de = Deserializer::new(&input);
+        de.disable_recursion_limit();
+        IgnoredAny::deserialize(&mut de).unwrap();
+
+        let mut de = Deserializer::new(&input);
+        let error = IgnoredAny::deserialize(&mut de).unwrap_err();
+        assert_eq!(error.category(), Category::RecursionLimitExceeded);
+    }
+
+    #[test]
+    fn numbers_are_typed_by_their_suffix() {
+        let cases = [
+            ("12b", Value::Byte(Byte::Integer(12))),
+            ("-128B", Value::Byte(Byte::Integer(-128))),
+            ("true", Value::Byte(Byte::Boolean(true))),
+            ("12s", Value::Short(12)),
+            ("12", Value::Int(12)),
+            ("12l", Value::Long(12)),
+            ("1.5f", Value::Float(1.5)),
+            ("1.5", Value::Double(1.5)),
+            ("-.5e1d", Value::Double(-5.0)),
+            ("9999999999", Value::String("9999999999".to_string())),
+            ("1.0e", Value::String("1.0e".to_string())),
+            ("1.0ef", Value::String("1.0ef".to_string())),
+            ("NaN", Value::String("NaN".to_string())),
+            ("12x", Value::String("12x".to_string())),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(from_snbt_str::<Value>(input).unwrap(), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn suffixed_numbers_out_of_range_are_rejected() {
+        let cases = ["128b", "-129b", "32768s", "9223372036854775808l"];
+
+        for input in cases {
+            let error = from_snbt_str::<Value>(input).unwrap_err();
+            assert_eq!(error.category(), Category::InvalidInput, "{input}");
+            assert_eq!(error.column(), Some(1), "{input}");
+        }
+    }
+
+    #[test]
+    fn strings_can_be_quoted_and_escaped() {
+        let cases = [
+            ("abc", "abc"),
+            ("a.b-c_d+e", "a.b-c_d+e"),
+            ("\"a b\"", "a b"),
+            ("'a b'", "a b"),
+            ("\"12b\"", "12b"),
+            ("\"it's\"", "it's"),
+            ("'say \"hi\"'", "say \"hi\""),
+            (r#""a\"b\\c""#, "a\"b\\c"),
+            (r"'it\'s'", "it's"),
+        ];
+
+        for (input, expected) in cases {
+            let value = from_snbt_str::<Value>(input).unwrap();
+            assert_eq!(value, Value::String(expected.to_string()), "{input}");
+        }
+    }
+
+    #[test]
+    fn arrays_are_typed_by_their_prefix() {
+        let cases = [
+            ("[B;]", Value::ByteArray(vec![])),
+            (
+                "[B; 1b, true, -2]",
+                Value::ByteArray(vec![Byte::Integer(1), Byte::Integer(1), Byte::Integer(-2)]),
+            ),
+            ("[I;1,2s,3b]", Value::IntArray(vec![1, 2, 3])),
+            ("[L;1,2l]", Value::LongArray(vec![1, 2])),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(from_snbt_str::<Value>(input).unwrap(), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn errors_point_at_the_offending_token() {
+        let cases = [
+            ("[B;1,128]", Category::InvalidInput, 1, 6),
+            ("[I;1,9999999999]", Category::InvalidInput, 1, 6),
+            ("[L;1,1.5]", Category::InvalidInput, 1, 6),
+            ("{a:1,\n b:128b}", Category::InvalidInput, 2, 4),
+            ("[1,2,]", Category::InvalidInput, 1, 5),
+            ("{a:1,}", Category::InvalidInput, 1, 5),
+            ("[I;1,]", Category::InvalidInput, 1, 5),
+            ("'abc\\x'", Category::InvalidInput, 1, 5),
+            ("{a:1", Category::UnexpectedEof, 1, 5),
+        ];
+
+        for (input, category, line, column) in cases {
+            let error = from_snbt_str::<Value>(input).unwrap_err();
+            assert_eq!(error.category(), category, "{input}");
+            assert_eq!(
+                (error.line(), error.column()),
+                (Some(line), Some(column)),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn trailing_commas_are_named_in_the_error() {
+        for input in ["[1,]", "{a:1,}", "[B;1b, ]"] {
+            let error = from_snbt_str::<Value>(input).unwrap_err();
+            assert!(
+                error.to_string().contains("trailing comma"),
+                "{input}: {error}"
+            );
+        }
+    }
+
+    #[test]
+    fn array_errors_carry_the_element_index() {
+        let error = from_snbt_str::<Value>("{a:[B;1,2,300]}").unwrap_err();
+        assert_eq!(error.path().unwrap().to_string(), "a[2]");
+    }
+}
@@ -0,0 +1,437 @@
+//! TODO
+
+#[cfg(feature = "compression")]
+use alloc::vec::Vec;
+#[cfg(feature = "snbt")]
+use core::str;
+#[cfg(feature = "compression")]
+use std::io::Read as _;
+
+use serde::Deserialize;
+
+use super::binary::{Progress, Scanner};
+#[cfg(feature = "compression")]
+use crate::binary::Compression;
+use crate::{
+    binary::Endec,
+    error::{Category, Error, Result},
+};
+
+/// The most decompressed bytes [`sniff`] looks at.
+#[cfg(feature = "compression")]
+const SNIFF_LIMIT: usize = 64 * 1024;
+
+/// The length of the header Minecraft: Bedrock Edition puts in front of
+/// `level.dat`: a little-endian storage version followed by a little-endian
+/// length of the NBT that follows.
+const BEDROCK_HEADER_LEN: usize = 8;
+
+/// One of the NBT formats [`sniff`] can tell apart.
+///
+/// Only formats whose features are enabled can be detected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// Big-endian binary NBT, as used by Minecraft: Java Edition.
+    #[cfg(feature = "be")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "be")))]
+    Java,
+    /// Little-endian binary NBT, as Minecraft: Bedrock Edition stores on
+    /// disk.
+    #[cfg(feature = "le")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "le")))]
+    Bedrock,
+    /// [`VarInt`](crate::binary::VarInt) binary NBT, as Minecraft: Bedrock
+    /// Edition sends over the network.
+    #[cfg(feature = "varint")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "varint")))]
+    BedrockNetwork,
+    /// SNBT, the textual form of NBT used in Minecraft's commands.
+    #[cfg(feature = "snbt")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "snbt")))]
+    Snbt,
+}
+
+/// The most likely encoding of some input, as guessed by [`sniff`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Detected {
+    /// The format of the NBT itself, once decompressed.
+    pub format: Format,
+    /// The compression the NBT is wrapped in, if any.
+    #[cfg(feature = "compression")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "compression")))]
+    pub compression: Option<Compression>,
+    /// Whether the NBT is preceded by the 8-byte header Minecraft: Bedrock
+    /// Edition writes at the start of `level.dat`.
+    pub bedrock_header: bool,
+    /// How sure the guess is, from `0.0` to `1.0`.
+    ///
+    /// Input that decodes cleanly as exactly one format scores close to
+    /// `1.0`. The score drops when several formats fit equally well, such as
+    /// for an empty `Compound`, when only a prefix of compressed input could
+    /// be checked, or when the input is truncated or has trailing bytes.
+    pub confidence: f32,
+}
+
+/// Guesses the encoding of `bytes` from its structure.
+///
+/// Compressed input is recognized by its magic bytes and decompressed before
+/// looking any further. Binary input is checked by walking its tags with a
+/// [`Scanner`] for every enabled dialect, without decoding anything, and
+/// SNBT by looking for text that opens a `Compound` or `List`.
+///
+/// Returns `None` if the input does not look like any enabled format.
+#[must_use]
+pub fn sniff(bytes: &[u8]) -> Option<Detected> {
+    // Uncompressed input can start with bytes that pass for a zlib header,
+    // so it is only treated as compressed if it decompresses to binary NBT.
+    #[cfg(feature = "compression")]
+    if let Some(compression) = detect_compression(bytes) {
+        if let Some(inner) = decompress_prefix(bytes, compression) {
+            if let Some(mut detected) = sniff_binary(&inner, inner.len() < SNIFF_LIMIT) {
+                // A prefix that happens to be valid is weaker evidence than a
+                // complete root tag, so don't claim more than the prefix
+                // supports.
+                if inner.len() == SNIFF_LIMIT {
+                    detected.confidence *= 0.9;
+                }
+
+                detected.compression = Some(compression);
+                return Some(detected);
+            }
+        }
+    }
+
+    #[cfg(feature = "le")]
+    if let Some(detected) = sniff_bedrock_header(bytes) {
+        return Some(detected);
+    }
+
+    #[cfg(feature = "snbt")]
+    if looks_like_snbt(bytes) {
+        return Some(detected(Format::Snbt, 0.9));
+    }
+
+    sniff_binary(bytes, true)
+}
+
+/// Deserializes an instance of type `T` from bytes in whichever format
+/// [`sniff`] considers most likely.
+///
+/// # Errors
+///
+/// This conversion can fail if the format of the input cannot be detected,
+/// or for any of the reasons decoding in that format can fail.
+pub fn from_slice_auto<'de, T>(slice: &'de [u8]) -> Result<T>
+where
+    T: Deserialize<'de>,
+{
+    let Some(detected) = sniff(slice) else {
+        return Err(Error::new(
+            Category::InvalidInput,
+            "could not detect the format of the input",
+        ));
+    };
+
+    let body = if detected.bedrock_header {
+        &slice[BEDROCK_HEADER_LEN..]
+    } else {
+        slice
+    };
+
+    match detected.format {
+        #[cfg(feature = "be")]
+        Format::Java => decode::<T, crate::binary::BigEndian>(body, detected),
+        #[cfg(feature = "le")]
+        Format::Bedrock => decode::<T, crate::binary::LittleEndian>(body, detected),
+        #[cfg(feature = "varint")]
+        Format::BedrockNetwork => decode::<T, crate::binary::VarInt>(body, detected),
+        #[cfg(feature = "snbt")]
+        Format::Snbt => match str::from_utf8(strip_bom(body)) {
+            Ok(s) => super::from_snbt_str(s),
+            Err(_) => Err(Error::new(
+                Category::InvalidInput,
+                "SNBT input is not valid UTF-8",
+            )),
+        },
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+fn decode<'de, T, E>(slice: &'de [u8], detected: Detected) -> Result<T>
+where
+    T: Deserialize<'de>,
+    E: Endec,
+{
+    let options = super::Options::<E>::new();
+
+    #[cfg(feature = "compression")]
+    let options = match detected.compression {
+        Some(compression) => options.with_compression(compression),
+        None => options,
+    };
+
+    #[cfg(not(feature = "compression"))]
+    let _ = detected;
+
+    options.from_slice(slice)
+}
+
+#[cfg(feature = "compression")]
+fn detect_compression(bytes: &[u8]) -> Option<Compression> {
+    match *bytes {
+        [0x1f, 0x8b, ..] => Some(Compression::Gzip),
+        // The compression method must be deflate, and the header checksum
+        // must make the first two bytes a multiple of 31.
+        [cmf, flg, ..] if cmf & 0x0f == 8 && (u16::from(cmf) << 8 | u16::from(flg)) % 31 == 0 => {
+            Some(Compression::Zlib)
+        }
+        _ => None,
+    }
+}
+
+/// Decompresses up to [`SNIFF_LIMIT`] bytes from the start of `bytes`.
+#[cfg(feature = "compression")]
+fn decompress_prefix(bytes: &[u8], compression: Compression) -> Option<Vec<u8>> {
+    let mut buf = Vec::new();
+    let limit = SNIFF_LIMIT as u64;
+
+    let result = match compression {
+        Compression::Gzip => flate2::read::GzDecoder::new(bytes)
+            .take(limit)
+            .read_to_end(&mut buf),
+        Compression::Zlib => flate2::read::ZlibDecoder::new(bytes)
+            .take(limit)
+            .read_to_end(&mut buf),
+    };
+
+    result.ok().map(|_| buf)
+}
+
+/// Guesses which binary dialect `bytes` is in.
+///
+/// `whole` says whether `bytes` is all of the input rather than a prefix of
+/// it, which decides whether running out of bytes counts against a dialect.
+fn sniff_binary(bytes: &[u8], whole: bool) -> Option<Detected> {
+    // Ordered by how common each format is, so that the first one wins when
+    // several fit equally well.
+    let candidates: &[(Format, f32)] = &[
+        #[cfg(feature = "be")]
+        (
+            Format::Java,
+            score::<crate::binary::BigEndian>(bytes, whole),
+        ),
+        #[cfg(feature = "le")]
+        (
+            Format::Bedrock,
+            score::<crate::binary::LittleEndian>(bytes, whole),
+        ),
+        #[cfg(feature = "varint")]
+        (
+            Format::BedrockNetwork,
+            score::<crate::binary::VarInt>(bytes, whole),
+        ),
+    ];
+
+    let total: f32 = candidates.iter().map(|&(_, score)| score).sum();
+    let (format, best) = candidates.iter().copied().reduce(|best, candidate| {
+        if candidate.1 > best.1 {
+            candidate
+        } else {
+            best
+        }
+    })?;
+
+    if best == 0.0 {
+        return None;
+    }
+
+    // Share the confidence out between every format that fits, so that a
+    // tie between two formats halves it.
+    Some(detected(format, best * best / total))
+}
+
+fn detected(format: Format, confidence: f32) -> Detected {
+    Detected {
+        format,
+        #[cfg(feature = "compression")]
+        compression: None,
+        bedrock_header: false,
+        confidence,
+    }
+}
+
+/// Scores how well `bytes` fits the binary dialect `E`, from `0.0` if it
+/// cannot be that dialect to `1.0` if it is exactly one root tag in it.
+fn score<E>(bytes: &[u8], whole: bool) -> f32
+where
+    E: Endec,
+{
+    match Scanner::<E>::new().scan(bytes) {
+        Ok(Progress::Complete(len)) if len == bytes.len() => 1.0,
+        Ok(Progress::Complete(_)) => 0.2,
+        Ok(Progress::Incomplete { .. }) if !whole => 0.9,
+        Ok(Progress::Incomplete { .. }) => 0.1,
+        Err(_) => 0.0,
+    }
+}
+
+/// Recognizes the header Minecraft: Bedrock Edition puts in front of
+/// `level.dat`, which gives the exact length of the NBT after it.
+#[cfg(feature = "le")]
+fn sniff_bedrock_header(bytes: &[u8]) -> Option<Detected> {
+    let (header, body) = bytes.split_at_checked(BEDROCK_HEADER_LEN)?;
+    let len = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+
+    if usize::try_from(len).ok()? != body.len() {
+        return None;
+    }
+
+    match Scanner::<crate::binary::LittleEndian>::new().scan(body) {
+        Ok(Progress::Complete(len)) if len == body.len() => Some(Detected {
+            bedrock_header: true,
+            ..detected(Format::Bedrock, 1.0)
+        }),
+        _ => None,
+    }
+}
+
+/// Recognizes text that opens a `Compound` or `List`, neither of which can
+/// start binary NBT since `{` and `[` are not tag IDs.
+#[cfg(feature = "snbt")]
+fn looks_like_snbt(bytes: &[u8]) -> bool {
+    let bytes = strip_bom(bytes);
+    let start = bytes.iter().position(|byte| !byte.is_ascii_whitespace());
+
+    match start {
+        Some(start) => matches!(bytes[start], b'{' | b'[') && str::from_utf8(bytes).is_ok(),
+        None => false,
+    }
+}
+
+/// Strips the byte order mark some text editors put at the start of UTF-8
+/// files.
+#[cfg(feature = "snbt")]
+fn strip_bom(bytes: &[u8]) -> &[u8] {
+    bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(bytes)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(all(test, feature = "be"))]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::{from_slice_auto, sniff, Format};
+    use crate::ser;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Root {
+        x: i32,
+    }
+
+    const ROOT: Root = Root { x: 1 };
+
+    #[test]
+    fn detects_java() {
+        let bytes = ser::to_be_vec(&ROOT).unwrap();
+        let detected = sniff(&bytes).unwrap();
+
+        assert_eq!(detected.format, Format::Java);
+        assert!(!detected.bedrock_header);
+        assert!(detected.confidence > 0.5);
+        assert_eq!(from_slice_auto::<Root>(&bytes).unwrap(), ROOT);
+    }
+
+    #[test]
+    fn trailing_bytes_lower_the_confidence() {
+        let mut bytes = ser::to_be_vec(&ROOT).unwrap();
+        let whole = sniff(&bytes).unwrap().confidence;
+        bytes.push(0);
+        let detected = sniff(&bytes).unwrap();
+
+        assert_eq!(detected.format, Format::Java);
+        assert!(detected.confidence < whole);
+    }
+
+    #[test]
+    fn rejects_unknown_input() {
+        assert_eq!(sniff(&[99, 0, 0]), None);
+        assert!(from_slice_auto::<Root>(&[99, 0, 0]).is_err());
+    }
+
+    #[cfg(feature = "le")]
+    #[test]
+    fn detects_bedrock() {
+        let bytes = ser::to_le_vec(&ROOT).unwrap();
+        let detected = sniff(&bytes).unwrap();
+
+        assert_eq!(detected.format, Format::Bedrock);
+        assert!(!detected.bedrock_header);
+        assert_eq!(from_slice_auto::<Root>(&bytes).unwrap(), ROOT);
+    }
+
+    #[cfg(feature = "le")]
+    #[test]
+    fn detects_the_bedrock_level_dat_header() {
+        let nbt = ser::to_le_vec(&ROOT).unwrap();
+        let mut bytes = alloc::vec::Vec::new();
+        bytes.extend_from_slice(&10_u32.to_le_bytes());
+        bytes.extend_from_slice(&u32::try_from(nbt.len()).unwrap().to_le_bytes());
+        bytes.extend_from_slice(&nbt);
+
+        let detected = sniff(&bytes).unwrap();
+
+        assert_eq!(detected.format, Format::Bedrock);
+        assert!(detected.bedrock_header);
+        assert!((detected.confidence - 1.0).abs() < f32::EPSILON);
+        assert_eq!(from_slice_auto::<Root>(&bytes).unwrap(), ROOT);
+    }
+
+    #[cfg(feature = "snbt")]
+    #[test]
+    fn detects_snbt() {
+        let bytes = b"\xef\xbb\xbf  {x: 1}";
+        let detected = sniff(bytes).unwrap();
+
+        assert_eq!(detected.format, Format::Snbt);
+        assert_eq!(from_slice_auto::<Root>(bytes).unwrap(), ROOT);
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn detects_compression() {
+        use crate::binary::Compression;
+
+        for compression in [Compression::Gzip, Compression::Zlib] {
+            let bytes = ser::Options::java()
+                .with_compression(compression)
+                .to_vec(&ROOT)
+                .unwrap();
+            let detected = sniff(&bytes).unwrap();
+
+            assert_eq!(detected.format, Format::Java);
+            assert_eq!(detected.compression, Some(compression));
+            assert_eq!(from_slice_auto::<Root>(&bytes).unwrap(), ROOT);
+        }
+    }
+
+    #[cfg(all(feature = "le", feature = "compression"))]
+    #[test]
+    fn falls_back_when_input_only_looks_compressed() {
+        // A root `String` with a 29-byte name starts with `0x08 0x1d`, which
+        // is also a valid zlib header.
+        let mut bytes = alloc::vec![8, 29, 0];
+        bytes.extend_from_slice(&[b'a'; 29]);
+        bytes.extend_from_slice(&[2, 0, b'h', b'i']);
+
+        let detected = sniff(&bytes).unwrap();
+
+        assert_eq!(detected.format, Format::Bedrock);
+        assert_eq!(detected.compression, None);
+        assert_eq!(
+            from_slice_auto::<alloc::string::String>(&bytes).unwrap(),
+            "hi"
+        );
+    }
+}
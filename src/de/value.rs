@@ -0,0 +1,752 @@
+//! TODO
+
+use alloc::{string::String, vec};
+
+use serde::de::{
+    self, value::BorrowedStrDeserializer, DeserializeSeed, IntoDeserializer, Unexpected, Visitor,
+};
+
+use super::key::{KeyDeserializer, Reference};
+use crate::{
+    error::{Detail, Error, Result},
+    map,
+    util::{Depth, BYTE_ARRAY_TOKEN, INT_ARRAY_TOKEN, LONG_ARRAY_TOKEN},
+    Byte, Type, Value,
+};
+
+/// A structure that deserializes Rust values out of a [`Value`].
+///
+/// Deserializing straight from a `Value` applies the default recursion limit;
+/// this structure is only needed to change it.
+pub struct Deserializer {
+    value: Value,
+    depth: Depth,
+}
+
+impl Deserializer {
+    /// Creates a deserializer that consumes `value`.
+    ///
+    /// Typically it is more convenient to use
+    /// [`from_value`](crate::de::from_value) instead.
+    #[must_use]
+    #[inline]
+    pub fn new(value: Value) -> Self {
+        Deserializer {
+            value,
+            depth: Depth::default(),
+        }
+    }
+
+    /// Sets how deeply `List`s and `Compound`s may nest before
+    /// deserialization fails with
+    /// [`Category::RecursionLimitExceeded`](crate::error::Category::RecursionLimitExceeded).
+    ///
+    /// The default limit is 512, the same as Minecraft's.
+    #[inline]
+    pub fn set_recursion_limit(&mut self, limit: usize) {
+        self.depth = Depth::new(limit);
+    }
+
+    /// Lets `List`s and `Compound`s nest arbitrarily deeply.
+    ///
+    /// Only do this for values from a trusted source; a deep enough value will
+    /// overflow the stack.
+    #[inline]
+    pub fn disable_recursion_limit(&mut self) {
+        self.depth = Depth::unbounded();
+    }
+}
+
+macro_rules! deserialize_root {
+    ($($method:ident$(($($arg:ident: $ty:ty),*))?,)*) => {
+        $(
+            #[inline]
+            fn $method<V>(self, $($($arg: $ty,)*)? visitor: V) -> Result<V::Value>
+            where
+                V: Visitor<'de>,
+            {
+                let Deserializer { value, mut depth } = Deserializer::from(self);
+                ValueDeserializer::new(value, &mut depth).$method($($($arg,)*)? visitor)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer {
+    type Error = Error;
+
+    deserialize_root! {
+        deserialize_any,
+        deserialize_bool,
+        deserialize_i8,
+        deserialize_i16,
+        deserialize_i32,
+        deserialize_i64,
+        deserialize_i128,
+        deserialize_u8,
+        deserialize_u16,
+        deserialize_u32,
+        deserialize_u64,
+        deserialize_u128,
+        deserialize_f32,
+        deserialize_f64,
+        deserialize_char,
+        deserialize_str,
+        deserialize_string,
+        deserialize_bytes,
+        deserialize_byte_buf,
+        deserialize_option,
+        deserialize_unit,
+        deserialize_unit_struct(name: &'static str),
+        deserialize_newtype_struct(name: &'static str),
+        deserialize_seq,
+        deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+        deserialize_map,
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+        deserialize_identifier,
+        deserialize_ignored_any,
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Value {
+    type Error = Error;
+
+    deserialize_root! {
+        deserialize_any,
+        deserialize_bool,
+        deserialize_i8,
+        deserialize_i16,
+        deserialize_i32,
+        deserialize_i64,
+        deserialize_i128,
+        deserialize_u8,
+        deserialize_u16,
+        deserialize_u32,
+        deserialize_u64,
+        deserialize_u128,
+        deserialize_f32,
+        deserialize_f64,
+        deserialize_char,
+        deserialize_str,
+        deserialize_string,
+        deserialize_bytes,
+        deserialize_byte_buf,
+        deserialize_option,
+        deserialize_unit,
+        deserialize_unit_struct(name: &'static str),
+        deserialize_newtype_struct(name: &'static str),
+        deserialize_seq,
+        deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+        deserialize_map,
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+        deserialize_identifier,
+        deserialize_ignored_any,
+    }
+}
+
+impl From<Value> for Deserializer {
+    #[inline]
+    fn from(value: Value) -> Self {
+        Deserializer::new(value)
+    }
+}
+
+impl IntoDeserializer<'_, Error> for Value {
+    type Deserializer = Deserializer;
+
+    #[inline]
+    fn into_deserializer(self) -> Self::Deserializer {
+        Deserializer::new(self)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Deserializes a single value, sharing the recursion limit of the value
+/// enclosing it.
+struct ValueDeserializer<'a> {
+    value: Value,
+    depth: &'a mut Depth,
+}
+
+impl<'a> ValueDeserializer<'a> {
+    #[inline]
+    fn new(value: Value, depth: &'a mut Depth) -> Self {
+        ValueDeserializer { value, depth }
+    }
+
+    /// Runs `f` one level deeper, failing if that exceeds the recursion limit.
+    fn nested<F, T>(depth: &mut Depth, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut Depth) -> Result<T>,
+    {
+        depth.enter()?;
+        let result = f(depth);
+        depth.leave();
+        result
+    }
+}
+
+/// Forwards to `deserialize_any`, recording the type of tag that `$method`
+/// corresponds to should the visitor reject the value's type.
+macro_rules! deserialize_expecting {
+    ($($method:ident$(($($arg:ident: $ty:ty),*))? => $expected:ident,)*) => {
+        $(
+            #[inline]
+            fn $method<V>(self, $($(_: $ty,)*)? visitor: V) -> Result<V::Value>
+            where
+                V: Visitor<'de>,
+            {
+                self.deserialize_any(visitor)
+                    .map_err(|error| error.with_expected_type(Type::$expected))
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let ty = self.value.ty();
+        let depth = self.depth;
+
+        let result = match self.value {
+            Value::Byte(Byte::Boolean(v)) => visitor.visit_bool(v),
+            Value::Byte(Byte::Integer(v)) => visitor.visit_i8(v),
+            Value::Short(v) => visitor.visit_i16(v),
+            Value::Int(v) => visitor.visit_i32(v),
+            Value::Long(v) => visitor.visit_i64(v),
+            Value::Float(v) => visitor.visit_f32(v),
+            Value::Double(v) => visitor.visit_f64(v),
+            Value::String(v) => visitor.visit_string(v),
+            Value::List(v) => Self::nested(depth, |depth| {
+                visit_seq(v.into_iter(), depth, visitor, "fewer elements in list")
+            }),
+            Value::Compound(v) => Self::nested(depth, |depth| {
+                visitor.visit_map(CompoundAccess {
+                    iter: v.into_iter(),
+                    depth,
+                    pending: None,
+                })
+            }),
+            Value::ByteArray(v) => visitor.visit_map(ArrayAccess::new(Array::Byte(v.into_iter()))),
+            Value::IntArray(v) => visitor.visit_map(ArrayAccess::new(Array::Int(v.into_iter()))),
+            Value::LongArray(v) => visitor.visit_map(ArrayAccess::new(Array::Long(v.into_iter()))),
+        };
+
+        result.map_err(|error| error.with_found_type(ty))
+    }
+
+    #[inline]
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if let Value::Byte(byte) = self.value {
+            visitor
+                .visit_bool(byte.to_bool())
+                .map_err(|error: Error| error.with_found_type(Type::Byte))
+        } else {
+            self.deserialize_any(visitor)
+                .map_err(|error| error.with_expected_type(Type::Byte))
+        }
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if let Value::ByteArray(array) = self.value {
+            #[allow(clippy::cast_sign_loss)]
+            let bytes = array.into_iter().map(|byte| byte.to_i8() as u8).collect();
+            visitor
+                .visit_byte_buf(bytes)
+                .map_err(|error: Error| error.with_found_type(Type::ByteArray))
+        } else {
+            self.deserialize_any(visitor)
+                .map_err(|error| error.with_expected_type(Type::ByteArray))
+        }
+    }
+
+    #[inline]
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_bytes(visitor)
+    }
+
+    #[inline]
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    #[inline]
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let (ty, array) = match self.value {
+            Value::ByteArray(v) => (Type::ByteArray, Array::Byte(v.into_iter())),
+            Value::IntArray(v) => (Type::IntArray, Array::Int(v.into_iter())),
+            Value::LongArray(v) => (Type::LongArray, Array::Long(v.into_iter())),
+            _ => {
+                return self
+                    .deserialize_any(visitor)
+                    .map_err(|error| error.with_expected_type(Type::List))
+            }
+        };
+
+        let mut depth = Depth::unbounded();
+        visit_seq(array, &mut depth, visitor, "fewer elements in array")
+            .map_err(|error| error.with_found_type(ty))
+    }
+
+    #[inline]
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    #[inline]
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let ty = self.value.ty();
+
+        let result = match self.value {
+            Value::String(variant) => visitor.visit_enum(variant.into_deserializer()),
+            Value::Compound(compound) if compound.len() == 1 => Self::nested(self.depth, |depth| {
+                let (name, value) = compound.into_iter().next().expect("one entry");
+                visitor.visit_enum(VariantAccess { name, value, depth })
+            }),
+            Value::Compound(_) => Err(de::Error::invalid_value(
+                Unexpected::Map,
+                &"a Compound with a single entry",
+            )),
+            _ => {
+                let unexp = Unexpected::Other(ty.as_str());
+                Err(de::Error::invalid_type(unexp, &"a String or Compound"))
+            }
+        };
+
+        result.map_err(|error| error.with_found_type(ty))
+    }
+
+    #[inline]
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        drop(self);
+        visitor.visit_unit()
+    }
+
+    deserialize_expecting! {
+        deserialize_i8 => Byte,
+        deserialize_i16 => Short,
+        deserialize_i32 => Int,
+        deserialize_i64 => Long,
+        deserialize_u8 => Short,
+        deserialize_u16 => Int,
+        deserialize_u32 => Long,
+        deserialize_u64 => Long,
+        deserialize_f32 => Float,
+        deserialize_f64 => Double,
+        deserialize_char => String,
+        deserialize_str => String,
+        deserialize_string => String,
+        deserialize_map => Compound,
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]) => Compound,
+        deserialize_identifier => String,
+    }
+
+    serde::forward_to_deserialize_any! {
+        i128 u128 unit unit_struct
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Visits the elements of a `List` or array, failing if the visitor leaves
+/// some of them untouched.
+fn visit_seq<'de, I, V>(
+    iter: I,
+    depth: &mut Depth,
+    visitor: V,
+    expected: &'static str,
+) -> Result<V::Value>
+where
+    I: ExactSizeIterator<Item = Value>,
+    V: Visitor<'de>,
+{
+    let len = iter.len();
+    let mut access = ListAccess {
+        iter,
+        depth,
+        index: 0,
+    };
+
+    let value = visitor.visit_seq(&mut access)?;
+    let remaining = access.iter.len();
+
+    if remaining == 0 {
+        Ok(value)
+    } else {
+        let error: Error = de::Error::invalid_length(len, &expected);
+        Err(error.with_detail(Detail::InvalidLength {
+            declared: len,
+            actual: len - remaining,
+        }))
+    }
+}
+
+struct ListAccess<'a, I> {
+    iter: I,
+    depth: &'a mut Depth,
+    /// The index of the next element, used to build error paths.
+    index: usize,
+}
+
+impl<'de, I> de::SeqAccess<'de> for ListAccess<'_, I>
+where
+    I: ExactSizeIterator<Item = Value>,
+{
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let Some(value) = self.iter.next() else {
+            return Ok(None);
+        };
+
+        let index = self.index;
+        self.index += 1;
+
+        seed.deserialize(ValueDeserializer::new(value, self.depth))
+            .map(Some)
+            .map_err(|error| error.prepend_path(index))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+struct CompoundAccess<'a> {
+    iter: map::IntoIter<String, Value>,
+    depth: &'a mut Depth,
+    /// The name and value of the entry whose name was just deserialized.
+    pending: Option<(String, Value)>,
+}
+
+impl<'de> de::MapAccess<'de> for CompoundAccess<'_> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let Some((name, value)) = self.iter.next() else {
+            return Ok(None);
+        };
+
+        let key = seed
+            .deserialize(KeyDeserializer::new(Reference::Copied(&name)))
+            .map_err(|error| error.with_found_type(Type::String))?;
+
+        self.pending = Some((name, value));
+        Ok(Some(key))
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let (name, value) = self
+            .pending
+            .take()
+            .expect("`next_value_seed` called before `next_key_seed`");
+
+        seed.deserialize(ValueDeserializer::new(value, self.depth))
+            .map_err(|error| error.prepend_path(name))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// The elements of one of the array types, yielded as [`Value`]s.
+enum Array {
+    Byte(vec::IntoIter<Byte>),
+    Int(vec::IntoIter<i32>),
+    Long(vec::IntoIter<i64>),
+}
+
+impl Iterator for Array {
+    type Item = Value;
+
+    #[inline]
+    fn next(&mut self) -> Option<Value> {
+        match self {
+            Array::Byte(iter) => iter.next().map(Value::Byte),
+            Array::Int(iter) => iter.next().map(Value::Int),
+            Array::Long(iter) => iter.next().map(Value::Long),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Array::Byte(iter) => iter.size_hint(),
+            Array::Int(iter) => iter.size_hint(),
+            Array::Long(iter) => iter.size_hint(),
+        }
+    }
+}
+
+impl ExactSizeIterator for Array {}
+
+/// Exposes an array as a single-entry map keyed by one of the private array
+/// tokens so that self-describing consumers can tell it apart from a `List`.
+struct ArrayAccess {
+    array: Option<Array>,
+    key: bool,
+}
+
+impl ArrayAccess {
+    #[inline]
+    fn new(array: Array) -> Self {
+        ArrayAccess {
+            array: Some(array),
+            key: true,
+        }
+    }
+}
+
+impl<'de> de::MapAccess<'de> for ArrayAccess {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        if !self.key {
+            return Ok(None);
+        }
+        self.key = false;
+
+        let token = match self.array {
+            Some(Array::Byte(_)) => BYTE_ARRAY_TOKEN,
+            Some(Array::Int(_)) => INT_ARRAY_TOKEN,
+            Some(Array::Long(_)) => LONG_ARRAY_TOKEN,
+            None => unreachable!(),
+        };
+
+        seed.deserialize(BorrowedStrDeserializer::new(token))
+            .map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let array = self
+            .array
+            .take()
+            .expect("`next_value_seed` called before `next_key_seed`");
+
+        seed.deserialize(ArrayDeserializer { array })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        Some(1)
+    }
+}
+
+/// Deserializes the elements of one of the array types as a sequence.
+struct ArrayDeserializer {
+    array: Array,
+}
+
+impl<'de> de::Deserializer<'de> for ArrayDeserializer {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        // Array elements never nest, so there is no depth to track.
+        let mut depth = Depth::unbounded();
+        visit_seq(self.array, &mut depth, visitor, "fewer elements in array")
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Deserializes an enum variant from a single-entry compound, where the name
+/// of the entry selects the variant and its value holds the variant's data.
+struct VariantAccess<'a> {
+    name: String,
+    value: Value,
+    depth: &'a mut Depth,
+}
+
+impl<'de, 'a> de::EnumAccess<'de> for VariantAccess<'a> {
+    type Error = Error;
+    type Variant = VariantPayload<'a>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant)>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(KeyDeserializer::new(Reference::Copied(&self.name)))?;
+        let payload = VariantPayload {
+            name: self.name,
+            value: self.value,
+            depth: self.depth,
+        };
+
+        Ok((variant, payload))
+    }
+}
+
+struct VariantPayload<'a> {
+    name: String,
+    value: Value,
+    depth: &'a mut Depth,
+}
+
+impl<'de> de::VariantAccess<'de> for VariantPayload<'_> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(ValueDeserializer::new(self.value, self.depth))
+            .map_err(|error| error.prepend_path(self.name))
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let de = ValueDeserializer::new(self.value, self.depth);
+        de::Deserializer::deserialize_seq(de, visitor)
+            .map_err(|error| error.prepend_path(self.name))
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let de = ValueDeserializer::new(self.value, self.depth);
+        de::Deserializer::deserialize_map(de, visitor)
+            .map_err(|error| error.prepend_path(self.name))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use serde::de::Deserialize;
+
+    use super::Deserializer;
+    use crate::{error::Category, Compound, Value};
+
+    // An ignored value is dropped without being visited, so these tests
+    // deserialize a `Value` to walk the whole tree.
+
+    /// `depth` `Compound`s nested inside each other.
+    fn nested(depth: usize) -> Value {
+        let mut value = Value::Compound(Compound::new());
+
+        for _ in 1..depth {
+            let mut compound = Compound::new();
+            compound.insert("a".into(), value);
+            value = Value::Compound(compound);
+        }
+
+        value
+    }
+
+    #[test]
+    fn recursion_limit_is_enforced() {
+        let mut de = Deserializer::new(nested(3));
+        de.set_recursion_limit(3);
+        Value::deserialize(de).unwrap();
+
+        let mut de = Deserializer::new(nested(3));
+        de.set_recursion_limit(2);
+        let error = Value::deserialize(de).unwrap_err();
+        assert_eq!(error.category(), Category::RecursionLimitExceeded);
+    }
+
+    #[test]
+    fn recursion_limit_can_be_disabled() {
+        let mut de = Deserializer::new(nested(3));
+        de.set_recursion_limit(2);
+        de.disable_recursion_limit();
+        Value::deserialize(de).unwrap();
+    }
+}
@@ -0,0 +1,146 @@
+//! TODO
+
+use alloc::vec::Vec;
+use core::fmt;
+
+/// The number of bytes rendered on each line of a [`Hexdump`].
+const BYTES_PER_LINE: usize = 16;
+
+/// A snapshot of the input surrounding the byte at which an [`Error`]
+/// occurred.
+///
+/// The [`Display`] implementation renders the snapshot in the familiar
+/// `hexdump -C` layout and marks the offending byte with carets:
+///
+/// ```text
+/// 00000000  0a 00 00 03 00 05 43 6f  75 6e 74 00 00 00 2a 0d  |......Count...*.|
+///                                                         ^^
+/// 00000010  00 00                                             |..|
+/// ```
+///
+/// [`Error`]: super::Error
+/// [`Display`]: fmt::Display
+#[derive(Clone, PartialEq, Eq)]
+pub struct Hexdump {
+    start: u64,
+    bytes: Vec<u8>,
+    offset: u64,
+}
+
+impl Hexdump {
+    /// Captures the line of `input` containing `offset`, along with the lines
+    /// immediately before and after it.
+    pub(crate) fn capture(input: &[u8], offset: u64) -> Self {
+        let index = usize::try_from(offset)
+            .unwrap_or(usize::MAX)
+            .min(input.len());
+        let line = index / BYTES_PER_LINE;
+        let start = line.saturating_sub(1) * BYTES_PER_LINE;
+        let end = input.len().min((line + 2) * BYTES_PER_LINE);
+
+        Hexdump {
+            start: start as u64,
+            bytes: input[start..end].to_vec(),
+            offset,
+        }
+    }
+
+    /// Returns the absolute offset of the first captured byte.
+    #[must_use]
+    #[inline]
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    /// Returns the captured bytes.
+    #[must_use]
+    #[inline]
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns the absolute offset of the offending byte.
+    ///
+    /// This may be one past the last captured byte if the error was caused by
+    /// the input ending early.
+    #[must_use]
+    #[inline]
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+}
+
+impl fmt::Debug for Hexdump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Hexdump")
+            .field("start", &self.start)
+            .field("offset", &self.offset)
+            .field("bytes", &self.bytes)
+            .finish()
+    }
+}
+
+impl fmt::Display for Hexdump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut marked = false;
+
+        for (i, chunk) in self.bytes.chunks(BYTES_PER_LINE).enumerate() {
+            let line_start = self.start + (i * BYTES_PER_LINE) as u64;
+
+            if i > 0 {
+                f.write_str("\n")?;
+            }
+
+            write!(f, "{line_start:08x} ")?;
+
+            for column in 0..BYTES_PER_LINE {
+                if column == BYTES_PER_LINE / 2 {
+                    f.write_str(" ")?;
+                }
+
+                match chunk.get(column) {
+                    Some(byte) => write!(f, " {byte:02x}")?,
+                    None => f.write_str("   ")?,
+                }
+            }
+
+            f.write_str("  |")?;
+
+            for &byte in chunk {
+                let c = if byte.is_ascii_graphic() || byte == b' ' {
+                    char::from(byte)
+                } else {
+                    '.'
+                };
+                write!(f, "{c}")?;
+            }
+
+            f.write_str("|")?;
+
+            let Some(column) = self.offset.checked_sub(line_start) else {
+                continue;
+            };
+
+            #[allow(clippy::cast_possible_truncation)]
+            let column = column as usize;
+
+            if column < chunk.len() {
+                // 8 characters for the offset, then 3 for every column before
+                // the marked one, plus the gap between the two halves.
+                let padding = 10 + column * 3 + usize::from(column >= BYTES_PER_LINE / 2);
+                write!(f, "\n{:padding$}^^", "")?;
+                marked = true;
+            }
+        }
+
+        if !marked {
+            if !self.bytes.is_empty() {
+                f.write_str("\n")?;
+            }
+
+            write!(f, "{:08x}  ^^ (end of input)", self.offset)?;
+        }
+
+        Ok(())
+    }
+}
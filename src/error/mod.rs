@@ -1,16 +1,27 @@
 //! TODO
 
+mod hexdump;
 mod path;
 
-pub(crate) use self::path::Path;
+pub use self::{
+    hexdump::Hexdump,
+    path::{Path, Segment},
+};
 
-use alloc::borrow::Cow;
-use core::{fmt, mem, result};
+use alloc::{
+    borrow::Cow,
+    boxed::Box,
+    format,
+    string::{String, ToString},
+};
+use core::{fmt, result};
 #[cfg(feature = "std")]
-use std::{backtrace::Backtrace, io::ErrorKind};
+use std::{backtrace::Backtrace, cell::Cell, io};
 
 use serde::{de, ser};
 
+use crate::Type;
+
 /// TODO
 pub type Result<T> = result::Result<T, Error>;
 
@@ -46,22 +57,288 @@ impl Error {
                 category,
                 message: message.into(),
                 position,
+                path: Path::new(),
+                detail: None,
+                hexdump: None,
+                #[cfg(feature = "std")]
+                io: None,
                 #[cfg(feature = "std")]
-                backtrace: Backtrace::capture(),
+                backtrace: if CAPTURE_BACKTRACES.with(Cell::get) {
+                    Backtrace::capture()
+                } else {
+                    Backtrace::disabled()
+                },
             }),
         }
     }
 
-    /// TODO
-    pub(crate) fn attach_path(mut self, path: &mut Path) -> Self {
+    /// Returns the [`Category`] this error falls under.
+    #[must_use]
+    #[inline]
+    pub fn category(&self) -> Category {
+        self.inner.category
+    }
+
+    /// Returns `true` if this error was caused by a failure to read or write
+    /// bytes from the underlying I/O stream.
+    ///
+    /// The original [`io::Error`] is available through [`Error::io_error`].
+    ///
+    /// [`io::Error`]: std::io::Error
+    #[must_use]
+    #[inline]
+    pub fn is_io(&self) -> bool {
+        self.inner.category == Category::Io
+    }
+
+    /// Returns `true` if this error was caused by the input ending before a
+    /// complete value could be decoded.
+    ///
+    /// For streams this usually means the other end went away mid-tag, which
+    /// is worth telling apart from a malformed tag.
+    #[must_use]
+    #[inline]
+    pub fn is_eof(&self) -> bool {
+        self.inner.category == Category::UnexpectedEof
+    }
+
+    /// Returns how many more bytes the input needed at the very least to get
+    /// any further, if this error was caused by the input ending partway
+    /// through a tag.
+    ///
+    /// Readers cannot tell how much of a failed read was available, so for
+    /// them this is always `Some(1)`.
+    #[must_use]
+    #[inline]
+    pub fn bytes_needed(&self) -> Option<usize> {
+        match self.inner.detail {
+            Some(Detail::Incomplete { needed }) => Some(needed),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if this error was caused by input that is not valid in
+    /// the format being decoded, such as an unknown tag ID, a negative length,
+    /// nesting deeper than the recursion limit or exceeding the memory quota.
+    ///
+    /// Errors of [`Category::RecursionLimitExceeded`] and
+    /// [`Category::QuotaExceeded`] count as syntax errors because they come
+    /// from the shape of the input rather than from the type being decoded.
+    /// Use [`Error::category`] to tell them apart from other malformed input.
+    #[must_use]
+    #[inline]
+    pub fn is_syntax(&self) -> bool {
+        matches!(
+            self.inner.category,
+            Category::InvalidInput | Category::RecursionLimitExceeded | Category::QuotaExceeded
+        )
+    }
+
+    /// Returns `true` if this error was caused by well-formed input that does
+    /// not match the structure of the type being decoded or encoded, such as
+    /// a tag of the wrong type or a missing field.
+    #[must_use]
+    #[inline]
+    pub fn is_data(&self) -> bool {
+        matches!(
+            self.inner.category,
+            Category::InvalidData | Category::Custom
+        )
+    }
+
+    /// Returns the absolute byte offset into the input at which this error
+    /// occurred, if it was raised while decoding a binary format.
+    #[must_use]
+    #[inline]
+    pub fn byte_offset(&self) -> Option<u64> {
+        match self.inner.position {
+            Position::Byte(offset) => Some(offset),
+            _ => None,
+        }
+    }
+
+    /// Returns the one-based line at which this error occurred, if it was
+    /// raised while parsing a textual format.
+    #[must_use]
+    #[inline]
+    pub fn line(&self) -> Option<u64> {
+        match self.inner.position {
+            Position::Cursor(Cursor { line, .. }) => Some(line),
+            _ => None,
+        }
+    }
+
+    /// Returns the one-based column at which this error occurred, if it was
+    /// raised while parsing a textual format.
+    #[must_use]
+    #[inline]
+    pub fn column(&self) -> Option<u64> {
+        match self.inner.position {
+            Position::Cursor(Cursor { column, .. }) => Some(column),
+            _ => None,
+        }
+    }
+
+    /// Returns the NBT path of the tag that caused this error.
+    ///
+    /// Returns `None` if the error was raised by the root tag itself, or
+    /// outside of any tag.
+    #[must_use]
+    #[inline]
+    pub fn path(&self) -> Option<&Path> {
+        if self.inner.path.is_empty() {
+            None
+        } else {
+            Some(&self.inner.path)
+        }
+    }
+
+    /// Returns machine-readable details about what went wrong, if any were
+    /// recorded.
+    #[must_use]
+    #[inline]
+    pub fn detail(&self) -> Option<&Detail> {
+        self.inner.detail.as_ref()
+    }
+
+    /// Returns the name of the `Compound` entry this error is about.
+    ///
+    /// This is the unknown, missing or duplicate key for errors about keys,
+    /// and otherwise the name of the entry the error occurred in, if the
+    /// error occurred directly within a `Compound`.
+    #[must_use]
+    pub fn key(&self) -> Option<&str> {
+        match &self.inner.detail {
+            Some(Detail::UnknownKey { key }) => return Some(key),
+            Some(Detail::MissingKey { key } | Detail::DuplicateKey { key }) => return Some(key),
+            _ => {}
+        }
+
+        match self.inner.path.segments().last() {
+            Some(Segment::Key(key)) => Some(key),
+            _ => None,
+        }
+    }
+
+    /// Returns the index of the `List` or array element this error occurred
+    /// in, if the error occurred directly within a `List` or array.
+    #[must_use]
+    pub fn index(&self) -> Option<usize> {
+        match self.inner.path.segments().last() {
+            Some(Segment::Index(index)) => Some(*index),
+            _ => None,
+        }
+    }
+
+    /// Returns a snapshot of the input surrounding [`byte_offset`], if the
+    /// input was available when this error was created.
+    ///
+    /// This is only ever captured when decoding from a slice, and not at all
+    /// once backtraces have been disabled.
+    ///
+    /// [`byte_offset`]: Error::byte_offset
+    #[must_use]
+    #[inline]
+    pub fn hexdump(&self) -> Option<&Hexdump> {
+        self.inner.hexdump.as_ref()
+    }
+
+    /// Returns the I/O error this error was created from, if any.
+    #[cfg(feature = "std")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+    #[must_use]
+    #[inline]
+    pub fn io_error(&self) -> Option<&io::Error> {
+        self.inner.io.as_ref()
+    }
+
+    /// Returns the backtrace captured when this error was created.
+    ///
+    /// Whether the backtrace actually contains any frames is governed by the
+    /// `RUST_BACKTRACE` and `RUST_LIB_BACKTRACE` environment variables; see
+    /// [`Backtrace::capture`] for more information. Decoders that had
+    /// backtraces disabled never capture one, regardless of the environment.
+    #[cfg(feature = "std")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+    #[inline]
+    pub fn backtrace(&self) -> &Backtrace {
+        &self.inner.backtrace
+    }
+
+    /// Sets the position of this error to the one returned by `f` if no
+    /// position has been recorded yet.
+    pub(crate) fn fix_position<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce() -> Position,
+    {
         if matches!(self.inner.position, Position::None) {
-            let path = mem::take(path);
-            self.inner.position = Position::Path(path);
+            self.inner.position = f();
+        }
+
+        self
+    }
+
+    /// Attaches a hexdump of `input` surrounding this error's byte offset if
+    /// it has one and no hexdump has been attached yet.
+    pub(crate) fn attach_hexdump(mut self, input: &[u8]) -> Self {
+        if let (Position::Byte(offset), None) = (&self.inner.position, &self.inner.hexdump) {
+            self.inner.hexdump = Some(Hexdump::capture(input, *offset));
         }
 
         self
     }
 
+    /// Attaches `detail` to this error, replacing any already recorded.
+    pub(crate) fn with_detail(mut self, detail: Detail) -> Self {
+        self.inner.detail = Some(detail);
+        self
+    }
+
+    /// Records the type of the tag that caused an [`Detail::InvalidType`]
+    /// error, unless it is already known.
+    ///
+    /// This only applies while the error has no path, i.e. while it is still
+    /// being propagated out of the tag that caused it.
+    pub(crate) fn with_found_type(mut self, ty: Type) -> Self {
+        if let (Some(Detail::InvalidType { found, .. }), true) =
+            (&mut self.inner.detail, self.inner.path.is_empty())
+        {
+            found.get_or_insert(ty);
+        }
+
+        self
+    }
+
+    /// Records the type a tag was expected to have for an
+    /// [`Detail::InvalidType`] error, unless it is already known.
+    ///
+    /// Like [`Error::with_found_type`], this only applies while the error has
+    /// no path.
+    pub(crate) fn with_expected_type(mut self, ty: Type) -> Self {
+        if let (Some(Detail::InvalidType { expected, .. }), true) =
+            (&mut self.inner.detail, self.inner.path.is_empty())
+        {
+            expected.get_or_insert(ty);
+        }
+
+        self
+    }
+
+    /// Records that this error occurred within the tag reached through
+    /// `segment`, relative to the path recorded so far.
+    ///
+    /// Deserializers call this on every error passing through a `Compound`
+    /// entry or `List` element, so the full path is assembled as the error
+    /// propagates back up to the root.
+    #[cold]
+    pub(crate) fn prepend_path<S>(mut self, segment: S) -> Self
+    where
+        S: Into<Segment>,
+    {
+        self.inner.path.push_front(segment.into());
+        self
+    }
+
     ////////////////////////////////////////////////////////////////////////////
     //
     ////////////////////////////////////////////////////////////////////////////
@@ -70,32 +347,179 @@ impl Error {
     #[cold]
     #[inline(never)]
     #[track_caller]
-    pub(crate) fn recursion_limit_exceeded(path: &mut Path) -> Self {
-        let path = mem::take(path);
+    pub(crate) fn recursion_limit_exceeded() -> Self {
+        Error::new(Category::RecursionLimitExceeded, "recursion limit exceeded")
+    }
 
-        Error::with_position(
-            Category::RecursionLimitExceeded,
-            "recursion limit exceeded",
-            Position::Path(path),
-        )
+    #[cfg(feature = "binary")]
+    #[must_use]
+    #[cold]
+    #[inline(never)]
+    #[track_caller]
+    pub(crate) fn quota_exceeded(quota: usize) -> Self {
+        let message = format!("memory quota of {quota} bytes exceeded");
+        Error::new(Category::QuotaExceeded, message)
+    }
+
+    /// Wraps an error raised by the underlying reader or writer.
+    #[must_use]
+    #[cold]
+    #[inline(never)]
+    #[track_caller]
+    #[cfg_attr(not(feature = "std"), allow(clippy::needless_pass_by_value))]
+    pub(crate) fn io(error: zc_io::Error) -> Self {
+        #[cfg(feature = "std")]
+        {
+            Error::from(io::Error::from(error))
+        }
+
+        #[cfg(not(feature = "std"))]
+        {
+            Error::new(Category::Io, error.to_string())
+        }
     }
 }
 
 impl fmt::Debug for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        todo!()
+        let mut debug = f.debug_struct("Error");
+        debug
+            .field("category", &self.inner.category)
+            .field("message", &self.inner.message);
+
+        match &self.inner.position {
+            Position::None => {}
+            Position::Byte(offset) => {
+                debug.field("byte_offset", offset);
+            }
+            Position::Cursor(cursor) => {
+                debug
+                    .field("line", &cursor.line)
+                    .field("column", &cursor.column);
+            }
+        }
+
+        if !self.inner.path.is_empty() {
+            let path = &self.inner.path;
+            debug.field("path", &format_args!("{path}"));
+        }
+
+        if let Some(detail) = &self.inner.detail {
+            debug.field("detail", detail);
+        }
+
+        if let Some(hexdump) = &self.inner.hexdump {
+            debug.field("hexdump", hexdump);
+        }
+
+        #[cfg(feature = "std")]
+        debug.field("backtrace", &self.inner.backtrace);
+
+        debug.finish()
     }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        todo!()
+        f.write_str(&self.inner.message)?;
+
+        let mut separator = " at ";
+
+        if !self.inner.path.is_empty() {
+            write!(f, " at `{}`", self.inner.path)?;
+            separator = ", ";
+        }
+
+        match &self.inner.position {
+            Position::None => Ok(()),
+            Position::Byte(offset) => write!(f, "{separator}byte offset {offset}"),
+            Position::Cursor(cursor) => {
+                write!(
+                    f,
+                    "{separator}line {} column {}",
+                    cursor.line, cursor.column
+                )
+            }
+        }
     }
 }
 
+impl core::error::Error for Error {
+    #[cfg(feature = "std")]
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match &self.inner.io {
+            Some(error) => Some(error),
+            None => None,
+        }
+    }
+}
+
+/// Wraps an [`io::Error`], keeping it as the [`source`] of the new error.
+///
+/// Errors of [`io::ErrorKind::UnexpectedEof`] are categorized as
+/// [`Category::UnexpectedEof`]; all others as [`Category::Io`].
+///
+/// [`source`]: std::error::Error::source
 #[cfg(feature = "std")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
-impl std::error::Error for Error {}
+impl From<io::Error> for Error {
+    #[cold]
+    #[track_caller]
+    fn from(error: io::Error) -> Self {
+        let category = match error.kind() {
+            io::ErrorKind::UnexpectedEof => Category::UnexpectedEof,
+            _ => Category::Io,
+        };
+
+        let mut result = Error::new(category, error.to_string());
+        result.inner.io = Some(error);
+        result
+    }
+}
+
+/// Converts an error back into an [`io::Error`], so that it can be returned
+/// from I/O adapters.
+///
+/// The original [`io::Error`] is returned as is if there is one. Otherwise
+/// errors of [`Category::UnexpectedEof`] become [`io::ErrorKind::UnexpectedEof`]
+/// and everything else becomes [`io::ErrorKind::InvalidData`].
+#[cfg(feature = "std")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+impl From<Error> for io::Error {
+    fn from(mut error: Error) -> Self {
+        if let Some(io) = error.inner.io.take() {
+            return io;
+        }
+
+        let kind = match error.inner.category {
+            Category::UnexpectedEof => io::ErrorKind::UnexpectedEof,
+            _ => io::ErrorKind::InvalidData,
+        };
+
+        io::Error::new(kind, error)
+    }
+}
+
+/// Wraps an error raised by a [`zc_io`] reader or writer, such as one passed
+/// to an [`Endec`](crate::binary::Endec).
+impl From<zc_io::Error> for Error {
+    #[cold]
+    #[track_caller]
+    fn from(error: zc_io::Error) -> Self {
+        Error::io(error)
+    }
+}
+
+impl ser::Error for Error {
+    #[track_caller]
+    #[cold]
+    fn custom<T>(message: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        Error::new(Category::Custom, message.to_string())
+    }
+}
 
 impl de::Error for Error {
     #[track_caller]
@@ -111,7 +535,10 @@ impl de::Error for Error {
     #[cold]
     fn invalid_type(unexp: de::Unexpected, exp: &dyn de::Expected) -> Self {
         let message = format!("invalid type: {unexp}, expected {exp}");
-        Error::new(Category::InvalidData, message)
+        Error::new(Category::InvalidData, message).with_detail(Detail::InvalidType {
+            expected: None,
+            found: None,
+        })
     }
 
     #[track_caller]
@@ -124,6 +551,8 @@ impl de::Error for Error {
     #[track_caller]
     #[cold]
     fn invalid_length(len: usize, exp: &dyn de::Expected) -> Self {
+        // Only the caller knows how much of the input was consumed, so the
+        // `InvalidLength` detail is attached where both lengths are known.
         let message = format!("invalid length: {len}, expected {exp}");
         Error::new(Category::InvalidData, message)
     }
@@ -139,7 +568,9 @@ impl de::Error for Error {
                 OneOf { names: expected }
             )
         };
-        Error::new(Category::InvalidData, message)
+        Error::new(Category::InvalidData, message).with_detail(Detail::UnknownVariant {
+            variant: variant.into(),
+        })
     }
 
     #[track_caller]
@@ -154,18 +585,21 @@ impl de::Error for Error {
             )
         };
         Error::new(Category::InvalidData, message)
+            .with_detail(Detail::UnknownKey { key: field.into() })
     }
 
     #[track_caller]
     #[cold]
     fn missing_field(field: &'static str) -> Self {
         Error::new(Category::InvalidData, format!("missing field `{field}`"))
+            .with_detail(Detail::MissingKey { key: field })
     }
 
     #[track_caller]
     #[cold]
     fn duplicate_field(field: &'static str) -> Self {
         Error::new(Category::InvalidData, format!("duplicate field `{field}`"))
+            .with_detail(Detail::DuplicateKey { key: field })
     }
 }
 
@@ -173,6 +607,11 @@ struct Inner {
     category: Category,
     message: Cow<'static, str>,
     position: Position,
+    path: Path,
+    detail: Option<Detail>,
+    hexdump: Option<Hexdump>,
+    #[cfg(feature = "std")]
+    io: Option<io::Error>,
     #[cfg(feature = "std")]
     backtrace: Backtrace,
 }
@@ -180,30 +619,136 @@ struct Inner {
 pub(crate) enum Position {
     None,
     Byte(u64),
+    #[cfg_attr(not(feature = "snbt"), allow(dead_code))]
     Cursor(Cursor),
-    Path(Path),
 }
 
 pub(crate) struct Cursor {
-    line: u64,
-    column: u64,
+    pub(crate) line: u64,
+    pub(crate) column: u64,
 }
 
-/// TODO
+/// Categorizes the cause of an [`Error`].
+///
+/// See [`Error::is_io`], [`Error::is_eof`], [`Error::is_syntax`] and
+/// [`Error::is_data`] for coarser groupings.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Category {
-    /// TODO
+    /// A custom error raised by a `Serialize` or `Deserialize` implementation.
     Custom,
-    /// TODO
+    /// The input was well-formed but did not match the type being decoded.
     InvalidData,
-    /// TODO
+    /// The input was not valid in the format being decoded.
     InvalidInput,
-    /// TODO
+    /// The underlying I/O stream failed.
     Io,
-    /// TODO
+    /// The input ended before a complete value could be decoded.
     UnexpectedEof,
-    /// TODO
+    /// The input nested deeper than the recursion limit allows.
     RecursionLimitExceeded,
+    /// Decoding the input would have allocated more memory than the quota
+    /// allows.
+    QuotaExceeded,
+}
+
+/// Machine-readable details about an [`Error`], for callers that want to react
+/// to or present an error without parsing its message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Detail {
+    /// A tag did not have the type the target expected.
+    ///
+    /// Either type is `None` if it could not be determined, such as when the
+    /// target accepts several types or the error did not come from a tag.
+    InvalidType {
+        /// The type the target expected.
+        expected: Option<Type>,
+        /// The type the tag actually had.
+        found: Option<Type>,
+    },
+    /// A `List` or array held a different number of elements than the target
+    /// consumed.
+    InvalidLength {
+        /// The length declared by the input.
+        declared: usize,
+        /// The number of elements actually consumed.
+        actual: usize,
+    },
+    /// A `Compound` held an entry the target does not know about.
+    UnknownKey {
+        /// The name of the entry.
+        key: String,
+    },
+    /// A `Compound` was missing an entry the target requires.
+    MissingKey {
+        /// The name of the entry.
+        key: &'static str,
+    },
+    /// A `Compound` held an entry the target had already seen.
+    DuplicateKey {
+        /// The name of the entry.
+        key: &'static str,
+    },
+    /// An enum variant name did not match any of the target's variants.
+    UnknownVariant {
+        /// The name of the variant.
+        variant: String,
+    },
+    /// The input ended partway through a tag.
+    ///
+    /// `needed` is a lower bound: once that many more bytes are available,
+    /// decoding gets further, but it may still need more after that.
+    Incomplete {
+        /// How many more bytes are needed at the very least.
+        needed: usize,
+    },
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    static CAPTURE_BACKTRACES: Cell<bool> = const { Cell::new(true) };
+}
+
+/// Restores whether backtraces are captured on this thread when dropped.
+///
+/// Errors are frequently created by `Deserialize` implementations that know
+/// nothing about the decoder driving them, so a decoder that does not want
+/// backtraces has to say so for the whole thread while it is running.
+#[cfg(feature = "binary")]
+pub(crate) struct BacktraceGuard {
+    #[cfg(feature = "std")]
+    previous: bool,
+}
+
+#[cfg(feature = "binary")]
+impl BacktraceGuard {
+    /// Sets whether errors created on this thread capture a backtrace until
+    /// the returned guard is dropped.
+    #[inline]
+    pub(crate) fn new(capture: bool) -> Self {
+        #[cfg(feature = "std")]
+        {
+            BacktraceGuard {
+                previous: CAPTURE_BACKTRACES.with(|cell| cell.replace(capture)),
+            }
+        }
+
+        #[cfg(not(feature = "std"))]
+        {
+            let _ = capture;
+            BacktraceGuard {}
+        }
+    }
+}
+
+#[cfg(all(feature = "binary", feature = "std"))]
+impl Drop for BacktraceGuard {
+    #[inline]
+    fn drop(&mut self) {
+        CAPTURE_BACKTRACES.with(|cell| cell.set(self.previous));
+    }
 }
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -231,10 +776,205 @@ impl fmt::Display for OneOf {
                     if i > 0 {
                         write!(formatter, ", ")?;
                     }
-                    write!(formatter, "`{}`", alt)?;
+                    write!(formatter, "`{alt}`")?;
                 }
                 Ok(())
             }
         }
     }
 }
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(all(test, feature = "be"))]
+mod tests {
+    use alloc::{format, string::ToString, vec, vec::Vec};
+
+    use serde::{Deserialize, Serialize};
+
+    use super::{Detail, Error};
+    use crate::{de::from_be_slice, error::Category, ser::to_be_vec, Type, Value};
+
+    #[test]
+    fn invalid_type_records_both_types() {
+        #[derive(Serialize)]
+        struct Input {
+            x: &'static str,
+        }
+
+        #[derive(Debug, Deserialize)]
+        struct Output {
+            #[allow(dead_code)]
+            x: i32,
+        }
+
+        let bytes = to_be_vec(&Input { x: "oops" }).unwrap();
+        let error = from_be_slice::<Output>(&bytes).unwrap_err();
+
+        assert_eq!(
+            error.detail(),
+            Some(&Detail::InvalidType {
+                expected: Some(Type::Int),
+                found: Some(Type::String),
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_length_records_the_length() {
+        #[derive(Serialize)]
+        struct Input {
+            pos: Vec<i32>,
+        }
+
+        #[derive(Debug, Deserialize)]
+        struct Output {
+            #[allow(dead_code)]
+            pos: (i32, i32),
+        }
+
+        let bytes = to_be_vec(&Input {
+            pos: vec![1, 2, 3, 4],
+        })
+        .unwrap();
+        let error = from_be_slice::<Output>(&bytes).unwrap_err();
+
+        assert_eq!(error.path().unwrap().to_string(), "pos");
+        assert_eq!(
+            error.detail(),
+            Some(&Detail::InvalidLength {
+                declared: 4,
+                actual: 2,
+            })
+        );
+
+        // Too few elements are reported by serde before the list is drained,
+        // so only the message is available.
+        let bytes = to_be_vec(&Input { pos: vec![1] }).unwrap();
+        let error = from_be_slice::<Output>(&bytes).unwrap_err();
+
+        assert_eq!(error.category(), Category::InvalidData);
+        assert_eq!(error.detail(), None);
+    }
+
+    #[test]
+    fn errors_carry_the_path_to_the_offending_tag() {
+        #[derive(Debug, Serialize, Deserialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct Level<T> {
+            level: Chunk<T>,
+        }
+
+        #[derive(Debug, Serialize, Deserialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct Chunk<T> {
+            sections: Vec<Section<T>>,
+        }
+
+        #[derive(Debug, Serialize, Deserialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct Section<T> {
+            block_states: T,
+        }
+
+        let section = |value| Section {
+            block_states: value,
+        };
+        let input = Level {
+            level: Chunk {
+                sections: vec![
+                    section(Value::Long(1)),
+                    section(Value::Long(2)),
+                    section(Value::Long(3)),
+                    section(Value::String("oops".into())),
+                ],
+            },
+        };
+
+        let bytes = to_be_vec(&input).unwrap();
+        let error = from_be_slice::<Level<i64>>(&bytes).unwrap_err();
+
+        let path = error.path().unwrap();
+        assert_eq!(path.to_string(), "Level.Sections[3].BlockStates");
+        assert_eq!(
+            error.to_string(),
+            format!("invalid type: string \"oops\", expected i64 at `{path}`, byte offset 96"),
+        );
+    }
+
+    #[test]
+    fn predicates_match_their_category() {
+        // (category, is_io, is_eof, is_syntax, is_data)
+        let cases = [
+            (Category::Custom, false, false, false, true),
+            (Category::InvalidData, false, false, false, true),
+            (Category::InvalidInput, false, false, true, false),
+            (Category::Io, true, false, false, false),
+            (Category::UnexpectedEof, false, true, false, false),
+            (Category::RecursionLimitExceeded, false, false, true, false),
+            (Category::QuotaExceeded, false, false, true, false),
+        ];
+
+        for (category, io, eof, syntax, data) in cases {
+            let error = Error::new(category, "oops");
+            assert_eq!(error.category(), category);
+            assert_eq!(
+                (
+                    error.is_io(),
+                    error.is_eof(),
+                    error.is_syntax(),
+                    error.is_data()
+                ),
+                (io, eof, syntax, data),
+                "{category:?}",
+            );
+        }
+    }
+
+    #[test]
+    fn decoding_errors_have_the_expected_category() {
+        // An unknown tag ID.
+        let error = from_be_slice::<Value>(&[13, 0, 0]).unwrap_err();
+        assert!(error.is_syntax());
+
+        // A `Compound` cut off after its header.
+        let error = from_be_slice::<Value>(&[10, 0, 0]).unwrap_err();
+        assert!(error.is_eof());
+
+        // A `String` where an `Int` was expected.
+        let bytes = to_be_vec(&Value::String("oops".into())).unwrap();
+        let error = from_be_slice::<i32>(&bytes).unwrap_err();
+        assert!(error.is_data());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn source_is_the_underlying_io_error() {
+        use std::{error::Error as _, io};
+
+        use crate::de::from_be_reader;
+
+        struct Broken;
+
+        impl io::Read for Broken {
+            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::other("disk on fire"))
+            }
+        }
+
+        let error = from_be_reader::<_, Value>(Broken).unwrap_err();
+        assert!(error.is_io());
+        let source = error.source().unwrap().downcast_ref::<io::Error>().unwrap();
+        assert_eq!(source.kind(), io::ErrorKind::Other);
+        assert_eq!(source.to_string(), "disk on fire");
+        assert!(core::ptr::eq(source, error.io_error().unwrap()));
+
+        let error = from_be_reader::<_, Value>(&[10, 0][..]).unwrap_err();
+        assert!(error.is_eof());
+        let source = error.source().unwrap().downcast_ref::<io::Error>().unwrap();
+        assert_eq!(source.kind(), io::ErrorKind::UnexpectedEof);
+
+        let error = Error::new(Category::InvalidInput, "oops");
+        assert!(error.source().is_none());
+    }
+}
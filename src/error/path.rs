@@ -0,0 +1,197 @@
+//! TODO
+
+use alloc::{borrow::ToOwned, string::String, vec::Vec};
+use core::{fmt, slice};
+
+/// The location of a tag within an NBT tree, as an [NBT path].
+///
+/// The [`Display`] implementation renders the path in the same syntax
+/// Minecraft's commands accept, e.g. `Level.Sections[3].BlockStates`. Names
+/// containing characters with special meaning in NBT paths are quoted.
+///
+/// [NBT path]: https://minecraft.fandom.com/wiki/NBT_path_format
+/// [`Display`]: fmt::Display
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Path {
+    segments: Vec<Segment>,
+}
+
+impl Path {
+    /// Creates an empty path, which refers to the root tag.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Path {
+            segments: Vec::new(),
+        }
+    }
+
+    /// Returns the segments of this path, starting from the root tag.
+    #[must_use]
+    #[inline]
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Returns an iterator over the segments of this path, starting from the
+    /// root tag.
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<'_, Segment> {
+        self.segments.iter()
+    }
+
+    /// Returns the number of segments in this path.
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Returns `true` if this path refers to the root tag.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Inserts `segment` at the start of this path.
+    ///
+    /// Paths are built from the inside out as an error propagates up through
+    /// the tags enclosing the one that caused it.
+    pub(crate) fn push_front(&mut self, segment: Segment) {
+        self.segments.insert(0, segment);
+    }
+}
+
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                Segment::Key(key) => {
+                    if i > 0 {
+                        f.write_str(".")?;
+                    }
+
+                    write_key(f, key)?;
+                }
+                Segment::Index(index) => write!(f, "[{index}]")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> IntoIterator for &'a Path {
+    type Item = &'a Segment;
+    type IntoIter = slice::Iter<'a, Segment>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// A single step of a [`Path`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Segment {
+    /// The entry of a `Compound` with the given name.
+    Key(String),
+    /// The element of a `List` or array at the given index.
+    Index(usize),
+}
+
+impl From<&str> for Segment {
+    #[inline]
+    fn from(key: &str) -> Self {
+        Segment::Key(key.to_owned())
+    }
+}
+
+impl From<String> for Segment {
+    #[inline]
+    fn from(key: String) -> Self {
+        Segment::Key(key)
+    }
+}
+
+impl From<usize> for Segment {
+    #[inline]
+    fn from(index: usize) -> Self {
+        Segment::Index(index)
+    }
+}
+
+/// Writes `key` as it would appear in an NBT path, quoting it if any of its
+/// characters would otherwise end the name early.
+fn write_key(f: &mut fmt::Formatter<'_>, key: &str) -> fmt::Result {
+    let needs_quotes = key.is_empty()
+        || key
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '"' | '\'' | '.' | '[' | ']' | '{' | '}'));
+
+    if !needs_quotes {
+        return f.write_str(key);
+    }
+
+    f.write_str("\"")?;
+
+    for c in key.chars() {
+        if matches!(c, '"' | '\\') {
+            f.write_str("\\")?;
+        }
+
+        write!(f, "{c}")?;
+    }
+
+    f.write_str("\"")
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::{Path, Segment};
+
+    fn path<const N: usize>(segments: [Segment; N]) -> Path {
+        let mut path = Path::new();
+        for segment in segments.into_iter().rev() {
+            path.push_front(segment);
+        }
+        path
+    }
+
+    #[test]
+    fn displays_keys_and_indices() {
+        let path = path([
+            "Level".into(),
+            "Sections".into(),
+            3.into(),
+            "BlockStates".into(),
+        ]);
+
+        assert_eq!(path.to_string(), "Level.Sections[3].BlockStates");
+    }
+
+    #[test]
+    fn displays_a_leading_index() {
+        let path = path([0.into(), "id".into()]);
+
+        assert_eq!(path.to_string(), "[0].id");
+    }
+
+    #[test]
+    fn quotes_keys_with_special_characters() {
+        let path = path(["a b".into(), "".into(), "say \"hi\"".into(), "x.y".into()]);
+
+        assert_eq!(path.to_string(), r#""a b".""."say \"hi\""."x.y""#);
+    }
+
+    #[test]
+    fn root_path_is_empty() {
+        assert!(Path::new().is_empty());
+        assert_eq!(Path::new().to_string(), "");
+    }
+}
@@ -6,14 +6,26 @@
 
 extern crate alloc;
 
+#[cfg(feature = "binary")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "binary")))]
+pub mod binary;
 pub mod de;
 pub mod error;
+pub mod ser;
 mod util;
 mod value;
 
+/// The I/O traits the binary formats read and write through, re-exported so
+/// that custom sources and sinks can implement the same version of them.
+#[cfg(feature = "binary")]
+#[doc(no_inline)]
+pub use zc_io;
+
 #[doc(inline)]
 pub use self::value::{
+    array::{self, IntArrayRef, LongArrayRef},
     list::{self, List},
     map::{self, Map},
-    Byte, ByteArray, Compound, IntArray, LongArray, Type, Value,
+    Byte, ByteArray, Compound, CompoundRef, IntArray, LongArray, Named, NamedValue, Type, Value,
+    ValueRef,
 };
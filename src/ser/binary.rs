@@ -0,0 +1,2058 @@
+//! TODO
+
+use alloc::{format, string::String, vec::Vec};
+use core::{marker::PhantomData, mem};
+
+use serde::ser::{self, Impossible, Serialize};
+
+use super::key::KeySerializer;
+use crate::{
+    binary::{write_all, Endec},
+    error::{Category, Detail, Error, Result},
+    util::{
+        Depth, BYTE_ARRAY_TOKEN, INT_ARRAY_TOKEN, LONG_ARRAY_TOKEN, NAMED_TOKEN, RAW_NBT_TOKEN,
+    },
+    Type,
+};
+
+/// A structure for serializing Rust values into binary NBT.
+///
+/// The dialect of binary NBT is selected with `E`, e.g.
+/// [`BigEndian`](crate::binary::BigEndian) for Minecraft: Java Edition.
+pub struct Serializer<W, E> {
+    writer: W,
+    depth: Depth,
+    root_name: String,
+    unnamed_root: bool,
+    endec: PhantomData<E>,
+}
+
+impl<W, E> Serializer<W, E>
+where
+    W: zc_io::Write,
+    E: Endec,
+{
+    /// Creates a binary NBT serializer that writes into `writer`.
+    ///
+    /// Any [`zc_io::Write`] works, including a `Vec<u8>` without the `std`
+    /// feature.
+    ///
+    /// Typically it is more convenient to use one of these functions instead:
+    ///
+    /// - [`to_be_vec`](crate::ser::to_be_vec)
+    /// - [`to_be_writer`](crate::ser::to_be_writer)
+    /// - [`Options`](crate::ser::Options), to configure it first
+    #[must_use]
+    #[inline]
+    pub fn new(writer: W) -> Self {
+        Serializer {
+            writer,
+            depth: Depth::default(),
+            root_name: String::new(),
+            unnamed_root: false,
+            endec: PhantomData,
+        }
+    }
+
+    /// Unwraps this `Serializer`, returning the underlying writer.
+    #[must_use]
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    /// Sets how deeply `List`s and `Compound`s may nest before serialization
+    /// fails with [`Category::RecursionLimitExceeded`].
+    ///
+    /// The default limit is 512, the same as Minecraft's.
+    #[inline]
+    pub fn set_recursion_limit(&mut self, limit: usize) {
+        self.depth = Depth::new(limit);
+    }
+
+    /// Lets `List`s and `Compound`s nest arbitrarily deeply.
+    ///
+    /// Only do this for values from a trusted source; a deep enough value
+    /// will overflow the stack.
+    #[inline]
+    pub fn disable_recursion_limit(&mut self) {
+        self.depth = Depth::unbounded();
+    }
+
+    /// Sets the name written ahead of the root tag.
+    ///
+    /// The name is empty by default, which is what Minecraft writes for
+    /// almost every file and packet; `level.dat` and some schematic formats
+    /// are the exception.
+    #[inline]
+    pub fn set_root_name<S>(&mut self, name: S)
+    where
+        S: Into<String>,
+    {
+        self.root_name = name.into();
+    }
+
+    /// Writes the root tag as an ID followed directly by its payload, with
+    /// no name in between.
+    ///
+    /// This is how Minecraft: Java Edition sends NBT over the network since
+    /// 1.20.2. The name of a [`Named`](crate::Named) is not written.
+    #[inline]
+    pub fn write_unnamed_root(&mut self) {
+        self.unnamed_root = true;
+    }
+
+    #[inline]
+    fn write_id(&mut self, ty: Type) -> Result<()> {
+        write_all(&mut self.writer, &[ty.id()])
+    }
+
+    #[inline]
+    fn write_str(&mut self, s: &str) -> Result<()> {
+        let bytes = E::encode_str(s);
+        E::write_string_len(&mut self.writer, bytes.len())?;
+        write_all(&mut self.writer, &bytes)
+    }
+
+    #[inline]
+    fn write_end(&mut self) -> Result<()> {
+        write_all(&mut self.writer, &[0])
+    }
+}
+
+macro_rules! serialize_root {
+    ($($method:ident($($arg:ident: $ty:ty),*) -> $ok:ty,)*) => {
+        $(
+            #[inline]
+            fn $method(self, $($arg: $ty),*) -> Result<$ok> {
+                TagSerializer::new(self, Header::Root).$method($($arg),*)
+            }
+        )*
+    };
+}
+
+impl<'a, W, E> ser::Serializer for &'a mut Serializer<W, E>
+where
+    W: zc_io::Write,
+    E: Endec,
+{
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = ListSerializer<'a, W, E>;
+    type SerializeTuple = ListSerializer<'a, W, E>;
+    type SerializeTupleStruct = ListSerializer<'a, W, E>;
+    type SerializeTupleVariant = ListSerializer<'a, W, E>;
+    type SerializeMap = CompoundSerializer<'a, W, E>;
+    type SerializeStruct = CompoundSerializer<'a, W, E>;
+    type SerializeStructVariant = CompoundSerializer<'a, W, E>;
+
+    serialize_root! {
+        serialize_bool(v: bool) -> (),
+        serialize_i8(v: i8) -> (),
+        serialize_i16(v: i16) -> (),
+        serialize_i32(v: i32) -> (),
+        serialize_i64(v: i64) -> (),
+        serialize_u8(v: u8) -> (),
+        serialize_u16(v: u16) -> (),
+        serialize_u32(v: u32) -> (),
+        serialize_u64(v: u64) -> (),
+        serialize_f32(v: f32) -> (),
+        serialize_f64(v: f64) -> (),
+        serialize_char(v: char) -> (),
+        serialize_str(v: &str) -> (),
+        serialize_bytes(v: &[u8]) -> (),
+        serialize_none() -> (),
+        serialize_unit() -> (),
+        serialize_unit_struct(name: &'static str) -> (),
+        serialize_unit_variant(
+            name: &'static str,
+            variant_index: u32,
+            variant: &'static str
+        ) -> (),
+        serialize_seq(len: Option<usize>) -> Self::SerializeSeq,
+        serialize_tuple(len: usize) -> Self::SerializeTuple,
+        serialize_tuple_struct(name: &'static str, len: usize) -> Self::SerializeTupleStruct,
+        serialize_tuple_variant(
+            name: &'static str,
+            variant_index: u32,
+            variant: &'static str,
+            len: usize
+        ) -> Self::SerializeTupleVariant,
+        serialize_map(len: Option<usize>) -> Self::SerializeMap,
+        serialize_struct(name: &'static str, len: usize) -> Self::SerializeStruct,
+        serialize_struct_variant(
+            name: &'static str,
+            variant_index: u32,
+            variant: &'static str,
+            len: usize
+        ) -> Self::SerializeStructVariant,
+    }
+
+    #[inline]
+    fn serialize_some<T>(self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        TagSerializer::new(self, Header::Root).serialize_some(value)
+    }
+
+    #[inline]
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        if name == NAMED_TOKEN {
+            let tag = TagSerializer::new(self, Header::Root);
+            return value.serialize(NamedSerializer { tag });
+        }
+
+        TagSerializer::new(self, Header::Root).serialize_newtype_struct(name, value)
+    }
+
+    #[inline]
+    fn serialize_newtype_variant<T>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        TagSerializer::new(self, Header::Root).serialize_newtype_variant(
+            name,
+            variant_index,
+            variant,
+            value,
+        )
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// What has to be written ahead of a tag's payload once its type is known.
+enum Header<'a> {
+    /// The root tag, which has an ID and the serializer's root name unless
+    /// the root is unnamed.
+    Root,
+    /// An entry of a compound, which has an ID and a name.
+    Entry(&'a str),
+    /// An element of a list, which has no header of its own. The first
+    /// element decides the element type written in the list's header.
+    Element(&'a mut ListState),
+}
+
+/// Tracks the header of a `List` whose element type is not known until its
+/// first element is serialized.
+struct ListState {
+    ty: Option<Type>,
+    len: usize,
+    index: usize,
+}
+
+#[cold]
+fn unsupported(what: &str) -> Error {
+    Error::new(
+        Category::InvalidData,
+        format!("{what} cannot be serialized as NBT"),
+    )
+}
+
+/// Serializes a single tag, writing its header and then its payload.
+struct TagSerializer<'a, W, E> {
+    ser: &'a mut Serializer<W, E>,
+    header: Header<'a>,
+}
+
+impl<'a, W, E> TagSerializer<'a, W, E>
+where
+    W: zc_io::Write,
+    E: Endec,
+{
+    #[inline]
+    fn new(ser: &'a mut Serializer<W, E>, header: Header<'a>) -> Self {
+        TagSerializer { ser, header }
+    }
+
+    fn write_header(&mut self, ty: Type) -> Result<()> {
+        match &mut self.header {
+            Header::Root => {
+                self.ser.write_id(ty)?;
+                if self.ser.unnamed_root {
+                    return Ok(());
+                }
+
+                let name = mem::take(&mut self.ser.root_name);
+                let result = self.ser.write_str(&name);
+                self.ser.root_name = name;
+                result
+            }
+            Header::Entry(name) => {
+                self.ser.write_id(ty)?;
+                self.ser.write_str(name)
+            }
+            Header::Element(list) => match list.ty {
+                None => {
+                    list.ty = Some(ty);
+                    self.ser.write_id(ty)?;
+                    E::write_len(&mut self.ser.writer, list.len)
+                }
+                Some(expected) if expected == ty => Ok(()),
+                Some(expected) => {
+                    let message = format!("list elements must all be {expected}, found {ty}");
+                    let error = Error::new(Category::InvalidData, message);
+                    Err(error.with_detail(Detail::InvalidType {
+                        expected: Some(expected),
+                        found: Some(ty),
+                    }))
+                }
+            },
+        }
+    }
+
+    /// Enters a `List` or `Compound` and writes its header, checking the
+    /// recursion limit before anything is written.
+    fn write_container_header(&mut self, ty: Type) -> Result<()> {
+        self.ser.depth.enter()?;
+
+        let result = self.write_header(ty);
+        if result.is_err() {
+            self.ser.depth.leave();
+        }
+        result
+    }
+
+    /// Writes the header of the `Compound` an enum variant is wrapped in,
+    /// along with the header of the variant's own entry.
+    ///
+    /// Both the wrapping `Compound` and the variant's `List` or `Compound`
+    /// are entered before anything is written.
+    fn begin_variant(
+        mut self,
+        variant: &'static str,
+        ty: Type,
+    ) -> Result<&'a mut Serializer<W, E>> {
+        self.ser.depth.enter()?;
+
+        if let Err(error) = self.ser.depth.enter() {
+            self.ser.depth.leave();
+            return Err(error);
+        }
+
+        let result = self.write_header(Type::Compound).and_then(|()| {
+            self.ser.write_id(ty)?;
+            self.ser.write_str(variant)
+        });
+
+        if let Err(error) = result {
+            self.ser.depth.leave();
+            self.ser.depth.leave();
+            return Err(error);
+        }
+
+        Ok(self.ser)
+    }
+
+    fn begin_list(mut self, len: Option<usize>) -> Result<ListSerializer<'a, W, E>> {
+        let Some(len) = len else {
+            return Err(Error::new(
+                Category::InvalidData,
+                "the length of a sequence must be known up front",
+            ));
+        };
+
+        self.write_container_header(Type::List)?;
+        Ok(ListSerializer::new(self.ser, len, false))
+    }
+
+    fn begin_compound(mut self) -> Result<CompoundSerializer<'a, W, E>> {
+        self.write_container_header(Type::Compound)?;
+        Ok(CompoundSerializer::new(self.ser, false))
+    }
+}
+
+impl<'a, W, E> ser::Serializer for TagSerializer<'a, W, E>
+where
+    W: zc_io::Write,
+    E: Endec,
+{
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = ListSerializer<'a, W, E>;
+    type SerializeTuple = ListSerializer<'a, W, E>;
+    type SerializeTupleStruct = ListSerializer<'a, W, E>;
+    type SerializeTupleVariant = ListSerializer<'a, W, E>;
+    type SerializeMap = CompoundSerializer<'a, W, E>;
+    type SerializeStruct = CompoundSerializer<'a, W, E>;
+    type SerializeStructVariant = CompoundSerializer<'a, W, E>;
+
+    #[inline]
+    fn serialize_bool(self, v: bool) -> Result<()> {
+        self.serialize_i8(i8::from(v))
+    }
+
+    #[inline]
+    fn serialize_i8(mut self, v: i8) -> Result<()> {
+        self.write_header(Type::Byte)?;
+        write_all(&mut self.ser.writer, &v.to_be_bytes())
+    }
+
+    #[inline]
+    fn serialize_i16(mut self, v: i16) -> Result<()> {
+        self.write_header(Type::Short)?;
+        E::write_i16(&mut self.ser.writer, v)
+    }
+
+    #[inline]
+    fn serialize_i32(mut self, v: i32) -> Result<()> {
+        self.write_header(Type::Int)?;
+        E::write_i32(&mut self.ser.writer, v)
+    }
+
+    #[inline]
+    fn serialize_i64(mut self, v: i64) -> Result<()> {
+        self.write_header(Type::Long)?;
+        E::write_i64(&mut self.ser.writer, v)
+    }
+
+    #[inline]
+    fn serialize_u8(self, v: u8) -> Result<()> {
+        self.serialize_i16(v.into())
+    }
+
+    #[inline]
+    fn serialize_u16(self, v: u16) -> Result<()> {
+        self.serialize_i32(v.into())
+    }
+
+    #[inline]
+    fn serialize_u32(self, v: u32) -> Result<()> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<()> {
+        match i64::try_from(v) {
+            Ok(v) => self.serialize_i64(v),
+            Err(_) => Err(Error::new(
+                Category::InvalidData,
+                format!("{v} is too large for a Long"),
+            )),
+        }
+    }
+
+    #[inline]
+    fn serialize_f32(mut self, v: f32) -> Result<()> {
+        self.write_header(Type::Float)?;
+        E::write_f32(&mut self.ser.writer, v)
+    }
+
+    #[inline]
+    fn serialize_f64(mut self, v: f64) -> Result<()> {
+        self.write_header(Type::Double)?;
+        E::write_f64(&mut self.ser.writer, v)
+    }
+
+    #[inline]
+    fn serialize_char(self, v: char) -> Result<()> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    #[inline]
+    fn serialize_str(mut self, v: &str) -> Result<()> {
+        self.write_header(Type::String)?;
+        self.ser.write_str(v)
+    }
+
+    fn serialize_bytes(mut self, v: &[u8]) -> Result<()> {
+        self.write_header(Type::ByteArray)?;
+        E::write_len(&mut self.ser.writer, v.len())?;
+        write_all(&mut self.ser.writer, v)
+    }
+
+    fn serialize_none(self) -> Result<()> {
+        match self.header {
+            // Absent fields are simply left out of the compound.
+            Header::Entry(_) => Ok(()),
+            Header::Root | Header::Element(_) => Err(unsupported("`None` outside of a compound")),
+        }
+    }
+
+    #[inline]
+    fn serialize_some<T>(self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<()> {
+        Err(unsupported("`()`"))
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<()> {
+        Err(unsupported(&format!("unit struct `{name}`")))
+    }
+
+    #[inline]
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<()> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        let ty = match name {
+            BYTE_ARRAY_TOKEN => Type::ByteArray,
+            INT_ARRAY_TOKEN => Type::IntArray,
+            LONG_ARRAY_TOKEN => Type::LongArray,
+            NAMED_TOKEN => return value.serialize(NamedSerializer { tag: self }),
+            _ => return value.serialize(self),
+        };
+
+        value.serialize(ArraySerializer { tag: self, ty })
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        if name == RAW_NBT_TOKEN {
+            let ty = u8::try_from(variant_index).ok().and_then(Type::from_id);
+            return match ty {
+                Some(ty) => value.serialize(RawSerializer { tag: self, ty }),
+                None => Err(invalid_raw()),
+            };
+        }
+
+        let mut compound = self.begin_compound()?;
+        ser::SerializeStruct::serialize_field(&mut compound, variant, value)?;
+        ser::SerializeStruct::end(compound)
+    }
+
+    #[inline]
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq> {
+        self.begin_list(len)
+    }
+
+    #[inline]
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple> {
+        self.begin_list(Some(len))
+    }
+
+    #[inline]
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        self.begin_list(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        let ser = self.begin_variant(variant, Type::List)?;
+        Ok(ListSerializer::new(ser, len, true))
+    }
+
+    #[inline]
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        self.begin_compound()
+    }
+
+    #[inline]
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+        self.begin_compound()
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        let ser = self.begin_variant(variant, Type::Compound)?;
+        Ok(CompoundSerializer::new(ser, true))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Serializes the elements of a `List`.
+pub struct ListSerializer<'a, W, E> {
+    ser: &'a mut Serializer<W, E>,
+    state: ListState,
+    /// Whether the list is the payload of an enum variant, in which case the
+    /// enclosing compound has to be closed as well.
+    variant: bool,
+}
+
+impl<'a, W, E> ListSerializer<'a, W, E>
+where
+    W: zc_io::Write,
+    E: Endec,
+{
+    #[inline]
+    fn new(ser: &'a mut Serializer<W, E>, len: usize, variant: bool) -> Self {
+        let state = ListState {
+            ty: None,
+            len,
+            index: 0,
+        };
+
+        ListSerializer {
+            ser,
+            state,
+            variant,
+        }
+    }
+
+    fn element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        let index = self.state.index;
+
+        if index == self.state.len {
+            return Err(self.length_mismatch(index + 1));
+        }
+
+        self.state.index += 1;
+
+        let tag = TagSerializer::new(&mut *self.ser, Header::Element(&mut self.state));
+        value
+            .serialize(tag)
+            .map_err(|error| error.prepend_path(index))
+    }
+
+    fn finish(self) -> Result<()> {
+        if self.state.index != self.state.len {
+            return Err(self.length_mismatch(self.state.index));
+        }
+
+        // An empty list never saw an element to decide its type.
+        if self.state.ty.is_none() {
+            self.ser.write_end()?;
+            E::write_len(&mut self.ser.writer, 0)?;
+        }
+
+        self.ser.depth.leave();
+
+        if self.variant {
+            self.ser.write_end()?;
+            self.ser.depth.leave();
+        }
+
+        Ok(())
+    }
+
+    #[cold]
+    fn length_mismatch(&self, actual: usize) -> Error {
+        let declared = self.state.len;
+        let message = format!("sequence declared a length of {declared} but had {actual} elements");
+        Error::new(Category::InvalidData, message)
+            .with_detail(Detail::InvalidLength { declared, actual })
+    }
+}
+
+impl<W, E> ser::SerializeSeq for ListSerializer<'_, W, E>
+where
+    W: zc_io::Write,
+    E: Endec,
+{
+    type Ok = ();
+    type Error = Error;
+
+    #[inline]
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    #[inline]
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<W, E> ser::SerializeTuple for ListSerializer<'_, W, E>
+where
+    W: zc_io::Write,
+    E: Endec,
+{
+    type Ok = ();
+    type Error = Error;
+
+    #[inline]
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    #[inline]
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<W, E> ser::SerializeTupleStruct for ListSerializer<'_, W, E>
+where
+    W: zc_io::Write,
+    E: Endec,
+{
+    type Ok = ();
+    type Error = Error;
+
+    #[inline]
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    #[inline]
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<W, E> ser::SerializeTupleVariant for ListSerializer<'_, W, E>
+where
+    W: zc_io::Write,
+    E: Endec,
+{
+    type Ok = ();
+    type Error = Error;
+
+    #[inline]
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    #[inline]
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Serializes the entries of a `Compound`.
+pub struct CompoundSerializer<'a, W, E> {
+    ser: &'a mut Serializer<W, E>,
+    /// The key passed to `serialize_key`, awaiting its value.
+    key: Option<String>,
+    /// Whether the compound is the payload of an enum variant, in which case
+    /// the enclosing compound has to be closed as well.
+    variant: bool,
+}
+
+impl<'a, W, E> CompoundSerializer<'a, W, E>
+where
+    W: zc_io::Write,
+    E: Endec,
+{
+    #[inline]
+    fn new(ser: &'a mut Serializer<W, E>, variant: bool) -> Self {
+        CompoundSerializer {
+            ser,
+            key: None,
+            variant,
+        }
+    }
+
+    fn entry<T>(&mut self, key: &str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        let tag = TagSerializer::new(&mut *self.ser, Header::Entry(key));
+        value
+            .serialize(tag)
+            .map_err(|error| error.prepend_path(key))
+    }
+
+    fn finish(self) -> Result<()> {
+        self.ser.write_end()?;
+        self.ser.depth.leave();
+
+        if self.variant {
+            self.ser.write_end()?;
+            self.ser.depth.leave();
+        }
+
+        Ok(())
+    }
+}
+
+impl<W, E> ser::SerializeMap for CompoundSerializer<'_, W, E>
+where
+    W: zc_io::Write,
+    E: Endec,
+{
+    type Ok = ();
+    type Error = Error;
+
+    #[inline]
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.key = Some(key.serialize(KeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        let key = self
+            .key
+            .take()
+            .expect("`serialize_value` called before `serialize_key`");
+
+        self.entry(&key, value)
+    }
+
+    #[inline]
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<W, E> ser::SerializeStruct for CompoundSerializer<'_, W, E>
+where
+    W: zc_io::Write,
+    E: Endec,
+{
+    type Ok = ();
+    type Error = Error;
+
+    #[inline]
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.entry(key, value)
+    }
+
+    #[inline]
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<W, E> ser::SerializeStructVariant for CompoundSerializer<'_, W, E>
+where
+    W: zc_io::Write,
+    E: Endec,
+{
+    type Ok = ();
+    type Error = Error;
+
+    #[inline]
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.entry(key, value)
+    }
+
+    #[inline]
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Serializes the sequence wrapped in a newtype struct named after one of the
+/// private array tokens as one of the array types.
+struct ArraySerializer<'a, W, E> {
+    tag: TagSerializer<'a, W, E>,
+    ty: Type,
+}
+
+#[cold]
+fn invalid_array(ty: Type) -> Error {
+    Error::new(
+        Category::InvalidData,
+        format!("expected a sequence of integers for a {ty}"),
+    )
+}
+
+macro_rules! invalid_array {
+    ($($method:ident$(<$generic:ident>)?($($arg:ident: $ty:ty),*) -> $ok:ty,)*) => {
+        $(
+            fn $method$(<$generic>)?(self, $(_: $ty),*) -> Result<$ok>
+            $(where $generic: ?Sized + Serialize)?
+            {
+                Err(invalid_array(self.ty))
+            }
+        )*
+    };
+}
+
+impl<'a, W, E> ser::Serializer for ArraySerializer<'a, W, E>
+where
+    W: zc_io::Write,
+    E: Endec,
+{
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = ArrayElements<'a, W, E>;
+    type SerializeTuple = ArrayElements<'a, W, E>;
+    type SerializeTupleStruct = Impossible<(), Error>;
+    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeMap = Impossible<(), Error>;
+    type SerializeStruct = Impossible<(), Error>;
+    type SerializeStructVariant = Impossible<(), Error>;
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<()> {
+        if self.ty == Type::ByteArray {
+            self.tag.serialize_bytes(v)
+        } else {
+            Err(invalid_array(self.ty))
+        }
+    }
+
+    fn serialize_seq(mut self, len: Option<usize>) -> Result<Self::SerializeSeq> {
+        let Some(len) = len else {
+            return Err(Error::new(
+                Category::InvalidData,
+                "the length of a sequence must be known up front",
+            ));
+        };
+
+        self.tag.write_header(self.ty)?;
+        E::write_len(&mut self.tag.ser.writer, len)?;
+
+        Ok(ArrayElements {
+            ser: self.tag.ser,
+            ty: self.ty,
+            len,
+            index: 0,
+        })
+    }
+
+    #[inline]
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple> {
+        self.serialize_seq(Some(len))
+    }
+
+    invalid_array! {
+        serialize_bool(v: bool) -> (),
+        serialize_i8(v: i8) -> (),
+        serialize_i16(v: i16) -> (),
+        serialize_i32(v: i32) -> (),
+        serialize_i64(v: i64) -> (),
+        serialize_u8(v: u8) -> (),
+        serialize_u16(v: u16) -> (),
+        serialize_u32(v: u32) -> (),
+        serialize_u64(v: u64) -> (),
+        serialize_f32(v: f32) -> (),
+        serialize_f64(v: f64) -> (),
+        serialize_char(v: char) -> (),
+        serialize_str(v: &str) -> (),
+        serialize_none() -> (),
+        serialize_some<T>(value: &T) -> (),
+        serialize_unit() -> (),
+        serialize_unit_struct(name: &'static str) -> (),
+        serialize_unit_variant(
+            name: &'static str,
+            variant_index: u32,
+            variant: &'static str
+        ) -> (),
+        serialize_newtype_struct<T>(name: &'static str, value: &T) -> (),
+        serialize_newtype_variant<T>(
+            name: &'static str,
+            variant_index: u32,
+            variant: &'static str,
+            value: &T
+        ) -> (),
+        serialize_tuple_struct(name: &'static str, len: usize) -> Self::SerializeTupleStruct,
+        serialize_tuple_variant(
+            name: &'static str,
+            variant_index: u32,
+            variant: &'static str,
+            len: usize
+        ) -> Self::SerializeTupleVariant,
+        serialize_map(len: Option<usize>) -> Self::SerializeMap,
+        serialize_struct(name: &'static str, len: usize) -> Self::SerializeStruct,
+        serialize_struct_variant(
+            name: &'static str,
+            variant_index: u32,
+            variant: &'static str,
+            len: usize
+        ) -> Self::SerializeStructVariant,
+    }
+}
+
+/// Serializes the elements of one of the array types.
+struct ArrayElements<'a, W, E> {
+    ser: &'a mut Serializer<W, E>,
+    ty: Type,
+    len: usize,
+    index: usize,
+}
+
+impl<W, E> ArrayElements<'_, W, E>
+where
+    W: zc_io::Write,
+    E: Endec,
+{
+    fn element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        let index = self.index;
+
+        if index == self.len {
+            return Err(self.length_mismatch(index + 1));
+        }
+
+        self.index += 1;
+
+        let element = ArrayElementSerializer {
+            ser: &mut *self.ser,
+            ty: self.ty,
+        };
+
+        value
+            .serialize(element)
+            .map_err(|error| error.prepend_path(index))
+    }
+
+    fn finish(self) -> Result<()> {
+        if self.index == self.len {
+            Ok(())
+        } else {
+            Err(self.length_mismatch(self.index))
+        }
+    }
+
+    #[cold]
+    fn length_mismatch(&self, actual: usize) -> Error {
+        let declared = self.len;
+        let message = format!("sequence declared a length of {declared} but had {actual} elements");
+        Error::new(Category::InvalidData, message)
+            .with_detail(Detail::InvalidLength { declared, actual })
+    }
+}
+
+impl<W, E> ser::SerializeSeq for ArrayElements<'_, W, E>
+where
+    W: zc_io::Write,
+    E: Endec,
+{
+    type Ok = ();
+    type Error = Error;
+
+    #[inline]
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    #[inline]
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<W, E> ser::SerializeTuple for ArrayElements<'_, W, E>
+where
+    W: zc_io::Write,
+    E: Endec,
+{
+    type Ok = ();
+    type Error = Error;
+
+    #[inline]
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    #[inline]
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+/// Serializes a single element of one of the array types, which has no
+/// header of its own.
+struct ArrayElementSerializer<'a, W, E> {
+    ser: &'a mut Serializer<W, E>,
+    ty: Type,
+}
+
+impl<W, E> ArrayElementSerializer<'_, W, E>
+where
+    W: zc_io::Write,
+    E: Endec,
+{
+    #[cold]
+    fn invalid_element(&self) -> Error {
+        let expected = match self.ty {
+            Type::ByteArray => Type::Byte,
+            Type::IntArray => Type::Int,
+            _ => Type::Long,
+        };
+
+        let message = format!("{} elements must be a {expected}", self.ty);
+        Error::new(Category::InvalidData, message).with_detail(Detail::InvalidType {
+            expected: Some(expected),
+            found: None,
+        })
+    }
+}
+
+impl<W, E> ser::Serializer for ArrayElementSerializer<'_, W, E>
+where
+    W: zc_io::Write,
+    E: Endec,
+{
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = Impossible<(), Error>;
+    type SerializeTuple = Impossible<(), Error>;
+    type SerializeTupleStruct = Impossible<(), Error>;
+    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeMap = Impossible<(), Error>;
+    type SerializeStruct = Impossible<(), Error>;
+    type SerializeStructVariant = Impossible<(), Error>;
+
+    #[inline]
+    fn serialize_bool(self, v: bool) -> Result<()> {
+        self.serialize_i8(i8::from(v))
+    }
+
+    #[inline]
+    fn serialize_i8(self, v: i8) -> Result<()> {
+        match self.ty {
+            Type::ByteArray => write_all(&mut self.ser.writer, &v.to_be_bytes()),
+            _ => self.serialize_i64(v.into()),
+        }
+    }
+
+    #[inline]
+    fn serialize_i16(self, v: i16) -> Result<()> {
+        self.serialize_i64(v.into())
+    }
+
+    #[inline]
+    fn serialize_i32(self, v: i32) -> Result<()> {
+        match self.ty {
+            Type::IntArray => E::write_i32(&mut self.ser.writer, v),
+            _ => self.serialize_i64(v.into()),
+        }
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<()> {
+        match self.ty {
+            Type::ByteArray => match i8::try_from(v) {
+                Ok(v) => write_all(&mut self.ser.writer, &v.to_be_bytes()),
+                Err(_) => Err(self.invalid_element()),
+            },
+            Type::IntArray => match i32::try_from(v) {
+                Ok(v) => E::write_i32(&mut self.ser.writer, v),
+                Err(_) => Err(self.invalid_element()),
+            },
+            _ => E::write_i64(&mut self.ser.writer, v),
+        }
+    }
+
+    #[inline]
+    fn serialize_u8(self, v: u8) -> Result<()> {
+        self.serialize_i64(v.into())
+    }
+
+    #[inline]
+    fn serialize_u16(self, v: u16) -> Result<()> {
+        self.serialize_i64(v.into())
+    }
+
+    #[inline]
+    fn serialize_u32(self, v: u32) -> Result<()> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<()> {
+        match i64::try_from(v) {
+            Ok(v) => self.serialize_i64(v),
+            Err(_) => Err(self.invalid_element()),
+        }
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<()> {
+        Err(self.invalid_element())
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<()> {
+        Err(self.invalid_element())
+    }
+
+    fn serialize_char(self, _v: char) -> Result<()> {
+        Err(self.invalid_element())
+    }
+
+    fn serialize_str(self, _v: &str) -> Result<()> {
+        Err(self.invalid_element())
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<()> {
+        Err(self.invalid_element())
+    }
+
+    fn serialize_none(self) -> Result<()> {
+        Err(self.invalid_element())
+    }
+
+    #[inline]
+    fn serialize_some<T>(self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<()> {
+        Err(self.invalid_element())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<()> {
+        Err(self.invalid_element())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<()> {
+        Err(self.invalid_element())
+    }
+
+    #[inline]
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(self.invalid_element())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Err(self.invalid_element())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        Err(self.invalid_element())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Err(self.invalid_element())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(self.invalid_element())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Err(self.invalid_element())
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+        Err(self.invalid_element())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(self.invalid_element())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Writes the encoded payload a [`RawNbt`](crate::binary::RawNbt) wraps
+/// as-is, after the header of a tag of the given type.
+struct RawSerializer<'a, W, E> {
+    tag: TagSerializer<'a, W, E>,
+    ty: Type,
+}
+
+#[cold]
+fn invalid_raw() -> Error {
+    Error::new(
+        Category::InvalidData,
+        "expected the encoded payload of a tag",
+    )
+}
+
+macro_rules! invalid_raw {
+    ($($method:ident$(<$generic:ident>)?($($arg:ident: $ty:ty),*) -> $ok:ty,)*) => {
+        $(
+            fn $method$(<$generic>)?(self, $(_: $ty),*) -> Result<$ok>
+            $(where $generic: ?Sized + Serialize)?
+            {
+                Err(invalid_raw())
+            }
+        )*
+    };
+}
+
+impl<W, E> ser::Serializer for RawSerializer<'_, W, E>
+where
+    W: zc_io::Write,
+    E: Endec,
+{
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = Impossible<(), Error>;
+    type SerializeTuple = Impossible<(), Error>;
+    type SerializeTupleStruct = Impossible<(), Error>;
+    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeMap = Impossible<(), Error>;
+    type SerializeStruct = Impossible<(), Error>;
+    type SerializeStructVariant = Impossible<(), Error>;
+
+    fn serialize_bytes(mut self, v: &[u8]) -> Result<()> {
+        self.tag.write_header(self.ty)?;
+        write_all(&mut self.tag.ser.writer, v)
+    }
+
+    invalid_raw! {
+        serialize_bool(v: bool) -> (),
+        serialize_i8(v: i8) -> (),
+        serialize_i16(v: i16) -> (),
+        serialize_i32(v: i32) -> (),
+        serialize_i64(v: i64) -> (),
+        serialize_u8(v: u8) -> (),
+        serialize_u16(v: u16) -> (),
+        serialize_u32(v: u32) -> (),
+        serialize_u64(v: u64) -> (),
+        serialize_f32(v: f32) -> (),
+        serialize_f64(v: f64) -> (),
+        serialize_char(v: char) -> (),
+        serialize_str(v: &str) -> (),
+        serialize_none() -> (),
+        serialize_some<T>(value: &T) -> (),
+        serialize_unit() -> (),
+        serialize_unit_struct(name: &'static str) -> (),
+        serialize_unit_variant(
+            name: &'static str,
+            variant_index: u32,
+            variant: &'static str
+        ) -> (),
+        serialize_newtype_struct<T>(name: &'static str, value: &T) -> (),
+        serialize_newtype_variant<T>(
+            name: &'static str,
+            variant_index: u32,
+            variant: &'static str,
+            value: &T
+        ) -> (),
+        serialize_seq(len: Option<usize>) -> Self::SerializeSeq,
+        serialize_tuple(len: usize) -> Self::SerializeTuple,
+        serialize_tuple_struct(name: &'static str, len: usize) -> Self::SerializeTupleStruct,
+        serialize_tuple_variant(
+            name: &'static str,
+            variant_index: u32,
+            variant: &'static str,
+            len: usize
+        ) -> Self::SerializeTupleVariant,
+        serialize_map(len: Option<usize>) -> Self::SerializeMap,
+        serialize_struct(name: &'static str, len: usize) -> Self::SerializeStruct,
+        serialize_struct_variant(
+            name: &'static str,
+            variant_index: u32,
+            variant: &'static str,
+            len: usize
+        ) -> Self::SerializeStructVariant,
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Serializes the `(name, value)` pair a [`Named`](crate::Named) wraps as a
+/// root tag with that name.
+struct NamedSerializer<'a, W, E> {
+    tag: TagSerializer<'a, W, E>,
+}
+
+#[cold]
+fn invalid_named() -> Error {
+    Error::new(
+        Category::InvalidData,
+        "expected a (name, value) pair for a named root tag",
+    )
+}
+
+macro_rules! invalid_named {
+    ($($method:ident$(<$generic:ident>)?($($arg:ident: $ty:ty),*) -> $ok:ty,)*) => {
+        $(
+            fn $method$(<$generic>)?(self, $(_: $ty),*) -> Result<$ok>
+            $(where $generic: ?Sized + Serialize)?
+            {
+                Err(invalid_named())
+            }
+        )*
+    };
+}
+
+impl<'a, W, E> ser::Serializer for NamedSerializer<'a, W, E>
+where
+    W: zc_io::Write,
+    E: Endec,
+{
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = Impossible<(), Error>;
+    type SerializeTuple = NamedElements<'a, W, E>;
+    type SerializeTupleStruct = Impossible<(), Error>;
+    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeMap = Impossible<(), Error>;
+    type SerializeStruct = Impossible<(), Error>;
+    type SerializeStructVariant = Impossible<(), Error>;
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple> {
+        if len != 2 {
+            return Err(invalid_named());
+        }
+
+        Ok(NamedElements {
+            tag: Some(self.tag),
+            name: None,
+        })
+    }
+
+    invalid_named! {
+        serialize_bool(v: bool) -> (),
+        serialize_i8(v: i8) -> (),
+        serialize_i16(v: i16) -> (),
+        serialize_i32(v: i32) -> (),
+        serialize_i64(v: i64) -> (),
+        serialize_u8(v: u8) -> (),
+        serialize_u16(v: u16) -> (),
+        serialize_u32(v: u32) -> (),
+        serialize_u64(v: u64) -> (),
+        serialize_f32(v: f32) -> (),
+        serialize_f64(v: f64) -> (),
+        serialize_char(v: char) -> (),
+        serialize_str(v: &str) -> (),
+        serialize_bytes(v: &[u8]) -> (),
+        serialize_none() -> (),
+        serialize_some<T>(value: &T) -> (),
+        serialize_unit() -> (),
+        serialize_unit_struct(name: &'static str) -> (),
+        serialize_unit_variant(
+            name: &'static str,
+            variant_index: u32,
+            variant: &'static str
+        ) -> (),
+        serialize_newtype_struct<T>(name: &'static str, value: &T) -> (),
+        serialize_newtype_variant<T>(
+            name: &'static str,
+            variant_index: u32,
+            variant: &'static str,
+            value: &T
+        ) -> (),
+        serialize_seq(len: Option<usize>) -> Self::SerializeSeq,
+        serialize_tuple_struct(name: &'static str, len: usize) -> Self::SerializeTupleStruct,
+        serialize_tuple_variant(
+            name: &'static str,
+            variant_index: u32,
+            variant: &'static str,
+            len: usize
+        ) -> Self::SerializeTupleVariant,
+        serialize_map(len: Option<usize>) -> Self::SerializeMap,
+        serialize_struct(name: &'static str, len: usize) -> Self::SerializeStruct,
+        serialize_struct_variant(
+            name: &'static str,
+            variant_index: u32,
+            variant: &'static str,
+            len: usize
+        ) -> Self::SerializeStructVariant,
+    }
+}
+
+/// Serializes the name of a named root tag and then the tag itself.
+///
+/// Only the root tag has a name of its own, so below it the name is dropped
+/// and the tag is serialized as if it was not wrapped at all.
+struct NamedElements<'a, W, E> {
+    tag: Option<TagSerializer<'a, W, E>>,
+    name: Option<String>,
+}
+
+impl<W, E> ser::SerializeTuple for NamedElements<'_, W, E>
+where
+    W: zc_io::Write,
+    E: Endec,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        let Some(name) = self.name.take() else {
+            self.name = Some(value.serialize(KeySerializer)?);
+            return Ok(());
+        };
+
+        let Some(tag) = self.tag.take() else {
+            return Err(invalid_named());
+        };
+
+        if !matches!(tag.header, Header::Root) {
+            return value.serialize(tag);
+        }
+
+        let ser = tag.ser;
+        let previous = mem::replace(&mut ser.root_name, name);
+        let result = value.serialize(TagSerializer::new(&mut *ser, Header::Root));
+        ser.root_name = previous;
+        result
+    }
+
+    #[inline]
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// A writer that emits binary NBT one tag at a time, without building a
+/// [`Value`](crate::Value) or going through serde first.
+///
+/// Every tag is written with a name, which is the name of the root tag for
+/// the first one and the key of the entry inside a `Compound`. Elements of a
+/// `List` have no names, so the name is ignored inside one. `List`s and
+/// `Compound`s are opened with [`begin_list`](Self::begin_list) and
+/// [`begin_compound`](Self::begin_compound) and closed with
+/// [`end`](Self::end); the element type and length of a `List` are declared
+/// up front, and every element must match them.
+///
+/// Any [`zc_io::Write`] works as the output, including a `Vec<u8>`; wrap an
+/// [`io::Write`](std::io::Write) in a [`zc_io::IoWriter`] to write to files
+/// and sockets. Call [`finish`](Self::finish) once the root tag is complete
+/// to get the output back.
+///
+/// # Panics
+///
+/// Misusing the writer, such as ending a `List` before all of its elements
+/// were written or writing a second root tag, is a bug in the caller. It
+/// panics in debug builds and returns an error of [`Category::InvalidData`]
+/// without writing anything otherwise.
+pub struct NbtWriter<W, E> {
+    ser: Serializer<W, E>,
+    /// The containers begun and not yet ended, innermost last.
+    stack: Vec<Container>,
+    /// Whether the header of the root tag has been written.
+    started: bool,
+}
+
+/// A container an [`NbtWriter`] is inside of.
+enum Container {
+    Compound,
+    List {
+        ty: Type,
+        len: usize,
+        written: usize,
+    },
+}
+
+macro_rules! write_scalar {
+    ($($(#[$attr:meta])* $method:ident($ty:ty) => $tag:ident, $write:expr;)*) => {
+        $(
+            $(#[$attr])*
+            ///
+            /// # Errors
+            ///
+            /// Fails if the output cannot be written to, or if the writer is
+            /// misused.
+            pub fn $method(&mut self, name: &str, value: $ty) -> Result<()> {
+                self.header(name, Type::$tag)?;
+                #[allow(clippy::redundant_closure_call)]
+                ($write)(&mut self.ser.writer, value)
+            }
+        )*
+    };
+}
+
+impl<W, E> NbtWriter<W, E>
+where
+    W: zc_io::Write,
+    E: Endec,
+{
+    /// Creates a writer that emits binary NBT into `writer`.
+    #[must_use]
+    #[inline]
+    pub fn new(writer: W) -> Self {
+        NbtWriter {
+            ser: Serializer::new(writer),
+            stack: Vec::new(),
+            started: false,
+        }
+    }
+
+    /// Sets how deeply `List`s and `Compound`s may nest before beginning
+    /// another fails with [`Category::RecursionLimitExceeded`].
+    ///
+    /// The default limit is 512, the same as Minecraft's.
+    #[inline]
+    pub fn set_recursion_limit(&mut self, limit: usize) {
+        self.ser.set_recursion_limit(limit);
+    }
+
+    /// Lets `List`s and `Compound`s nest arbitrarily deeply.
+    ///
+    /// Minecraft refuses to read NBT nested deeper than 512 levels.
+    #[inline]
+    pub fn disable_recursion_limit(&mut self) {
+        self.ser.disable_recursion_limit();
+    }
+
+    /// Writes the root tag without a name, which is then ignored.
+    ///
+    /// See [`Serializer::write_unnamed_root`].
+    #[inline]
+    pub fn write_unnamed_root(&mut self) {
+        self.ser.write_unnamed_root();
+    }
+
+    /// Returns how many `List`s and `Compound`s have been begun and not yet
+    /// ended.
+    #[must_use]
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Begins a `Compound`, whose entries are written next.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the output cannot be written to, if the `Compound` nests too
+    /// deeply, or if the writer is misused.
+    pub fn begin_compound(&mut self, name: &str) -> Result<()> {
+        self.container_header(name, Type::Compound)?;
+        self.stack.push(Container::Compound);
+        Ok(())
+    }
+
+    /// Begins a `List` of `len` elements of type `ty`, which are written
+    /// next.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the output cannot be written to, if the `List` nests too
+    /// deeply, or if the writer is misused.
+    pub fn begin_list(&mut self, name: &str, ty: Type, len: usize) -> Result<()> {
+        self.container_header(name, Type::List)?;
+        self.ser.write_id(ty)?;
+        E::write_len(&mut self.ser.writer, len)?;
+        self.stack.push(Container::List {
+            ty,
+            len,
+            written: 0,
+        });
+        Ok(())
+    }
+
+    /// Ends the innermost `List` or `Compound`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the output cannot be written to, or if the writer is
+    /// misused, e.g. by ending a `List` before all of its elements were
+    /// written.
+    pub fn end(&mut self) -> Result<()> {
+        match self.stack.last() {
+            None => return Err(misuse("there is no List or Compound to end")),
+            Some(Container::Compound) => self.ser.write_end()?,
+            Some(&Container::List { len, written, .. }) if written == len => {}
+            Some(&Container::List { len, written, .. }) => {
+                return Err(length_mismatch(len, written));
+            }
+        }
+
+        self.stack.pop();
+        self.ser.depth.leave();
+        Ok(())
+    }
+
+    write_scalar! {
+        /// Writes a `Byte`.
+        byte(i8) => Byte, |writer: &mut W, value: i8| {
+            #[allow(clippy::cast_sign_loss)]
+            write_all(writer, &[value as u8])
+        };
+        /// Writes a `Short`.
+        short(i16) => Short, E::write_i16;
+        /// Writes an `Int`.
+        int(i32) => Int, E::write_i32;
+        /// Writes a `Long`.
+        long(i64) => Long, E::write_i64;
+        /// Writes a `Float`.
+        float(f32) => Float, E::write_f32;
+        /// Writes a `Double`.
+        double(f64) => Double, E::write_f64;
+    }
+
+    /// Writes a `Byte` holding `1` for `true` and `0` for `false`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the output cannot be written to, or if the writer is misused.
+    #[inline]
+    pub fn bool(&mut self, name: &str, value: bool) -> Result<()> {
+        self.byte(name, value.into())
+    }
+
+    /// Writes a `String`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the output cannot be written to, if `value` is too long, or
+    /// if the writer is misused.
+    pub fn string(&mut self, name: &str, value: &str) -> Result<()> {
+        self.header(name, Type::String)?;
+        self.ser.write_str(value)
+    }
+
+    /// Writes a `ByteArray` of raw bytes.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the output cannot be written to, if `value` is too long, or
+    /// if the writer is misused.
+    pub fn byte_array(&mut self, name: &str, value: &[u8]) -> Result<()> {
+        self.header(name, Type::ByteArray)?;
+        E::write_len(&mut self.ser.writer, value.len())?;
+        write_all(&mut self.ser.writer, value)
+    }
+
+    /// Writes an `IntArray`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the output cannot be written to, if `value` is too long, or
+    /// if the writer is misused.
+    pub fn int_array(&mut self, name: &str, value: &[i32]) -> Result<()> {
+        self.header(name, Type::IntArray)?;
+        E::write_len(&mut self.ser.writer, value.len())?;
+        value
+            .iter()
+            .try_for_each(|&element| E::write_i32(&mut self.ser.writer, element))
+    }
+
+    /// Writes a `LongArray`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the output cannot be written to, if `value` is too long, or
+    /// if the writer is misused.
+    pub fn long_array(&mut self, name: &str, value: &[i64]) -> Result<()> {
+        self.header(name, Type::LongArray)?;
+        E::write_len(&mut self.ser.writer, value.len())?;
+        value
+            .iter()
+            .try_for_each(|&element| E::write_i64(&mut self.ser.writer, element))
+    }
+
+    /// Checks that the root tag is complete and returns the underlying
+    /// writer.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the writer is misused, i.e. if no root tag was written or a
+    /// `List` or `Compound` has not been ended.
+    pub fn finish(self) -> Result<W> {
+        if !self.started {
+            return Err(misuse("no root tag was written"));
+        }
+
+        if let Some(container) = self.stack.last() {
+            let what = match container {
+                Container::Compound => "Compound",
+                Container::List { .. } => "List",
+            };
+            return Err(misuse(format!("a {what} has not been ended")));
+        }
+
+        Ok(self.ser.into_inner())
+    }
+
+    /// Enters a `List` or `Compound` and writes its header, checking the
+    /// recursion limit before anything is written.
+    fn container_header(&mut self, name: &str, ty: Type) -> Result<()> {
+        self.ser.depth.enter()?;
+
+        let result = self.header(name, ty);
+        if result.is_err() {
+            self.ser.depth.leave();
+        }
+        result
+    }
+
+    /// Writes whatever precedes the payload of a tag of the given type, after
+    /// checking that such a tag may be written next.
+    fn header(&mut self, name: &str, ty: Type) -> Result<()> {
+        match self.stack.last_mut() {
+            None if self.started => Err(misuse("the root tag has already been written")),
+            None => {
+                self.started = true;
+                self.ser.write_id(ty)?;
+                if self.ser.unnamed_root {
+                    return Ok(());
+                }
+
+                self.ser.write_str(name)
+            }
+            Some(Container::Compound) => {
+                self.ser.write_id(ty)?;
+                self.ser.write_str(name)
+            }
+            Some(Container::List { ty: expected, .. }) if *expected != ty => {
+                let message = format!("list elements must all be {expected}, found {ty}");
+                let error = misuse(message);
+                Err(error.with_detail(Detail::InvalidType {
+                    expected: Some(*expected),
+                    found: Some(ty),
+                }))
+            }
+            Some(Container::List { len, written, .. }) if written == len => {
+                Err(length_mismatch(*len, *written + 1))
+            }
+            Some(Container::List { written, .. }) => {
+                *written += 1;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Reports a `List` written with a different number of elements than it
+/// declared, which panics in debug builds.
+#[cold]
+#[track_caller]
+fn length_mismatch(declared: usize, actual: usize) -> Error {
+    let message = format!("List declared a length of {declared} but had {actual} elements");
+    misuse(message).with_detail(Detail::InvalidLength { declared, actual })
+}
+
+/// Reports a misuse of an [`NbtWriter`], which panics in debug builds.
+#[cold]
+#[track_caller]
+fn misuse<T>(message: T) -> Error
+where
+    T: Into<String>,
+{
+    let message = message.into();
+
+    #[cfg(debug_assertions)]
+    panic!("misuse of NbtWriter: {message}");
+
+    #[cfg(not(debug_assertions))]
+    Error::new(Category::InvalidData, message)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(all(test, feature = "be"))]
+mod tests {
+    use alloc::vec::Vec;
+
+    use serde::Serialize;
+
+    use super::{NbtWriter, Serializer};
+    use crate::{binary::BigEndian, error::Category, Type};
+
+    #[derive(Serialize)]
+    struct Outer {
+        inner: Inner,
+    }
+
+    #[derive(Serialize)]
+    struct Inner {}
+
+    #[test]
+    fn serializer_checks_depth_before_writing_a_header() {
+        let mut ser = Serializer::<_, BigEndian>::new(Vec::new());
+        ser.set_recursion_limit(1);
+        let error = Outer { inner: Inner {} }.serialize(&mut ser).unwrap_err();
+
+        assert_eq!(error.category(), Category::RecursionLimitExceeded);
+        assert_eq!(ser.into_inner(), &[10, 0, 0]);
+    }
+
+    #[derive(Serialize)]
+    enum Variant {
+        Newtype(Inner),
+        Tuple(i32, i32),
+        Struct { x: i32 },
+    }
+
+    #[test]
+    fn serializer_checks_depth_before_writing_a_variant() {
+        for variant in [Variant::Tuple(1, 2), Variant::Struct { x: 1 }] {
+            let mut ser = Serializer::<_, BigEndian>::new(Vec::new());
+            ser.set_recursion_limit(1);
+            let error = variant.serialize(&mut ser).unwrap_err();
+            assert_eq!(error.category(), Category::RecursionLimitExceeded);
+
+            // Neither level stays entered, so a root `Compound` still fits.
+            Inner {}.serialize(&mut ser).unwrap();
+            assert_eq!(ser.into_inner(), &[10, 0, 0, 0]);
+        }
+
+        let mut ser = Serializer::<_, BigEndian>::new(Vec::new());
+        ser.set_recursion_limit(2);
+        Variant::Struct { x: 1 }.serialize(&mut ser).unwrap();
+
+        let mut ser = Serializer::<_, BigEndian>::new(Vec::new());
+        ser.set_recursion_limit(1);
+        let error = Variant::Newtype(Inner {}).serialize(&mut ser).unwrap_err();
+        assert_eq!(error.category(), Category::RecursionLimitExceeded);
+        assert_eq!(ser.into_inner(), &[10, 0, 0]);
+    }
+
+    #[test]
+    fn writer_checks_depth_before_writing_a_header() {
+        let mut writer = NbtWriter::<_, BigEndian>::new(Vec::new());
+        writer.set_recursion_limit(1);
+        writer.begin_compound("").unwrap();
+
+        let error = writer.begin_list("list", Type::Int, 0).unwrap_err();
+        assert_eq!(error.category(), Category::RecursionLimitExceeded);
+        let error = writer.begin_compound("compound").unwrap_err();
+        assert_eq!(error.category(), Category::RecursionLimitExceeded);
+        assert_eq!(writer.depth(), 1);
+
+        writer.end().unwrap();
+        assert_eq!(writer.finish().unwrap(), &[10, 0, 0, 0]);
+    }
+
+    #[test]
+    fn writer_does_not_count_an_element_that_nests_too_deeply() {
+        let mut writer = NbtWriter::<_, BigEndian>::new(Vec::new());
+        writer.set_recursion_limit(1);
+        writer.begin_list("", Type::Compound, 0).unwrap();
+
+        let error = writer.begin_compound("").unwrap_err();
+        assert_eq!(error.category(), Category::RecursionLimitExceeded);
+
+        writer.end().unwrap();
+        assert_eq!(writer.finish().unwrap(), &[9, 0, 0, 10, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    #[cfg_attr(
+        debug_assertions,
+        should_panic = "the root tag has already been written"
+    )]
+    fn writer_rejects_a_second_root() {
+        let mut writer = NbtWriter::<_, BigEndian>::new(Vec::new());
+        writer.int("", 1).unwrap();
+
+        let error = writer.int("", 2).unwrap_err();
+        assert_eq!(error.category(), Category::InvalidData);
+    }
+
+    #[test]
+    #[cfg_attr(debug_assertions, should_panic = "list elements must all be")]
+    fn writer_rejects_elements_of_the_wrong_type() {
+        let mut writer = NbtWriter::<_, BigEndian>::new(Vec::new());
+        writer.begin_list("", Type::Int, 1).unwrap();
+
+        let error = writer.long("", 1).unwrap_err();
+        assert_eq!(error.category(), Category::InvalidData);
+    }
+
+    #[test]
+    #[cfg_attr(debug_assertions, should_panic = "declared a length of 2 but had 1")]
+    fn writer_rejects_ending_a_list_early() {
+        let mut writer = NbtWriter::<_, BigEndian>::new(Vec::new());
+        writer.begin_list("", Type::Int, 2).unwrap();
+        writer.int("", 1).unwrap();
+
+        let error = writer.end().unwrap_err();
+        assert_eq!(error.category(), Category::InvalidData);
+    }
+
+    #[test]
+    #[cfg_attr(debug_assertions, should_panic = "a Compound has not been ended")]
+    fn writer_rejects_finishing_inside_a_compound() {
+        let mut writer = NbtWriter::<_, BigEndian>::new(Vec::new());
+        writer.begin_compound("").unwrap();
+
+        let error = writer.finish().unwrap_err();
+        assert_eq!(error.category(), Category::InvalidData);
+    }
+}
@@ -0,0 +1,208 @@
+//! TODO
+
+use alloc::{
+    borrow::ToOwned,
+    string::{String, ToString},
+};
+
+use serde::ser::{self, Impossible, Serialize};
+
+use crate::error::{Category, Error, Result};
+
+/// Serializes a map key into the name of a compound entry.
+///
+/// Names are always strings in NBT, so numbers and booleans are formatted in
+/// a way the deserializers can parse back out of the name.
+pub(crate) struct KeySerializer;
+
+#[cold]
+fn key_must_be_a_string() -> Error {
+    Error::new(Category::InvalidData, "compound keys must be strings")
+}
+
+impl ser::Serializer for KeySerializer {
+    type Ok = String;
+    type Error = Error;
+
+    type SerializeSeq = Impossible<String, Error>;
+    type SerializeTuple = Impossible<String, Error>;
+    type SerializeTupleStruct = Impossible<String, Error>;
+    type SerializeTupleVariant = Impossible<String, Error>;
+    type SerializeMap = Impossible<String, Error>;
+    type SerializeStruct = Impossible<String, Error>;
+    type SerializeStructVariant = Impossible<String, Error>;
+
+    #[inline]
+    fn serialize_bool(self, v: bool) -> Result<String> {
+        Ok(if v { "true" } else { "false" }.to_owned())
+    }
+
+    #[inline]
+    fn serialize_i8(self, v: i8) -> Result<String> {
+        Ok(itoa::Buffer::new().format(v).to_owned())
+    }
+
+    #[inline]
+    fn serialize_i16(self, v: i16) -> Result<String> {
+        Ok(itoa::Buffer::new().format(v).to_owned())
+    }
+
+    #[inline]
+    fn serialize_i32(self, v: i32) -> Result<String> {
+        Ok(itoa::Buffer::new().format(v).to_owned())
+    }
+
+    #[inline]
+    fn serialize_i64(self, v: i64) -> Result<String> {
+        Ok(itoa::Buffer::new().format(v).to_owned())
+    }
+
+    #[inline]
+    fn serialize_i128(self, v: i128) -> Result<String> {
+        Ok(itoa::Buffer::new().format(v).to_owned())
+    }
+
+    #[inline]
+    fn serialize_u8(self, v: u8) -> Result<String> {
+        Ok(itoa::Buffer::new().format(v).to_owned())
+    }
+
+    #[inline]
+    fn serialize_u16(self, v: u16) -> Result<String> {
+        Ok(itoa::Buffer::new().format(v).to_owned())
+    }
+
+    #[inline]
+    fn serialize_u32(self, v: u32) -> Result<String> {
+        Ok(itoa::Buffer::new().format(v).to_owned())
+    }
+
+    #[inline]
+    fn serialize_u64(self, v: u64) -> Result<String> {
+        Ok(itoa::Buffer::new().format(v).to_owned())
+    }
+
+    #[inline]
+    fn serialize_u128(self, v: u128) -> Result<String> {
+        Ok(itoa::Buffer::new().format(v).to_owned())
+    }
+
+    #[inline]
+    fn serialize_f32(self, v: f32) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    #[inline]
+    fn serialize_f64(self, v: f64) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    #[inline]
+    fn serialize_char(self, v: char) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    #[inline]
+    fn serialize_str(self, v: &str) -> Result<String> {
+        Ok(v.to_owned())
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<String> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_none(self) -> Result<String> {
+        Err(key_must_be_a_string())
+    }
+
+    #[inline]
+    fn serialize_some<T>(self, value: &T) -> Result<String>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<String> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String> {
+        Err(key_must_be_a_string())
+    }
+
+    #[inline]
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<String> {
+        Ok(variant.to_owned())
+    }
+
+    #[inline]
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<String>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(key_must_be_a_string())
+    }
+}
@@ -0,0 +1,163 @@
+//! TODO
+
+#[cfg(feature = "binary")]
+pub mod binary;
+#[cfg(feature = "binary")]
+mod key;
+#[cfg(feature = "binary")]
+mod options;
+
+#[cfg(any(feature = "be", feature = "le"))]
+use alloc::vec::Vec;
+#[cfg(all(any(feature = "be", feature = "le"), feature = "std"))]
+use std::io;
+
+#[cfg(any(feature = "be", feature = "le"))]
+use serde::Serialize;
+
+#[cfg(feature = "binary")]
+pub use self::options::Options;
+
+#[cfg(any(feature = "be", feature = "le"))]
+use crate::{binary::Endec, error::Result};
+
+////////////////////////////////////////////////////////////////////////////////
+// Minecraft: Java Edition
+////////////////////////////////////////////////////////////////////////////////
+
+/// Serializes `value` as big-endian binary NBT, as used by Minecraft: Java
+/// Edition.
+///
+/// # Errors
+///
+/// Serialization can fail if `value` holds a type NBT cannot represent, or if
+/// it nests too deeply.
+#[cfg(feature = "be")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "be")))]
+pub fn to_be_vec<T>(value: &T) -> Result<Vec<u8>>
+where
+    T: ?Sized + Serialize,
+{
+    to_vec::<T, crate::binary::BigEndian>(value)
+}
+
+/// Serializes `value` as big-endian binary NBT into an I/O stream, as used by
+/// Minecraft: Java Edition.
+///
+/// The writer is not buffered internally; wrap it in a
+/// [`BufWriter`](std::io::BufWriter) if it performs a system call per write.
+///
+/// # Errors
+///
+/// Serialization can fail if the stream fails to write, if `value` holds a
+/// type NBT cannot represent, or if it nests too deeply.
+#[cfg(all(feature = "be", feature = "std"))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "be", feature = "std"))))]
+pub fn to_be_writer<W, T>(writer: W, value: &T) -> Result<()>
+where
+    W: io::Write,
+    T: ?Sized + Serialize,
+{
+    to_writer::<W, T, crate::binary::BigEndian>(writer, value)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Minecraft: Bedrock Edition
+////////////////////////////////////////////////////////////////////////////////
+
+/// Serializes `value` as little-endian binary NBT, as used by Minecraft:
+/// Bedrock Edition to store data on disk.
+///
+/// # Errors
+///
+/// Serialization can fail if `value` holds a type NBT cannot represent, or if
+/// it nests too deeply.
+#[cfg(feature = "le")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "le")))]
+pub fn to_le_vec<T>(value: &T) -> Result<Vec<u8>>
+where
+    T: ?Sized + Serialize,
+{
+    to_vec::<T, crate::binary::LittleEndian>(value)
+}
+
+/// Serializes `value` as little-endian binary NBT into an I/O stream, as used
+/// by Minecraft: Bedrock Edition to store data on disk.
+///
+/// The writer is not buffered internally; wrap it in a
+/// [`BufWriter`](std::io::BufWriter) if it performs a system call per write.
+///
+/// # Errors
+///
+/// Serialization can fail if the stream fails to write, if `value` holds a
+/// type NBT cannot represent, or if it nests too deeply.
+#[cfg(all(feature = "le", feature = "std"))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "le", feature = "std"))))]
+pub fn to_le_writer<W, T>(writer: W, value: &T) -> Result<()>
+where
+    W: io::Write,
+    T: ?Sized + Serialize,
+{
+    to_writer::<W, T, crate::binary::LittleEndian>(writer, value)
+}
+
+/// Serializes `value` as [`VarInt`] binary NBT, as used by Minecraft: Bedrock
+/// Edition to send data over the network.
+///
+/// # Errors
+///
+/// Serialization can fail if `value` holds a type NBT cannot represent, or if
+/// it nests too deeply.
+///
+/// [`VarInt`]: crate::binary::VarInt
+#[cfg(feature = "varint")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "varint")))]
+pub fn to_varint_vec<T>(value: &T) -> Result<Vec<u8>>
+where
+    T: ?Sized + Serialize,
+{
+    to_vec::<T, crate::binary::VarInt>(value)
+}
+
+/// Serializes `value` as [`VarInt`] binary NBT into an I/O stream, as used by
+/// Minecraft: Bedrock Edition to send data over the network.
+///
+/// The writer is not buffered internally; wrap it in a
+/// [`BufWriter`](std::io::BufWriter) if it performs a system call per write.
+///
+/// # Errors
+///
+/// Serialization can fail if the stream fails to write, if `value` holds a
+/// type NBT cannot represent, or if it nests too deeply.
+///
+/// [`VarInt`]: crate::binary::VarInt
+#[cfg(all(feature = "varint", feature = "std"))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "varint", feature = "std"))))]
+pub fn to_varint_writer<W, T>(writer: W, value: &T) -> Result<()>
+where
+    W: io::Write,
+    T: ?Sized + Serialize,
+{
+    to_writer::<W, T, crate::binary::VarInt>(writer, value)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(any(feature = "be", feature = "le"))]
+fn to_vec<T, E>(value: &T) -> Result<Vec<u8>>
+where
+    T: ?Sized + Serialize,
+    E: Endec,
+{
+    Options::<E>::new().to_vec(value)
+}
+
+#[cfg(all(any(feature = "be", feature = "le"), feature = "std"))]
+fn to_writer<W, T, E>(writer: W, value: &T) -> Result<()>
+where
+    W: io::Write,
+    T: ?Sized + Serialize,
+    E: Endec,
+{
+    Options::<E>::new().to_writer(writer, value)
+}
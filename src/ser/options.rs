@@ -0,0 +1,334 @@
+//! TODO
+
+use alloc::{string::String, vec::Vec};
+use core::marker::PhantomData;
+#[cfg(feature = "std")]
+use std::io;
+
+use serde::Serialize;
+
+use super::binary::Serializer;
+#[cfg(feature = "compression")]
+use crate::binary::Compression;
+use crate::{binary::Endec, error::Result, util::DEFAULT_RECURSION_LIMIT};
+
+/// A builder for configuring how binary NBT is encoded.
+///
+/// The dialect is part of the type and is chosen by the constructor, e.g.
+/// [`Options::java`]. Everything else can be changed with the `with_*` and
+/// `without_*` methods, after which the options either encode values directly
+/// or create a configured [`Serializer`].
+///
+/// The defaults match those of [`Serializer::new`].
+#[derive(Debug, Clone)]
+pub struct Options<E> {
+    recursion_limit: Option<usize>,
+    root_name: String,
+    unnamed_root: bool,
+    #[cfg(feature = "compression")]
+    compression: Option<Compression>,
+    endec: PhantomData<E>,
+}
+
+#[cfg(feature = "be")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "be")))]
+impl Options<crate::binary::BigEndian> {
+    /// Creates the default options for Minecraft: Java Edition's big-endian
+    /// binary NBT.
+    #[must_use]
+    #[inline]
+    pub fn java() -> Self {
+        Options::new()
+    }
+}
+
+#[cfg(feature = "le")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "le")))]
+impl Options<crate::binary::LittleEndian> {
+    /// Creates the default options for the little-endian binary NBT
+    /// Minecraft: Bedrock Edition stores on disk.
+    #[must_use]
+    #[inline]
+    pub fn bedrock() -> Self {
+        Options::new()
+    }
+}
+
+#[cfg(feature = "varint")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "varint")))]
+impl Options<crate::binary::VarInt> {
+    /// Creates the default options for the [`VarInt`] binary NBT Minecraft:
+    /// Bedrock Edition sends over the network.
+    ///
+    /// [`VarInt`]: crate::binary::VarInt
+    #[must_use]
+    #[inline]
+    pub fn bedrock_network() -> Self {
+        Options::new()
+    }
+}
+
+impl<E> Options<E>
+where
+    E: Endec,
+{
+    /// Creates the default options for the dialect `E`.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Options {
+            recursion_limit: Some(DEFAULT_RECURSION_LIMIT),
+            root_name: String::new(),
+            unnamed_root: false,
+            #[cfg(feature = "compression")]
+            compression: None,
+            endec: PhantomData,
+        }
+    }
+
+    /// Sets how deeply `List`s and `Compound`s may nest.
+    ///
+    /// See [`Serializer::set_recursion_limit`].
+    #[must_use]
+    #[inline]
+    pub fn with_recursion_limit(mut self, limit: usize) -> Self {
+        self.recursion_limit = Some(limit);
+        self
+    }
+
+    /// Lets `List`s and `Compound`s nest arbitrarily deeply.
+    ///
+    /// See [`Serializer::disable_recursion_limit`].
+    #[must_use]
+    #[inline]
+    pub fn without_recursion_limit(mut self) -> Self {
+        self.recursion_limit = None;
+        self
+    }
+
+    /// Sets the name written ahead of the root tag.
+    ///
+    /// See [`Serializer::set_root_name`].
+    #[must_use]
+    #[inline]
+    pub fn with_root_name<S>(mut self, name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.root_name = name.into();
+        self
+    }
+
+    /// Writes the root tag without a name, as Java Edition sends it over the
+    /// network since 1.20.2.
+    ///
+    /// See [`Serializer::write_unnamed_root`].
+    #[must_use]
+    #[inline]
+    pub fn with_unnamed_root(mut self) -> Self {
+        self.unnamed_root = true;
+        self
+    }
+
+    /// Compresses the output after encoding it.
+    ///
+    /// Only [`to_vec`](Self::to_vec) and [`to_writer`](Self::to_writer)
+    /// compress; a serializer made by [`serializer`](Self::serializer) writes
+    /// its output as-is.
+    #[cfg(feature = "compression")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "compression")))]
+    #[must_use]
+    #[inline]
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    /// Creates a serializer with these options that writes into `writer`.
+    #[must_use]
+    pub fn serializer<W>(&self, writer: W) -> Serializer<W, E>
+    where
+        W: zc_io::Write,
+    {
+        let mut ser = Serializer::new(writer);
+
+        match self.recursion_limit {
+            Some(limit) => ser.set_recursion_limit(limit),
+            None => ser.disable_recursion_limit(),
+        }
+
+        ser.set_root_name(self.root_name.as_str());
+
+        if self.unnamed_root {
+            ser.write_unnamed_root();
+        }
+
+        ser
+    }
+
+    /// Serializes `value` as binary NBT.
+    ///
+    /// # Errors
+    ///
+    /// Serialization can fail if `value` holds a type NBT cannot represent,
+    /// if it nests too deeply, or if the output cannot be compressed.
+    pub fn to_vec<T>(&self, value: &T) -> Result<Vec<u8>>
+    where
+        T: ?Sized + Serialize,
+    {
+        #[cfg(feature = "compression")]
+        if let Some(compression) = self.compression {
+            return self.to_compressed(Vec::new(), compression, value);
+        }
+
+        let mut ser = self.serializer(Vec::new());
+        value.serialize(&mut ser)?;
+        Ok(ser.into_inner())
+    }
+
+    /// Serializes `value` as binary NBT into an I/O stream.
+    ///
+    /// The writer is not buffered internally; wrap it in a
+    /// [`BufWriter`](std::io::BufWriter) if it performs a system call per
+    /// write.
+    ///
+    /// # Errors
+    ///
+    /// Serialization can fail if the stream fails to write, if `value` holds
+    /// a type NBT cannot represent, if it nests too deeply, or if the output
+    /// cannot be compressed.
+    #[cfg(feature = "std")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+    pub fn to_writer<W, T>(&self, writer: W, value: &T) -> Result<()>
+    where
+        W: io::Write,
+        T: ?Sized + Serialize,
+    {
+        #[cfg(feature = "compression")]
+        if let Some(compression) = self.compression {
+            return self.to_compressed(writer, compression, value).map(drop);
+        }
+
+        let mut ser = self.serializer(zc_io::IoWriter::new(writer));
+        value.serialize(&mut ser)
+    }
+
+    /// Serializes `value` through a compressor into `writer`, returning the
+    /// writer once the compressed stream is complete.
+    #[cfg(feature = "compression")]
+    fn to_compressed<W, T>(&self, writer: W, compression: Compression, value: &T) -> Result<W>
+    where
+        W: io::Write,
+        T: ?Sized + Serialize,
+    {
+        let encoder = Encoder::new(writer, compression);
+        let mut ser = self.serializer(zc_io::IoWriter::new(encoder));
+        value.serialize(&mut ser)?;
+        Ok(ser.into_inner().into_inner().finish()?)
+    }
+}
+
+impl<E> Default for Options<E>
+where
+    E: Endec,
+{
+    #[inline]
+    fn default() -> Self {
+        Options::new()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Compresses a stream in whichever format the options asked for.
+#[cfg(feature = "compression")]
+enum Encoder<W>
+where
+    W: io::Write,
+{
+    Gzip(flate2::write::GzEncoder<W>),
+    Zlib(flate2::write::ZlibEncoder<W>),
+}
+
+#[cfg(feature = "compression")]
+impl<W> Encoder<W>
+where
+    W: io::Write,
+{
+    fn new(writer: W, compression: Compression) -> Self {
+        let level = flate2::Compression::default();
+
+        match compression {
+            Compression::Gzip => Encoder::Gzip(flate2::write::GzEncoder::new(writer, level)),
+            Compression::Zlib => Encoder::Zlib(flate2::write::ZlibEncoder::new(writer, level)),
+        }
+    }
+
+    /// Writes out whatever is left of the compressed stream.
+    fn finish(self) -> io::Result<W> {
+        match self {
+            Encoder::Gzip(encoder) => encoder.finish(),
+            Encoder::Zlib(encoder) => encoder.finish(),
+        }
+    }
+}
+
+#[cfg(feature = "compression")]
+impl<W> io::Write for Encoder<W>
+where
+    W: io::Write,
+{
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Encoder::Gzip(encoder) => encoder.write(buf),
+            Encoder::Zlib(encoder) => encoder.write(buf),
+        }
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Encoder::Gzip(encoder) => encoder.flush(),
+            Encoder::Zlib(encoder) => encoder.flush(),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(all(test, feature = "be"))]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::Options;
+    use crate::de;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Root {
+        x: i8,
+    }
+
+    /// A root `Compound` with no name, holding a `Byte` named `x`.
+    const UNNAMED: &[u8] = &[10, 1, 0, 1, b'x', 7, 0];
+
+    #[test]
+    fn unnamed_root_writes_only_the_id() {
+        let options = Options::java()
+            .with_root_name("ignored")
+            .with_unnamed_root();
+        let bytes = options.to_vec(&Root { x: 7 }).unwrap();
+
+        assert_eq!(bytes, UNNAMED);
+    }
+
+    #[test]
+    fn unnamed_root_round_trips() {
+        let value = de::Options::java()
+            .with_unnamed_root()
+            .from_slice::<Root>(UNNAMED)
+            .unwrap();
+
+        assert_eq!(value, Root { x: 7 });
+    }
+}
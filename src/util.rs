@@ -0,0 +1,343 @@
+//! TODO
+
+#[cfg(feature = "be")]
+use alloc::vec::Vec;
+#[cfg(any(feature = "be", feature = "le"))]
+use alloc::{borrow::Cow, string::String};
+#[cfg(any(feature = "be", feature = "le"))]
+use core::str;
+
+use crate::error::{Error, Result};
+
+////////////////////////////////////////////////////////////////////////////////
+// Private Tokens
+////////////////////////////////////////////////////////////////////////////////
+
+/// The key of the single-entry map a self-describing deserializer produces
+/// when a `ByteArray` is deserialized through `deserialize_any`.
+///
+/// NBT distinguishes arrays from lists, but serde's data model doesn't. To let
+/// [`Value`](crate::Value) recover which one it was handed, arrays are exposed
+/// as a map with a single entry keyed by one of these tokens. In the other
+/// direction, serializers write a newtype struct named after one of these
+/// tokens as the corresponding array.
+pub(crate) const BYTE_ARRAY_TOKEN: &str = "$serde_nbt::private::ByteArray";
+
+/// The `IntArray` counterpart to [`BYTE_ARRAY_TOKEN`].
+pub(crate) const INT_ARRAY_TOKEN: &str = "$serde_nbt::private::IntArray";
+
+/// The `LongArray` counterpart to [`BYTE_ARRAY_TOKEN`].
+pub(crate) const LONG_ARRAY_TOKEN: &str = "$serde_nbt::private::LongArray";
+
+/// The name of the newtype struct [`IntArrayRef`](crate::IntArrayRef)
+/// deserializes through.
+///
+/// The big- and little-endian binary deserializers recognize it on an
+/// `IntArray` and hand over the undecoded payload as an enum whose variant is
+/// whether it is big-endian, so that it can be borrowed from the input. Every
+/// other deserializer sees an ordinary newtype struct.
+pub(crate) const INT_ARRAY_REF_TOKEN: &str = "$serde_nbt::private::IntArrayRef";
+
+/// The `LongArray` counterpart to [`INT_ARRAY_REF_TOKEN`].
+pub(crate) const LONG_ARRAY_REF_TOKEN: &str = "$serde_nbt::private::LongArrayRef";
+
+/// The name of the newtype struct [`RawNbt`](crate::binary::RawNbt)
+/// deserializes through, and of the newtype variant it serializes as.
+///
+/// The binary deserializers recognize it and hand over the encoded payload
+/// of the tag as an enum whose variant is the tag's ID. In the other
+/// direction, the binary serializers write the bytes wrapped in the variant
+/// whose index is the tag's ID as-is.
+#[cfg(feature = "binary")]
+pub(crate) const RAW_NBT_TOKEN: &str = "$serde_nbt::private::RawNbt";
+
+/// The name of the newtype struct [`Named`](crate::Named) wraps a
+/// `(name, value)` pair in.
+///
+/// The binary serializers and deserializers recognize it at the root and map
+/// the pair onto the root tag's name and payload. Every other format sees an
+/// ordinary newtype struct.
+pub(crate) const NAMED_TOKEN: &str = "$serde_nbt::private::Named";
+
+////////////////////////////////////////////////////////////////////////////////
+// Recursion Limit
+////////////////////////////////////////////////////////////////////////////////
+
+/// The default limit on how deeply `List`s and `Compound`s may nest, matching
+/// the limit Minecraft itself enforces.
+pub(crate) const DEFAULT_RECURSION_LIMIT: usize = 512;
+
+/// Tracks how many more levels of `List`s and `Compound`s may be entered
+/// before the recursion limit is exceeded.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Depth {
+    /// `None` if the recursion limit is disabled.
+    remaining: Option<usize>,
+}
+
+impl Depth {
+    #[inline]
+    pub(crate) const fn new(limit: usize) -> Self {
+        Depth {
+            remaining: Some(limit),
+        }
+    }
+
+    #[inline]
+    pub(crate) const fn unbounded() -> Self {
+        Depth { remaining: None }
+    }
+
+    /// Enters one more level of nesting, failing if that would exceed the
+    /// recursion limit.
+    #[inline]
+    pub(crate) fn enter(&mut self) -> Result<()> {
+        match &mut self.remaining {
+            Some(0) => Err(Error::recursion_limit_exceeded()),
+            Some(remaining) => {
+                *remaining -= 1;
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Leaves a level of nesting previously entered with [`Depth::enter`].
+    #[inline]
+    pub(crate) fn leave(&mut self) {
+        if let Some(remaining) = &mut self.remaining {
+            *remaining += 1;
+        }
+    }
+}
+
+impl Default for Depth {
+    #[inline]
+    fn default() -> Self {
+        Depth::new(DEFAULT_RECURSION_LIMIT)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Memory Quota
+////////////////////////////////////////////////////////////////////////////////
+
+/// The memory quota Minecraft applies to NBT received over the network,
+/// 2 MiB.
+#[cfg(feature = "binary")]
+pub const NETWORK_QUOTA: usize = 2 * 1024 * 1024;
+
+/// The memory quota Minecraft applies to NBT read from disk, 100 MiB. This is
+/// also the default quota.
+#[cfg(feature = "binary")]
+pub const DISK_QUOTA: usize = 100 * 1024 * 1024;
+
+/// Tracks the memory charged against a quota while decoding, in the same
+/// units as Minecraft's `NbtAccounter`.
+///
+/// The charges approximate what the decoded tags occupy in memory, so that
+/// the quota bounds allocations rather than the size of the input.
+#[cfg(feature = "binary")]
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Quota {
+    /// `None` if the quota is disabled.
+    limit: Option<usize>,
+    used: usize,
+}
+
+#[cfg(feature = "binary")]
+impl Quota {
+    /// The charge for a `Compound`, on top of its entries.
+    pub(crate) const COMPOUND: usize = 48;
+    /// The charge for each entry of a `Compound`, on top of its name and
+    /// value.
+    pub(crate) const ENTRY: usize = 32;
+
+    #[inline]
+    pub(crate) const fn new(limit: usize) -> Self {
+        Quota {
+            limit: Some(limit),
+            used: 0,
+        }
+    }
+
+    #[inline]
+    pub(crate) const fn unlimited() -> Self {
+        Quota {
+            limit: None,
+            used: 0,
+        }
+    }
+
+    /// Charges `bytes` against the quota, failing if that would exceed it.
+    #[inline]
+    pub(crate) fn charge(&mut self, bytes: usize) -> Result<()> {
+        let Some(limit) = self.limit else {
+            return Ok(());
+        };
+
+        match self.used.checked_add(bytes) {
+            Some(used) if used <= limit => {
+                self.used = used;
+                Ok(())
+            }
+            _ => Err(Error::quota_exceeded(limit)),
+        }
+    }
+
+    /// Charges for a string of `len` bytes.
+    #[inline]
+    pub(crate) fn charge_str(&mut self, len: usize) -> Result<()> {
+        self.charge(len.saturating_mul(2).saturating_add(36))
+    }
+
+    /// Charges for a `List` of `len` elements, not counting the elements'
+    /// own payloads.
+    #[inline]
+    pub(crate) fn charge_list(&mut self, len: usize) -> Result<()> {
+        self.charge(len.saturating_mul(4).saturating_add(37))
+    }
+
+    /// Charges for an array of `len` elements that are `width` bytes each.
+    #[inline]
+    pub(crate) fn charge_array(&mut self, len: usize, width: usize) -> Result<()> {
+        self.charge(len.saturating_mul(width).saturating_add(24))
+    }
+}
+
+#[cfg(feature = "binary")]
+impl Default for Quota {
+    #[inline]
+    fn default() -> Self {
+        Quota::new(DISK_QUOTA)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// String Decoding
+////////////////////////////////////////////////////////////////////////////////
+
+/// Decodes UTF-8 into a string, borrowing from `bytes` if possible.
+///
+/// Returns `None` if the input is malformed.
+#[cfg(feature = "le")]
+pub(crate) fn decode_utf8(bytes: Cow<'_, [u8]>) -> Option<Cow<'_, str>> {
+    match bytes {
+        Cow::Borrowed(bytes) => str::from_utf8(bytes).ok().map(Cow::Borrowed),
+        Cow::Owned(bytes) => String::from_utf8(bytes).ok().map(Cow::Owned),
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Modified UTF-8
+////////////////////////////////////////////////////////////////////////////////
+
+/// Decodes Java's [modified UTF-8][mutf8] into a string, borrowing from
+/// `bytes` whenever the input also happens to be valid UTF-8.
+///
+/// Returns `None` if the input is malformed or encodes an unpaired surrogate.
+///
+/// [mutf8]: https://docs.oracle.com/javase/8/docs/api/java/io/DataInput.html#modified-utf-8
+#[cfg(feature = "be")]
+pub(crate) fn decode_mutf8(bytes: Cow<'_, [u8]>) -> Option<Cow<'_, str>> {
+    match bytes {
+        Cow::Borrowed(bytes) => match str::from_utf8(bytes) {
+            Ok(s) => Some(Cow::Borrowed(s)),
+            Err(_) => decode_mutf8_slow(bytes).map(Cow::Owned),
+        },
+        Cow::Owned(bytes) => match String::from_utf8(bytes) {
+            Ok(s) => Some(Cow::Owned(s)),
+            Err(error) => decode_mutf8_slow(error.as_bytes()).map(Cow::Owned),
+        },
+    }
+}
+
+/// Encodes a string as Java's [modified UTF-8][mutf8], borrowing it if it is
+/// already valid modified UTF-8.
+///
+/// [mutf8]: https://docs.oracle.com/javase/8/docs/api/java/io/DataInput.html#modified-utf-8
+#[cfg(feature = "be")]
+pub(crate) fn encode_mutf8(s: &str) -> Cow<'_, [u8]> {
+    // Only NUL and supplementary characters are encoded differently.
+    if s.bytes().all(|byte| byte != 0 && byte < 0xF0) {
+        return Cow::Borrowed(s.as_bytes());
+    }
+
+    let mut bytes = Vec::with_capacity(s.len() + s.len() / 2);
+
+    for c in s.chars() {
+        match c {
+            '\0' => bytes.extend_from_slice(&[0xC0, 0x80]),
+            c if c.len_utf16() == 2 => {
+                let mut units = [0; 2];
+
+                for unit in c.encode_utf16(&mut units) {
+                    let unit = *unit;
+                    #[allow(clippy::cast_possible_truncation)]
+                    bytes.extend_from_slice(&[
+                        0xE0 | (unit >> 12) as u8,
+                        0x80 | ((unit >> 6) & 0x3F) as u8,
+                        0x80 | (unit & 0x3F) as u8,
+                    ]);
+                }
+            }
+            c => bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+        }
+    }
+
+    Cow::Owned(bytes)
+}
+
+#[cfg(feature = "be")]
+fn decode_mutf8_slow(bytes: &[u8]) -> Option<String> {
+    let mut string = String::with_capacity(bytes.len());
+    let mut iter = bytes.iter().copied();
+
+    let continuation = |iter: &mut dyn Iterator<Item = u8>| match iter.next() {
+        Some(byte) if byte & 0xC0 == 0x80 => Some(u32::from(byte & 0x3F)),
+        _ => None,
+    };
+
+    while let Some(byte) = iter.next() {
+        let code_point = match byte {
+            0x00..=0x7F => u32::from(byte),
+            0xC0..=0xDF => (u32::from(byte & 0x1F) << 6) | continuation(&mut iter)?,
+            0xE0..=0xEF => {
+                let high = (u32::from(byte & 0x0F) << 12)
+                    | (continuation(&mut iter)? << 6)
+                    | continuation(&mut iter)?;
+
+                if (0xD800..0xDC00).contains(&high) {
+                    // Supplementary characters are encoded as a surrogate
+                    // pair, with each half taking up three bytes.
+                    if iter.next()? != 0xED {
+                        return None;
+                    }
+
+                    let low = 0xD000 | (continuation(&mut iter)? << 6) | continuation(&mut iter)?;
+
+                    if !(0xDC00..0xE000).contains(&low) {
+                        return None;
+                    }
+
+                    0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+                } else {
+                    high
+                }
+            }
+            // Strictly speaking, four byte sequences aren't valid modified
+            // UTF-8, but some encoders emit them anyway.
+            0xF0..=0xF7 => {
+                (u32::from(byte & 0x07) << 18)
+                    | (continuation(&mut iter)? << 12)
+                    | (continuation(&mut iter)? << 6)
+                    | continuation(&mut iter)?
+            }
+            _ => return None,
+        };
+
+        string.push(char::from_u32(code_point)?);
+    }
+
+    Some(string)
+}
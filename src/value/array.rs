@@ -0,0 +1,543 @@
+//! Borrowed views over the payloads of `IntArray`s and `LongArray`s.
+
+use alloc::{borrow::Cow, vec::Vec};
+use core::{fmt, iter::FusedIterator, marker::PhantomData, slice};
+
+use serde::{
+    de::{
+        self, Deserialize, Deserializer, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor,
+    },
+    ser::{Serialize, Serializer},
+};
+
+use crate::util::{INT_ARRAY_REF_TOKEN, INT_ARRAY_TOKEN, LONG_ARRAY_REF_TOKEN, LONG_ARRAY_TOKEN};
+
+/// How the elements of an array view are stored.
+#[derive(Clone)]
+enum Repr<'a, T>
+where
+    T: Clone,
+{
+    /// Undecoded fixed-width integers, straight from the input.
+    Raw { bytes: &'a [u8], big_endian: bool },
+    /// Elements that have already been decoded.
+    Decoded(Cow<'a, [T]>),
+}
+
+enum IterRepr<'a, T> {
+    Raw {
+        chunks: slice::ChunksExact<'a, u8>,
+        big_endian: bool,
+    },
+    Decoded(slice::Iter<'a, T>),
+}
+
+/// The payload of an array as handed over by a deserializer, borrowed from
+/// the input if possible.
+enum RawBytes<'de> {
+    Borrowed(&'de [u8]),
+    Owned(Vec<u8>),
+}
+
+impl<'de> Deserialize<'de> for RawBytes<'de> {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_bytes(RawBytesVisitor)
+    }
+}
+
+struct RawBytesVisitor;
+
+impl<'de> Visitor<'de> for RawBytesVisitor {
+    type Value = RawBytes<'de>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("the raw payload of an array")
+    }
+
+    #[inline]
+    fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(RawBytes::Borrowed(v))
+    }
+
+    #[inline]
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(RawBytes::Owned(v.to_vec()))
+    }
+
+    #[inline]
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(RawBytes::Owned(v))
+    }
+}
+
+macro_rules! array_ref {
+    (
+        $(#[$attr:meta])*
+        $name:ident, $iter:ident, $element:ty, $width:literal,
+        $tag:literal, $token:ident, $ref_token:ident
+    ) => {
+        $(#[$attr])*
+        #[derive(Clone)]
+        pub struct $name<'a> {
+            repr: Repr<'a, $element>,
+        }
+
+        impl<'a> $name<'a> {
+            /// Creates a view over big-endian encoded elements.
+            ///
+            /// Returns `None` if the length of `bytes` is not a multiple of
+            #[doc = concat!("the size of an element, ", stringify!($width), " bytes.")]
+            #[must_use]
+            #[inline]
+            pub fn from_be_bytes(bytes: &'a [u8]) -> Option<Self> {
+                Self::from_raw(bytes, true)
+            }
+
+            /// Creates a view over little-endian encoded elements.
+            ///
+            /// Returns `None` if the length of `bytes` is not a multiple of
+            #[doc = concat!("the size of an element, ", stringify!($width), " bytes.")]
+            #[must_use]
+            #[inline]
+            pub fn from_le_bytes(bytes: &'a [u8]) -> Option<Self> {
+                Self::from_raw(bytes, false)
+            }
+
+            #[inline]
+            fn from_raw(bytes: &'a [u8], big_endian: bool) -> Option<Self> {
+                if bytes.len() % $width == 0 {
+                    Some($name {
+                        repr: Repr::Raw { bytes, big_endian },
+                    })
+                } else {
+                    None
+                }
+            }
+
+            /// Returns the number of elements in the array.
+            #[must_use]
+            #[inline]
+            pub fn len(&self) -> usize {
+                match &self.repr {
+                    Repr::Raw { bytes, .. } => bytes.len() / $width,
+                    Repr::Decoded(elements) => elements.len(),
+                }
+            }
+
+            /// Returns `true` if the array has no elements.
+            #[must_use]
+            #[inline]
+            pub fn is_empty(&self) -> bool {
+                self.len() == 0
+            }
+
+            /// Returns `true` if the elements are still encoded in the input
+            /// they were borrowed from, and are decoded as they are read.
+            #[must_use]
+            #[inline]
+            pub fn is_raw(&self) -> bool {
+                matches!(self.repr, Repr::Raw { .. })
+            }
+
+            /// Decodes the element at `index`, or returns `None` if it is out
+            /// of bounds.
+            #[must_use]
+            #[inline]
+            pub fn get(&self, index: usize) -> Option<$element> {
+                match &self.repr {
+                    Repr::Raw { bytes, big_endian } => bytes
+                        .chunks_exact($width)
+                        .nth(index)
+                        .map(|chunk| decode(chunk, *big_endian)),
+                    Repr::Decoded(elements) => elements.get(index).copied(),
+                }
+            }
+
+            /// Returns an iterator that decodes the elements one at a time.
+            #[inline]
+            pub fn iter(&self) -> $iter<'_> {
+                let repr = match &self.repr {
+                    Repr::Raw { bytes, big_endian } => IterRepr::Raw {
+                        chunks: bytes.chunks_exact($width),
+                        big_endian: *big_endian,
+                    },
+                    Repr::Decoded(elements) => IterRepr::Decoded(elements.iter()),
+                };
+
+                $iter { repr }
+            }
+
+            /// Decodes every element into a new vector.
+            #[must_use]
+            pub fn to_vec(&self) -> Vec<$element> {
+                self.iter().collect()
+            }
+
+            /// Decodes the elements if they are borrowed, so that the view no
+            /// longer borrows from the input.
+            #[must_use]
+            pub fn into_owned(self) -> $name<'static> {
+                $name {
+                    repr: Repr::Decoded(Cow::Owned(self.into_vec())),
+                }
+            }
+
+            /// Decodes every element into a vector, reusing the one the view
+            /// owns if there is one.
+            #[must_use]
+            pub fn into_vec(self) -> Vec<$element> {
+                match self.repr {
+                    Repr::Raw { .. } => self.to_vec(),
+                    Repr::Decoded(elements) => elements.into_owned(),
+                }
+            }
+        }
+
+        /// Decodes a single element from exactly `$width` bytes.
+        #[inline]
+        fn decode(chunk: &[u8], big_endian: bool) -> $element {
+            let mut bytes = [0; $width];
+            bytes.copy_from_slice(chunk);
+
+            if big_endian {
+                <$element>::from_be_bytes(bytes)
+            } else {
+                <$element>::from_le_bytes(bytes)
+            }
+        }
+
+        impl Default for $name<'_> {
+            #[inline]
+            fn default() -> Self {
+                $name {
+                    repr: Repr::Decoded(Cow::Borrowed(&[])),
+                }
+            }
+        }
+
+        impl fmt::Debug for $name<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_list().entries(self.iter()).finish()
+            }
+        }
+
+        impl PartialEq for $name<'_> {
+            fn eq(&self, other: &Self) -> bool {
+                self.len() == other.len() && self.iter().eq(other.iter())
+            }
+        }
+
+        impl Eq for $name<'_> {}
+
+        impl<'a> From<&'a [$element]> for $name<'a> {
+            #[inline]
+            fn from(elements: &'a [$element]) -> Self {
+                $name {
+                    repr: Repr::Decoded(Cow::Borrowed(elements)),
+                }
+            }
+        }
+
+        impl From<Vec<$element>> for $name<'_> {
+            #[inline]
+            fn from(elements: Vec<$element>) -> Self {
+                $name {
+                    repr: Repr::Decoded(Cow::Owned(elements)),
+                }
+            }
+        }
+
+        impl From<$name<'_>> for Vec<$element> {
+            #[inline]
+            fn from(array: $name<'_>) -> Self {
+                array.into_vec()
+            }
+        }
+
+        impl<'b> IntoIterator for &'b $name<'_> {
+            type Item = $element;
+            type IntoIter = $iter<'b>;
+
+            #[inline]
+            fn into_iter(self) -> Self::IntoIter {
+                self.iter()
+            }
+        }
+
+        #[doc = concat!("An iterator over the elements of an [`", stringify!($name), "`].")]
+        ///
+        #[doc = concat!("This struct is created by [`", stringify!($name), "::iter`].")]
+        pub struct $iter<'a> {
+            repr: IterRepr<'a, $element>,
+        }
+
+        impl Iterator for $iter<'_> {
+            type Item = $element;
+
+            #[inline]
+            fn next(&mut self) -> Option<Self::Item> {
+                match &mut self.repr {
+                    IterRepr::Raw { chunks, big_endian } => {
+                        chunks.next().map(|chunk| decode(chunk, *big_endian))
+                    }
+                    IterRepr::Decoded(elements) => elements.next().copied(),
+                }
+            }
+
+            #[inline]
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                match &self.repr {
+                    IterRepr::Raw { chunks, .. } => chunks.size_hint(),
+                    IterRepr::Decoded(elements) => elements.size_hint(),
+                }
+            }
+        }
+
+        impl DoubleEndedIterator for $iter<'_> {
+            #[inline]
+            fn next_back(&mut self) -> Option<Self::Item> {
+                match &mut self.repr {
+                    IterRepr::Raw { chunks, big_endian } => {
+                        chunks.next_back().map(|chunk| decode(chunk, *big_endian))
+                    }
+                    IterRepr::Decoded(elements) => elements.next_back().copied(),
+                }
+            }
+        }
+
+        impl ExactSizeIterator for $iter<'_> {}
+
+        impl FusedIterator for $iter<'_> {}
+
+        impl Serialize for $name<'_> {
+            #[inline]
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                serializer.serialize_newtype_struct($token, &Elements(self))
+            }
+        }
+
+        /// Serializes the elements of a view as a sequence.
+        struct Elements<'b, 'a>(&'b $name<'a>);
+
+        impl Serialize for Elements<'_, '_> {
+            #[inline]
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                serializer.collect_seq(self.0.iter())
+            }
+        }
+
+        impl<'de: 'a, 'a> Deserialize<'de> for $name<'a> {
+            #[inline]
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                deserializer.deserialize_newtype_struct($ref_token, ArrayRefVisitor {
+                    lifetime: PhantomData,
+                })
+            }
+        }
+
+        struct ArrayRefVisitor<'a> {
+            lifetime: PhantomData<&'a ()>,
+        }
+
+        impl<'de: 'a, 'a> Visitor<'de> for ArrayRefVisitor<'a> {
+            type Value = $name<'a>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str(concat!("an ", $tag))
+            }
+
+            /// Called by the binary deserializers with the byte order of the
+            /// dialect and the raw payload.
+            fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+            where
+                A: EnumAccess<'de>,
+            {
+                let (big_endian, payload) = data.variant::<bool>()?;
+
+                match payload.newtype_variant()? {
+                    RawBytes::Borrowed(bytes) => $name::from_raw(bytes, big_endian)
+                        .ok_or_else(|| de::Error::invalid_length(bytes.len(), &self)),
+                    RawBytes::Owned(bytes) => match $name::from_raw(&bytes, big_endian) {
+                        Some(array) => Ok(array.to_vec().into()),
+                        None => Err(de::Error::invalid_length(bytes.len(), &self)),
+                    },
+                }
+            }
+
+            /// Called by deserializers that don't borrow arrays, which hand
+            /// over the tag itself.
+            #[inline]
+            fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                Vec::<$element>::deserialize(deserializer).map($name::from)
+            }
+
+            fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                Vec::<$element>::deserialize(de::value::SeqAccessDeserializer::new(seq))
+                    .map($name::from)
+            }
+
+            /// Called through `deserialize_any` with the single-entry map that
+            /// self-describing deserializers expose arrays as.
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                match map.next_key::<Cow<'_, str>>()? {
+                    Some(key) if key == $token => map.next_value(),
+                    _ => Err(de::Error::invalid_type(de::Unexpected::Map, &self)),
+                }
+            }
+        }
+    };
+}
+
+mod int {
+    use super::{
+        de, fmt, Cow, Deserialize, Deserializer, EnumAccess, FusedIterator, IterRepr, MapAccess,
+        PhantomData, RawBytes, Repr, SeqAccess, Serialize, Serializer, VariantAccess, Vec, Visitor,
+        INT_ARRAY_REF_TOKEN, INT_ARRAY_TOKEN,
+    };
+
+    array_ref!(
+        /// A view over the payload of an `IntArray`.
+        ///
+        /// Deserializing from a slice of big- or little-endian binary NBT
+        /// borrows the encoded elements straight from the input and decodes
+        /// them as they are read, so large arrays such as heightmaps can be
+        /// scanned without allocating. Input that cannot be borrowed, such as
+        /// a stream or a dialect that does not store elements at a fixed
+        /// width, is decoded up front instead.
+        ///
+        /// A field of this type needs `#[serde(borrow)]` to borrow from the
+        /// input.
+        IntArrayRef, IntArrayIter, i32, 4,
+        "IntArray", INT_ARRAY_TOKEN, INT_ARRAY_REF_TOKEN
+    );
+}
+
+mod long {
+    use super::{
+        de, fmt, Cow, Deserialize, Deserializer, EnumAccess, FusedIterator, IterRepr, MapAccess,
+        PhantomData, RawBytes, Repr, SeqAccess, Serialize, Serializer, VariantAccess, Vec, Visitor,
+        LONG_ARRAY_REF_TOKEN, LONG_ARRAY_TOKEN,
+    };
+
+    array_ref!(
+        /// A view over the payload of a `LongArray`.
+        ///
+        /// Deserializing from a slice of big- or little-endian binary NBT
+        /// borrows the encoded elements straight from the input and decodes
+        /// them as they are read, so packed block states can be scanned
+        /// without allocating. Input that cannot be borrowed is decoded up
+        /// front instead.
+        ///
+        /// A field of this type needs `#[serde(borrow)]` to borrow from the
+        /// input.
+        LongArrayRef, LongArrayIter, i64, 8,
+        "LongArray", LONG_ARRAY_TOKEN, LONG_ARRAY_REF_TOKEN
+    );
+}
+
+pub use self::{
+    int::{IntArrayIter, IntArrayRef},
+    long::{LongArrayIter, LongArrayRef},
+};
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::{IntArrayRef, LongArrayRef};
+
+    #[test]
+    fn views_decode_either_byte_order() {
+        let be = IntArrayRef::from_be_bytes(&[0, 0, 0, 1, 0xFF, 0xFF, 0xFF, 0xFE]).unwrap();
+        let le = IntArrayRef::from_le_bytes(&[1, 0, 0, 0, 0xFE, 0xFF, 0xFF, 0xFF]).unwrap();
+
+        assert!(be.is_raw());
+        assert_eq!(be.len(), 2);
+        assert_eq!(be.get(1), Some(-2));
+        assert_eq!(be.get(2), None);
+        assert_eq!(be.to_vec(), [1, -2]);
+        assert_eq!(be, le);
+    }
+
+    #[test]
+    fn views_reject_partial_elements() {
+        assert!(IntArrayRef::from_be_bytes(&[0; 6]).is_none());
+        assert!(LongArrayRef::from_le_bytes(&[0; 12]).is_none());
+    }
+
+    #[test]
+    fn owned_views_no_longer_borrow() {
+        let view = LongArrayRef::from_be_bytes(&[0, 0, 0, 0, 0, 0, 0, 7]).unwrap();
+        let owned = view.clone().into_owned();
+
+        assert!(!owned.is_raw());
+        assert_eq!(owned, view);
+        assert_eq!(owned.into_vec(), vec![7]);
+    }
+
+    #[cfg(feature = "be")]
+    #[test]
+    fn views_borrow_from_binary_nbt() {
+        use serde::{Deserialize, Serialize};
+
+        use crate::{de, ser};
+
+        #[derive(Serialize, Deserialize)]
+        struct Chunk<'a> {
+            #[serde(borrow)]
+            heights: LongArrayRef<'a>,
+            #[serde(borrow)]
+            biomes: IntArrayRef<'a>,
+        }
+
+        let chunk = Chunk {
+            heights: vec![1, i64::MIN].into(),
+            biomes: vec![-1, 2, 3].into(),
+        };
+
+        let bytes = ser::to_be_vec(&chunk).unwrap();
+        let borrowed = de::from_be_slice::<Chunk>(&bytes).unwrap();
+        assert!(borrowed.heights.is_raw());
+        assert_eq!(borrowed.heights, chunk.heights);
+        assert_eq!(borrowed.biomes, chunk.biomes);
+
+        let value = de::from_be_slice::<crate::Value>(&bytes).unwrap();
+        let decoded = Chunk::deserialize(de::value::Deserializer::new(value)).unwrap();
+        assert!(!decoded.heights.is_raw());
+        assert_eq!(decoded.heights, chunk.heights);
+        assert_eq!(decoded.biomes, chunk.biomes);
+    }
+}
@@ -0,0 +1,521 @@
+//! A [`Value`] that borrows from the input it was deserialized from.
+
+use super::{Byte, Compound, IntArrayRef, List, LongArrayRef, Type, Value};
+use crate::{
+    error::{Category, Detail, Error, Result},
+    map::Map,
+};
+
+use alloc::{
+    borrow::{Cow, ToOwned},
+    format,
+    vec::Vec,
+};
+
+/// A borrowed counterpart to [`Value`].
+///
+/// Deserializing a `ValueRef` from a slice borrows strings, names and arrays
+/// straight from the input wherever their encoded form can be used as-is, so
+/// read-only scans over large amounts of NBT allocate little beyond the tree
+/// itself. Anything that cannot be borrowed, such as a string that needs
+/// converting from Modified UTF-8 or input read from a stream, is owned
+/// instead.
+///
+/// `ByteArray`s are borrowed as raw bytes, so their elements are exposed as
+/// `u8` rather than [`Byte`].
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValueRef<'a> {
+    /// A `Byte`.
+    Byte(Byte),
+    /// A `Short`.
+    Short(i16),
+    /// An `Int`.
+    Int(i32),
+    /// A `Long`.
+    Long(i64),
+    /// A `Float`.
+    Float(f32),
+    /// A `Double`.
+    Double(f64),
+    /// A `ByteArray`, as raw bytes.
+    ByteArray(Cow<'a, [u8]>),
+    /// A `String`.
+    String(Cow<'a, str>),
+    /// A `List`, whose elements all share the same type.
+    List(Vec<ValueRef<'a>>),
+    /// A `Compound`.
+    Compound(CompoundRef<'a>),
+    /// An `IntArray`.
+    IntArray(IntArrayRef<'a>),
+    /// A `LongArray`.
+    LongArray(LongArrayRef<'a>),
+}
+
+/// A borrowed counterpart to [`Compound`](super::Compound).
+pub type CompoundRef<'a> = Map<Cow<'a, str>, ValueRef<'a>>;
+
+impl<'a> ValueRef<'a> {
+    /// Returns the type of this value.
+    #[must_use]
+    #[inline]
+    pub const fn ty(&self) -> Type {
+        match self {
+            ValueRef::Byte(_) => Type::Byte,
+            ValueRef::Short(_) => Type::Short,
+            ValueRef::Int(_) => Type::Int,
+            ValueRef::Long(_) => Type::Long,
+            ValueRef::Float(_) => Type::Float,
+            ValueRef::Double(_) => Type::Double,
+            ValueRef::ByteArray(_) => Type::ByteArray,
+            ValueRef::String(_) => Type::String,
+            ValueRef::List(_) => Type::List,
+            ValueRef::Compound(_) => Type::Compound,
+            ValueRef::IntArray(_) => Type::IntArray,
+            ValueRef::LongArray(_) => Type::LongArray,
+        }
+    }
+
+    /// Converts this value into an owned [`Value`], copying everything it
+    /// borrows.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of [`Category::InvalidData`] if a `List` holds
+    /// elements of different types, which cannot happen to a value that was
+    /// deserialized. The path of the error leads to the first element that
+    /// does not match.
+    ///
+    /// [`Category::InvalidData`]: crate::error::Category::InvalidData
+    pub fn to_owned(&self) -> Result<Value> {
+        let value = match self {
+            ValueRef::Byte(v) => Value::Byte(*v),
+            ValueRef::Short(v) => Value::Short(*v),
+            ValueRef::Int(v) => Value::Int(*v),
+            ValueRef::Long(v) => Value::Long(*v),
+            ValueRef::Float(v) => Value::Float(*v),
+            ValueRef::Double(v) => Value::Double(*v),
+            ValueRef::ByteArray(v) => {
+                #[allow(clippy::cast_possible_wrap)]
+                let array = v.iter().map(|&byte| Byte::Integer(byte as i8)).collect();
+                Value::ByteArray(array)
+            }
+            ValueRef::String(v) => Value::String((**v).to_owned()),
+            ValueRef::List(v) => Value::List(list_to_owned(v)?),
+            ValueRef::Compound(v) => {
+                let mut compound = Compound::with_capacity(v.len());
+
+                for (key, value) in v {
+                    let value = value
+                        .to_owned()
+                        .map_err(|error| error.prepend_path(&**key))?;
+                    compound.insert((**key).to_owned(), value);
+                }
+
+                Value::Compound(compound)
+            }
+            ValueRef::IntArray(v) => Value::IntArray(v.to_vec()),
+            ValueRef::LongArray(v) => Value::LongArray(v.to_vec()),
+        };
+
+        Ok(value)
+    }
+
+    ////////////////////////////////////////////////////////////////////////////
+    // `is_*` Methods
+    ////////////////////////////////////////////////////////////////////////////
+
+    /// Returns `true` if this value is a `Byte`.
+    #[must_use]
+    #[inline]
+    pub const fn is_byte(&self) -> bool {
+        matches!(self, ValueRef::Byte(_))
+    }
+
+    /// Returns `true` if this value is a `Short`.
+    #[must_use]
+    #[inline]
+    pub const fn is_short(&self) -> bool {
+        matches!(self, ValueRef::Short(_))
+    }
+
+    /// Returns `true` if this value is an `Int`.
+    #[must_use]
+    #[inline]
+    pub const fn is_int(&self) -> bool {
+        matches!(self, ValueRef::Int(_))
+    }
+
+    /// Returns `true` if this value is a `Long`.
+    #[must_use]
+    #[inline]
+    pub const fn is_long(&self) -> bool {
+        matches!(self, ValueRef::Long(_))
+    }
+
+    /// Returns `true` if this value is a `Float`.
+    #[must_use]
+    #[inline]
+    pub const fn is_float(&self) -> bool {
+        matches!(self, ValueRef::Float(_))
+    }
+
+    /// Returns `true` if this value is a `Double`.
+    #[must_use]
+    #[inline]
+    pub const fn is_double(&self) -> bool {
+        matches!(self, ValueRef::Double(_))
+    }
+
+    /// Returns `true` if this value is a `ByteArray`.
+    #[must_use]
+    #[inline]
+    pub const fn is_byte_array(&self) -> bool {
+        matches!(self, ValueRef::ByteArray(_))
+    }
+
+    /// Returns `true` if this value is a `String`.
+    #[must_use]
+    #[inline]
+    pub const fn is_string(&self) -> bool {
+        matches!(self, ValueRef::String(_))
+    }
+
+    /// Returns `true` if this value is a `List`.
+    #[must_use]
+    #[inline]
+    pub const fn is_list(&self) -> bool {
+        matches!(self, ValueRef::List(_))
+    }
+
+    /// Returns `true` if this value is a `Compound`.
+    #[must_use]
+    #[inline]
+    pub const fn is_compound(&self) -> bool {
+        matches!(self, ValueRef::Compound(_))
+    }
+
+    /// Returns `true` if this value is an `IntArray`.
+    #[must_use]
+    #[inline]
+    pub const fn is_int_array(&self) -> bool {
+        matches!(self, ValueRef::IntArray(_))
+    }
+
+    /// Returns `true` if this value is a `LongArray`.
+    #[must_use]
+    #[inline]
+    pub const fn is_long_array(&self) -> bool {
+        matches!(self, ValueRef::LongArray(_))
+    }
+
+    ////////////////////////////////////////////////////////////////////////////
+    // `as_*` Methods
+    ////////////////////////////////////////////////////////////////////////////
+
+    /// Returns the payload of a `Byte`, or `None` for any other type.
+    #[must_use]
+    #[inline]
+    pub const fn as_byte(&self) -> Option<Byte> {
+        if let ValueRef::Byte(value) = self {
+            Some(*value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the payload of a `Short`, or `None` for any other type.
+    #[must_use]
+    #[inline]
+    pub const fn as_short(&self) -> Option<i16> {
+        if let ValueRef::Short(value) = self {
+            Some(*value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the payload of an `Int`, or `None` for any other type.
+    #[must_use]
+    #[inline]
+    pub const fn as_int(&self) -> Option<i32> {
+        if let ValueRef::Int(value) = self {
+            Some(*value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the payload of a `Long`, or `None` for any other type.
+    #[must_use]
+    #[inline]
+    pub const fn as_long(&self) -> Option<i64> {
+        if let ValueRef::Long(value) = self {
+            Some(*value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the payload of a `Float`, or `None` for any other type.
+    #[must_use]
+    #[inline]
+    pub const fn as_float(&self) -> Option<f32> {
+        if let ValueRef::Float(value) = self {
+            Some(*value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the payload of a `Double`, or `None` for any other type.
+    #[must_use]
+    #[inline]
+    pub const fn as_double(&self) -> Option<f64> {
+        if let ValueRef::Double(value) = self {
+            Some(*value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the bytes of a `ByteArray`, or `None` for any other type.
+    #[must_use]
+    #[inline]
+    pub fn as_byte_array(&self) -> Option<&[u8]> {
+        if let ValueRef::ByteArray(value) = self {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the payload of a `String`, or `None` for any other type.
+    #[must_use]
+    #[inline]
+    pub fn as_string(&self) -> Option<&str> {
+        if let ValueRef::String(value) = self {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the elements of a `List`, or `None` for any other type.
+    #[must_use]
+    #[inline]
+    pub fn as_list(&self) -> Option<&[ValueRef<'a>]> {
+        if let ValueRef::List(value) = self {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the entries of a `Compound`, or `None` for any other type.
+    #[must_use]
+    #[inline]
+    pub const fn as_compound(&self) -> Option<&CompoundRef<'a>> {
+        if let ValueRef::Compound(value) = self {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the elements of an `IntArray`, or `None` for any other type.
+    #[must_use]
+    #[inline]
+    pub const fn as_int_array(&self) -> Option<&IntArrayRef<'a>> {
+        if let ValueRef::IntArray(value) = self {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the elements of a `LongArray`, or `None` for any other type.
+    #[must_use]
+    #[inline]
+    pub const fn as_long_array(&self) -> Option<&LongArrayRef<'a>> {
+        if let ValueRef::LongArray(value) = self {
+            Some(value)
+        } else {
+            None
+        }
+    }
+}
+
+impl TryFrom<ValueRef<'_>> for Value {
+    type Error = Error;
+
+    #[inline]
+    fn try_from(value: ValueRef<'_>) -> Result<Self> {
+        value.to_owned()
+    }
+}
+
+/// Copies the elements of a borrowed `List`, checking that they all share the
+/// type of the first.
+fn list_to_owned(elements: &[ValueRef<'_>]) -> Result<List> {
+    let mut list = List::Empty;
+
+    for (index, element) in elements.iter().enumerate() {
+        let element = element
+            .to_owned()
+            .map_err(|error| error.prepend_path(index))?;
+
+        if let Some(expected) = list.ty() {
+            let found = element.ty();
+
+            if found != expected {
+                let message = format!("list elements must all be {expected}, found {found}");
+                let error =
+                    Error::new(Category::InvalidData, message).with_detail(Detail::InvalidType {
+                        expected: Some(expected),
+                        found: Some(found),
+                    });
+                return Err(error.prepend_path(index));
+            }
+        }
+
+        list.push(element);
+    }
+
+    Ok(list)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(all(test, feature = "be"))]
+mod tests {
+    use alloc::{borrow::Cow, string::ToString, vec};
+
+    use super::{CompoundRef, ValueRef};
+    use crate::{
+        de,
+        error::{Category, Detail},
+        ser, Byte, Compound, List, Type, Value,
+    };
+
+    /// A `Compound` holding one tag of every type.
+    fn every_type() -> Value {
+        let mut nested = Compound::new();
+        nested.insert("name".into(), Value::String("nested".into()));
+
+        let mut compound = Compound::new();
+        compound.insert("byte".into(), Value::Byte(Byte::Integer(-1)));
+        compound.insert("short".into(), Value::Short(2));
+        compound.insert("int".into(), Value::Int(3));
+        compound.insert("long".into(), Value::Long(4));
+        compound.insert("float".into(), Value::Float(5.5));
+        compound.insert("double".into(), Value::Double(6.5));
+        compound.insert(
+            "bytes".into(),
+            Value::ByteArray(vec![Byte::Integer(1), Byte::Integer(-2)]),
+        );
+        compound.insert("string".into(), Value::String("text".into()));
+        compound.insert(
+            "list".into(),
+            Value::List(List::from_vec(vec![Value::Int(7), Value::Int(8)])),
+        );
+        compound.insert("compound".into(), Value::Compound(nested));
+        compound.insert("ints".into(), Value::IntArray(vec![9, 10]));
+        compound.insert("longs".into(), Value::LongArray(vec![11, 12]));
+        Value::Compound(compound)
+    }
+
+    #[test]
+    fn borrows_strings_and_arrays() {
+        let bytes = ser::to_be_vec(&every_type()).unwrap();
+        let value = de::from_be_slice::<ValueRef>(&bytes).unwrap();
+        let compound = value.as_compound().unwrap();
+
+        assert!(compound.keys().all(|key| matches!(key, Cow::Borrowed(_))));
+        assert!(matches!(
+            compound.get("string").unwrap(),
+            ValueRef::String(Cow::Borrowed("text"))
+        ));
+        assert!(matches!(
+            compound.get("bytes").unwrap(),
+            ValueRef::ByteArray(Cow::Borrowed(&[1, 0xFE]))
+        ));
+        assert!(compound
+            .get("ints")
+            .unwrap()
+            .as_int_array()
+            .unwrap()
+            .is_raw());
+        assert!(compound
+            .get("longs")
+            .unwrap()
+            .as_long_array()
+            .unwrap()
+            .is_raw());
+    }
+
+    #[test]
+    fn accessors_match_the_type() {
+        let bytes = ser::to_be_vec(&every_type()).unwrap();
+        let value = de::from_be_slice::<ValueRef>(&bytes).unwrap();
+        let compound = value.as_compound().unwrap();
+
+        assert!(value.is_compound());
+        assert_eq!(
+            compound.get("byte").unwrap().as_byte(),
+            Some(Byte::Integer(-1))
+        );
+        assert_eq!(compound.get("short").unwrap().as_short(), Some(2));
+        assert_eq!(compound.get("int").unwrap().as_int(), Some(3));
+        assert_eq!(compound.get("long").unwrap().as_long(), Some(4));
+        assert_eq!(compound.get("float").unwrap().as_float(), Some(5.5));
+        assert_eq!(compound.get("double").unwrap().as_double(), Some(6.5));
+        assert_eq!(compound.get("string").unwrap().as_string(), Some("text"));
+        assert_eq!(
+            compound.get("list").unwrap().as_list().map(<[_]>::len),
+            Some(2)
+        );
+        assert_eq!(compound.get("ints").unwrap().ty(), Type::IntArray);
+        assert!(compound.get("int").unwrap().as_long().is_none());
+        assert!(!compound.get("int").unwrap().is_long());
+    }
+
+    #[test]
+    fn to_owned_matches_value() {
+        let bytes = ser::to_be_vec(&every_type()).unwrap();
+        let value = de::from_be_slice::<ValueRef>(&bytes).unwrap();
+
+        assert_eq!(
+            value.to_owned().unwrap(),
+            de::from_be_slice::<Value>(&bytes).unwrap()
+        );
+
+        // Compounds may be written back in a different order, so compare the
+        // decoded trees rather than the bytes.
+        let rewritten = ser::to_be_vec(&value).unwrap();
+        assert_eq!(
+            de::from_be_slice::<Value>(&rewritten).unwrap(),
+            every_type()
+        );
+    }
+
+    #[test]
+    fn to_owned_rejects_a_mixed_list() {
+        let mut compound = CompoundRef::new();
+        let list = vec![ValueRef::Int(1), ValueRef::Short(2)];
+        compound.insert(Cow::Borrowed("list"), ValueRef::List(list));
+        let value = ValueRef::Compound(compound);
+
+        let error = value.to_owned().unwrap_err();
+        assert_eq!(error.category(), Category::InvalidData);
+        assert_eq!(error.path().unwrap().to_string(), "list[1]");
+        assert_eq!(
+            error.detail(),
+            Some(&Detail::InvalidType {
+                expected: Some(Type::Int),
+                found: Some(Type::Short),
+            })
+        );
+        assert!(Value::try_from(value).is_err());
+    }
+}
@@ -0,0 +1,707 @@
+//! TODO
+
+use super::{
+    Byte, ByteArray, Compound, CompoundRef, IntArray, IntArrayRef, List, LongArray, LongArrayRef,
+    Value, ValueRef,
+};
+use crate::{
+    map::Map,
+    util::{BYTE_ARRAY_TOKEN, INT_ARRAY_TOKEN, LONG_ARRAY_TOKEN},
+};
+
+use alloc::{
+    borrow::{Cow, ToOwned},
+    format,
+    string::String,
+    vec::Vec,
+};
+use core::{
+    fmt,
+    hash::{BuildHasher, Hash},
+    marker::PhantomData,
+};
+
+use serde::de::{
+    self, Deserialize, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Unexpected, Visitor,
+};
+
+////////////////////////////////////////////////////////////////////////////////
+// `Value`
+////////////////////////////////////////////////////////////////////////////////
+
+impl<'de> Deserialize<'de> for Value {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any valid NBT value")
+    }
+
+    #[inline]
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(Value::Byte(Byte::Boolean(v)))
+    }
+
+    #[inline]
+    fn visit_i8<E>(self, v: i8) -> Result<Self::Value, E> {
+        Ok(Value::Byte(Byte::Integer(v)))
+    }
+
+    #[inline]
+    fn visit_i16<E>(self, v: i16) -> Result<Self::Value, E> {
+        Ok(Value::Short(v))
+    }
+
+    #[inline]
+    fn visit_i32<E>(self, v: i32) -> Result<Self::Value, E> {
+        Ok(Value::Int(v))
+    }
+
+    #[inline]
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(Value::Long(v))
+    }
+
+    #[inline]
+    fn visit_u8<E>(self, v: u8) -> Result<Self::Value, E> {
+        Ok(Value::Short(v.into()))
+    }
+
+    #[inline]
+    fn visit_u16<E>(self, v: u16) -> Result<Self::Value, E> {
+        Ok(Value::Int(v.into()))
+    }
+
+    #[inline]
+    fn visit_u32<E>(self, v: u32) -> Result<Self::Value, E> {
+        Ok(Value::Long(v.into()))
+    }
+
+    #[inline]
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        match i64::try_from(v) {
+            Ok(v) => Ok(Value::Long(v)),
+            Err(_) => Err(E::invalid_value(Unexpected::Unsigned(v), &self)),
+        }
+    }
+
+    #[inline]
+    fn visit_f32<E>(self, v: f32) -> Result<Self::Value, E> {
+        Ok(Value::Float(v))
+    }
+
+    #[inline]
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(Value::Double(v))
+    }
+
+    #[inline]
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(Value::String(v.to_owned()))
+    }
+
+    #[inline]
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E> {
+        Ok(Value::String(v))
+    }
+
+    #[inline]
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E> {
+        #[allow(clippy::cast_possible_wrap)]
+        let array = v.iter().map(|&byte| Byte::Integer(byte as i8)).collect();
+        Ok(Value::ByteArray(array))
+    }
+
+    #[inline]
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Deserialize::deserialize(deserializer)
+    }
+
+    #[inline]
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Deserialize::deserialize(deserializer)
+    }
+
+    #[inline]
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        ListVisitor.visit_seq(seq).map(Value::List)
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        match map.next_key_seed(KeyClassifier)? {
+            Some(KeyClass::ByteArray) => Ok(Value::ByteArray(map.next_value::<ByteArray>()?)),
+            Some(KeyClass::IntArray) => Ok(Value::IntArray(map.next_value::<IntArray>()?)),
+            Some(KeyClass::LongArray) => Ok(Value::LongArray(map.next_value::<LongArray>()?)),
+            Some(KeyClass::Key(first)) => {
+                let mut compound = Compound::new();
+                compound.insert(first, map.next_value()?);
+
+                while let Some((key, value)) = map.next_entry()? {
+                    compound.insert(key, value);
+                }
+
+                Ok(Value::Compound(compound))
+            }
+            None => Ok(Value::Compound(Compound::new())),
+        }
+    }
+}
+
+/// Tells apart the private array tokens from ordinary compound keys.
+struct KeyClassifier;
+
+enum KeyClass<K = String> {
+    ByteArray,
+    IntArray,
+    LongArray,
+    Key(K),
+}
+
+impl<'de> DeserializeSeed<'de> for KeyClassifier {
+    type Value = KeyClass;
+
+    #[inline]
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl Visitor<'_> for KeyClassifier {
+    type Value = KeyClass;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a string key")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+        match v {
+            BYTE_ARRAY_TOKEN => Ok(KeyClass::ByteArray),
+            INT_ARRAY_TOKEN => Ok(KeyClass::IntArray),
+            LONG_ARRAY_TOKEN => Ok(KeyClass::LongArray),
+            _ => Ok(KeyClass::Key(v.to_owned())),
+        }
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E> {
+        match v.as_str() {
+            BYTE_ARRAY_TOKEN => Ok(KeyClass::ByteArray),
+            INT_ARRAY_TOKEN => Ok(KeyClass::IntArray),
+            LONG_ARRAY_TOKEN => Ok(KeyClass::LongArray),
+            _ => Ok(KeyClass::Key(v)),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// `ValueRef`
+////////////////////////////////////////////////////////////////////////////////
+
+impl<'de: 'a, 'a> Deserialize<'de> for ValueRef<'a> {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(ValueRefVisitor {
+            lifetime: PhantomData,
+        })
+    }
+}
+
+struct ValueRefVisitor<'a> {
+    lifetime: PhantomData<&'a ()>,
+}
+
+impl<'de: 'a, 'a> Visitor<'de> for ValueRefVisitor<'a> {
+    type Value = ValueRef<'a>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any valid NBT value")
+    }
+
+    #[inline]
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(ValueRef::Byte(Byte::Boolean(v)))
+    }
+
+    #[inline]
+    fn visit_i8<E>(self, v: i8) -> Result<Self::Value, E> {
+        Ok(ValueRef::Byte(Byte::Integer(v)))
+    }
+
+    #[inline]
+    fn visit_i16<E>(self, v: i16) -> Result<Self::Value, E> {
+        Ok(ValueRef::Short(v))
+    }
+
+    #[inline]
+    fn visit_i32<E>(self, v: i32) -> Result<Self::Value, E> {
+        Ok(ValueRef::Int(v))
+    }
+
+    #[inline]
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(ValueRef::Long(v))
+    }
+
+    #[inline]
+    fn visit_u8<E>(self, v: u8) -> Result<Self::Value, E> {
+        Ok(ValueRef::Short(v.into()))
+    }
+
+    #[inline]
+    fn visit_u16<E>(self, v: u16) -> Result<Self::Value, E> {
+        Ok(ValueRef::Int(v.into()))
+    }
+
+    #[inline]
+    fn visit_u32<E>(self, v: u32) -> Result<Self::Value, E> {
+        Ok(ValueRef::Long(v.into()))
+    }
+
+    #[inline]
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        match i64::try_from(v) {
+            Ok(v) => Ok(ValueRef::Long(v)),
+            Err(_) => Err(E::invalid_value(Unexpected::Unsigned(v), &self)),
+        }
+    }
+
+    #[inline]
+    fn visit_f32<E>(self, v: f32) -> Result<Self::Value, E> {
+        Ok(ValueRef::Float(v))
+    }
+
+    #[inline]
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(ValueRef::Double(v))
+    }
+
+    #[inline]
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E> {
+        Ok(ValueRef::String(Cow::Borrowed(v)))
+    }
+
+    #[inline]
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(ValueRef::String(Cow::Owned(v.to_owned())))
+    }
+
+    #[inline]
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E> {
+        Ok(ValueRef::String(Cow::Owned(v)))
+    }
+
+    #[inline]
+    fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+        Ok(ValueRef::ByteArray(Cow::Borrowed(v)))
+    }
+
+    #[inline]
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(ValueRef::ByteArray(Cow::Owned(v.to_vec())))
+    }
+
+    #[inline]
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(ValueRef::ByteArray(Cow::Owned(v)))
+    }
+
+    #[inline]
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Deserialize::deserialize(deserializer)
+    }
+
+    #[inline]
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Deserialize::deserialize(deserializer)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        // The hint may come straight from a length prefix in the input, so
+        // only trust it up to a point.
+        let mut list = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+
+        while let Some(value) = seq.next_element::<ValueRef<'a>>()? {
+            if let Some(first) = list.first().map(ValueRef::ty) {
+                if value.ty() != first {
+                    let message =
+                        format!("list elements must all be {first}, found {}", value.ty());
+                    return Err(de::Error::custom(message));
+                }
+            }
+
+            list.push(value);
+        }
+
+        Ok(ValueRef::List(list))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        match map.next_key_seed(KeyRefClassifier {
+            lifetime: PhantomData,
+        })? {
+            Some(KeyClass::ByteArray) => {
+                let bytes = map.next_value_seed(BytesRef {
+                    lifetime: PhantomData,
+                })?;
+                Ok(ValueRef::ByteArray(bytes))
+            }
+            Some(KeyClass::IntArray) => {
+                Ok(ValueRef::IntArray(map.next_value::<IntArrayRef<'a>>()?))
+            }
+            Some(KeyClass::LongArray) => {
+                Ok(ValueRef::LongArray(map.next_value::<LongArrayRef<'a>>()?))
+            }
+            Some(KeyClass::Key(first)) => {
+                let capacity = map.size_hint().unwrap_or(0).min(4096);
+                let mut compound = CompoundRef::with_capacity(capacity);
+                compound.insert(first, map.next_value()?);
+
+                while let Some(key) = map.next_key_seed(KeyRef {
+                    lifetime: PhantomData,
+                })? {
+                    compound.insert(key, map.next_value()?);
+                }
+
+                Ok(ValueRef::Compound(compound))
+            }
+            None => Ok(ValueRef::Compound(CompoundRef::new())),
+        }
+    }
+}
+
+/// Tells apart the private array tokens from ordinary compound keys, which
+/// are borrowed if possible.
+struct KeyRefClassifier<'a> {
+    lifetime: PhantomData<&'a ()>,
+}
+
+impl<'de: 'a, 'a> DeserializeSeed<'de> for KeyRefClassifier<'a> {
+    type Value = KeyClass<Cow<'a, str>>;
+
+    #[inline]
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let key = KeyRef {
+            lifetime: PhantomData,
+        }
+        .deserialize(deserializer)?;
+
+        Ok(match &*key {
+            BYTE_ARRAY_TOKEN => KeyClass::ByteArray,
+            INT_ARRAY_TOKEN => KeyClass::IntArray,
+            LONG_ARRAY_TOKEN => KeyClass::LongArray,
+            _ => KeyClass::Key(key),
+        })
+    }
+}
+
+/// Deserializes a compound key, borrowing it if possible.
+struct KeyRef<'a> {
+    lifetime: PhantomData<&'a ()>,
+}
+
+impl<'de: 'a, 'a> DeserializeSeed<'de> for KeyRef<'a> {
+    type Value = Cow<'a, str>;
+
+    #[inline]
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'de: 'a, 'a> Visitor<'de> for KeyRef<'a> {
+    type Value = Cow<'a, str>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a string key")
+    }
+
+    #[inline]
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E> {
+        Ok(Cow::Borrowed(v))
+    }
+
+    #[inline]
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(Cow::Owned(v.to_owned()))
+    }
+
+    #[inline]
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E> {
+        Ok(Cow::Owned(v))
+    }
+}
+
+/// Deserializes the payload of a `ByteArray`, borrowing it if possible.
+struct BytesRef<'a> {
+    lifetime: PhantomData<&'a ()>,
+}
+
+impl<'de: 'a, 'a> DeserializeSeed<'de> for BytesRef<'a> {
+    type Value = Cow<'a, [u8]>;
+
+    #[inline]
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_bytes(self)
+    }
+}
+
+impl<'de: 'a, 'a> Visitor<'de> for BytesRef<'a> {
+    type Value = Cow<'a, [u8]>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a byte array")
+    }
+
+    #[inline]
+    fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+        Ok(Cow::Borrowed(v))
+    }
+
+    #[inline]
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(Cow::Owned(v.to_vec()))
+    }
+
+    #[inline]
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(Cow::Owned(v))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+
+        while let Some(byte) = seq.next_element::<Byte>()? {
+            #[allow(clippy::cast_sign_loss)]
+            bytes.push(byte.to_i8() as u8);
+        }
+
+        Ok(Cow::Owned(bytes))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// `Byte`
+////////////////////////////////////////////////////////////////////////////////
+
+impl<'de> Deserialize<'de> for Byte {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(ByteVisitor)
+    }
+}
+
+struct ByteVisitor;
+
+impl Visitor<'_> for ByteVisitor {
+    type Value = Byte;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a boolean or an 8-bit integer")
+    }
+
+    #[inline]
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(Byte::Boolean(v))
+    }
+
+    #[inline]
+    fn visit_i8<E>(self, v: i8) -> Result<Self::Value, E> {
+        Ok(Byte::Integer(v))
+    }
+
+    #[inline]
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        match i8::try_from(v) {
+            Ok(v) => Ok(Byte::Integer(v)),
+            Err(_) => Err(E::invalid_value(Unexpected::Signed(v), &self)),
+        }
+    }
+
+    #[inline]
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        match i8::try_from(v) {
+            Ok(v) => Ok(Byte::Integer(v)),
+            Err(_) => Err(E::invalid_value(Unexpected::Unsigned(v), &self)),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// `List`
+////////////////////////////////////////////////////////////////////////////////
+
+impl<'de> Deserialize<'de> for List {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(ListVisitor)
+    }
+}
+
+struct ListVisitor;
+
+impl<'de> Visitor<'de> for ListVisitor {
+    type Value = List;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a list of values sharing the same type")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        // The hint may come straight from a length prefix in the input, so
+        // only trust it up to a point and grow fallibly past that.
+        let mut reserve = seq.size_hint().unwrap_or(0).min(4096);
+        let mut list = List::Empty;
+
+        while let Some(value) = seq.next_element::<Value>()? {
+            // An empty list has no element type yet and so nothing to
+            // reserve; the first push allocates it.
+            if !list.is_empty() && list.len() == list.capacity() {
+                let additional = reserve.max(1);
+                reserve = 0;
+
+                if list.try_reserve(additional).is_err() {
+                    let message = format!(
+                        "failed to allocate a list of {} elements",
+                        list.len() + additional
+                    );
+                    return Err(de::Error::custom(message));
+                }
+            }
+
+            if let Err(value) = list.push_checked(value) {
+                let expected = list.ty().expect("non-empty list has a type");
+                let message = format!("list elements must all be {expected}, found {}", value.ty());
+                return Err(de::Error::custom(message));
+            }
+        }
+
+        Ok(list)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// `Map`
+////////////////////////////////////////////////////////////////////////////////
+
+impl<'de, K, V, S> Deserialize<'de> for Map<K, V, S>
+where
+    K: Deserialize<'de> + Eq + Hash,
+    V: Deserialize<'de>,
+    S: BuildHasher + Default,
+{
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(MapVisitor {
+            marker: PhantomData,
+        })
+    }
+}
+
+struct MapVisitor<K, V, S> {
+    marker: PhantomData<Map<K, V, S>>,
+}
+
+impl<'de, K, V, S> Visitor<'de> for MapVisitor<K, V, S>
+where
+    K: Deserialize<'de> + Eq + Hash,
+    V: Deserialize<'de>,
+    S: BuildHasher + Default,
+{
+    type Value = Map<K, V, S>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a compound")
+    }
+
+    fn visit_map<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        // Never trust a size hint more than a little; it may come straight
+        // from untrusted input.
+        let capacity = access.size_hint().unwrap_or(0).min(4096);
+        let mut map = Map::with_capacity_and_hasher(capacity, S::default());
+
+        while let Some((key, value)) = access.next_entry()? {
+            map.insert(key, value);
+        }
+
+        Ok(map)
+    }
+}
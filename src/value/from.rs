@@ -1,6 +1,12 @@
-use super::{Byte, ByteArray, Compound, IntArray, List, LongArray, Value};
+use super::{
+    Byte, ByteArray, Compound, IntArray, IntArrayRef, List, LongArray, LongArrayRef, Value,
+};
 
-use alloc::{borrow::Cow, boxed::Box, string::String};
+use alloc::{
+    borrow::{Cow, ToOwned},
+    boxed::Box,
+    string::{String, ToString},
+};
 
 ////////////////////////////////////////////////////////////////////////////////
 // Simple Conversions
@@ -181,6 +187,20 @@ impl From<&[i64]> for Value {
     }
 }
 
+impl From<IntArrayRef<'_>> for Value {
+    #[inline]
+    fn from(value: IntArrayRef<'_>) -> Self {
+        Value::IntArray(value.into_vec())
+    }
+}
+
+impl From<LongArrayRef<'_>> for Value {
+    #[inline]
+    fn from(value: LongArrayRef<'_>) -> Self {
+        Value::LongArray(value.into_vec())
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // Compound Conversions
 ////////////////////////////////////////////////////////////////////////////////
@@ -3,7 +3,9 @@
 use super::{Byte, ByteArray, Compound, IntArray, LongArray, Type, Value};
 
 use alloc::{
+    boxed::Box,
     collections::TryReserveError,
+    string::String,
     vec::{self, Vec},
 };
 use core::{fmt, iter::FusedIterator, mem};
@@ -1151,18 +1153,18 @@ impl From<Value> for List {
     #[inline]
     fn from(value: Value) -> Self {
         match value {
-            Value::Byte(value) => List::Byte(vec![value]),
-            Value::Short(value) => List::Short(vec![value]),
-            Value::Int(value) => List::Int(vec![value]),
-            Value::Long(value) => List::Long(vec![value]),
-            Value::Float(value) => List::Float(vec![value]),
-            Value::Double(value) => List::Double(vec![value]),
-            Value::ByteArray(value) => List::ByteArray(vec![value]),
-            Value::String(value) => List::String(vec![value]),
-            Value::List(value) => List::List(vec![value]),
-            Value::Compound(value) => List::Compound(vec![value]),
-            Value::IntArray(value) => List::IntArray(vec![value]),
-            Value::LongArray(value) => List::LongArray(vec![value]),
+            Value::Byte(value) => List::Byte(alloc::vec![value]),
+            Value::Short(value) => List::Short(alloc::vec![value]),
+            Value::Int(value) => List::Int(alloc::vec![value]),
+            Value::Long(value) => List::Long(alloc::vec![value]),
+            Value::Float(value) => List::Float(alloc::vec![value]),
+            Value::Double(value) => List::Double(alloc::vec![value]),
+            Value::ByteArray(value) => List::ByteArray(alloc::vec![value]),
+            Value::String(value) => List::String(alloc::vec![value]),
+            Value::List(value) => List::List(alloc::vec![value]),
+            Value::Compound(value) => List::Compound(alloc::vec![value]),
+            Value::IntArray(value) => List::IntArray(alloc::vec![value]),
+            Value::LongArray(value) => List::LongArray(alloc::vec![value]),
         }
     }
 }
@@ -4,13 +4,12 @@
 
 use core::{
     borrow::Borrow,
-    cmp::Ordering,
     fmt,
     hash::{BuildHasher, Hash},
-    iter::{DoubleEndedIterator, FusedIterator},
-    marker::PhantomData,
-    ops::RangeBounds,
+    iter::FusedIterator,
 };
+#[cfg(feature = "preserve_order")]
+use core::{cmp::Ordering, marker::PhantomData, ops::RangeBounds};
 
 use ahash::RandomState;
 #[cfg(not(feature = "preserve_order"))]
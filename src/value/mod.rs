@@ -1,13 +1,23 @@
+pub mod array;
+mod borrowed;
 mod byte;
 mod de;
 mod from;
 pub mod list;
 pub mod map;
-
-pub use self::byte::Byte;
+mod named;
+mod ser;
+
+pub use self::{
+    array::{IntArrayRef, LongArrayRef},
+    borrowed::{CompoundRef, ValueRef},
+    byte::Byte,
+    named::{Named, NamedValue},
+};
 use self::{list::List, map::Map};
 
-use alloc::vec::Vec;
+use alloc::{string::String, vec::Vec};
+use core::fmt;
 
 /// TODO
 #[derive(Debug, Clone, PartialEq)]
@@ -420,7 +430,13 @@ impl Value {
     }
 }
 
-/// TODO
+/// The entries of a `Compound` tag, keyed by name.
+///
+/// Without the `std` feature, the hash seeds of every new `Compound` are fixed
+/// rather than random, since many `no_std` targets have no source of
+/// randomness. Maps holding names from untrusted input are then open to
+/// collision attacks, unless they are created by [`Map::with_hasher`] with a
+/// [`RandomState`](ahash::RandomState) seeded by the caller.
 pub type Compound = Map<String, Value>;
 
 /// TODO
@@ -461,3 +477,72 @@ pub enum Type {
     /// TODO
     LongArray,
 }
+
+impl Type {
+    /// Returns the ID that tags this type in the binary formats.
+    #[must_use]
+    #[inline]
+    pub const fn id(self) -> u8 {
+        match self {
+            Type::Byte => 1,
+            Type::Short => 2,
+            Type::Int => 3,
+            Type::Long => 4,
+            Type::Float => 5,
+            Type::Double => 6,
+            Type::ByteArray => 7,
+            Type::String => 8,
+            Type::List => 9,
+            Type::Compound => 10,
+            Type::IntArray => 11,
+            Type::LongArray => 12,
+        }
+    }
+
+    /// Returns the name of this type as it appears in error messages.
+    pub(crate) const fn as_str(self) -> &'static str {
+        match self {
+            Type::Byte => "Byte",
+            Type::Short => "Short",
+            Type::Int => "Int",
+            Type::Long => "Long",
+            Type::Float => "Float",
+            Type::Double => "Double",
+            Type::ByteArray => "ByteArray",
+            Type::String => "String",
+            Type::List => "List",
+            Type::Compound => "Compound",
+            Type::IntArray => "IntArray",
+            Type::LongArray => "LongArray",
+        }
+    }
+
+    /// Returns the type tagged by `id` in the binary formats.
+    ///
+    /// Returns `None` for `TAG_End` and any unknown ID.
+    #[must_use]
+    #[inline]
+    pub const fn from_id(id: u8) -> Option<Type> {
+        match id {
+            1 => Some(Type::Byte),
+            2 => Some(Type::Short),
+            3 => Some(Type::Int),
+            4 => Some(Type::Long),
+            5 => Some(Type::Float),
+            6 => Some(Type::Double),
+            7 => Some(Type::ByteArray),
+            8 => Some(Type::String),
+            9 => Some(Type::List),
+            10 => Some(Type::Compound),
+            11 => Some(Type::IntArray),
+            12 => Some(Type::LongArray),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
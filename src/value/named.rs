@@ -0,0 +1,221 @@
+//! TODO
+
+use alloc::string::String;
+use core::{fmt, marker::PhantomData};
+
+use serde::{
+    de::{self, Deserialize, Deserializer, SeqAccess, Visitor},
+    ser::{Serialize, Serializer},
+};
+
+use super::Value;
+use crate::util::NAMED_TOKEN;
+
+/// A root tag together with its name.
+///
+/// Binary NBT gives the root tag a name like any other tag. It is almost
+/// always empty, but `level.dat` and some schematic formats use a real one.
+/// Wrapping a type in `Named` keeps that name around: the binary
+/// deserializers fill it in from the input and the binary serializers write
+/// it back out in place of the serializer's own root name.
+///
+/// Only the root tag has a name of its own. Below it, the binary serializers
+/// leave the name out and the binary deserializers fill in an empty one, so
+/// a nested `Named` round-trips its value but not its name. Formats without
+/// root names, such as SNBT and [`Value`], also deserialize `Named` with an
+/// empty name, while other serde formats see a `(name, value)` pair.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Named<T> {
+    /// The name of the root tag.
+    pub name: String,
+    /// The root tag itself.
+    pub value: T,
+}
+
+/// A [`Value`] together with the name of its root tag.
+pub type NamedValue = Named<Value>;
+
+impl<T> Named<T> {
+    /// Pairs `value` with the root tag name `name`.
+    #[must_use]
+    #[inline]
+    pub fn new<S>(name: S, value: T) -> Self
+    where
+        S: Into<String>,
+    {
+        Named {
+            name: name.into(),
+            value,
+        }
+    }
+
+    /// Discards the name, returning the root tag.
+    #[must_use]
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Serialize for Named<T>
+where
+    T: Serialize,
+{
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_newtype_struct(NAMED_TOKEN, &(&self.name, &self.value))
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Named<T>
+where
+    T: Deserialize<'de>,
+{
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct(
+            NAMED_TOKEN,
+            NamedVisitor {
+                marker: PhantomData,
+            },
+        )
+    }
+}
+
+struct NamedVisitor<T> {
+    marker: PhantomData<T>,
+}
+
+impl<'de, T> Visitor<'de> for NamedVisitor<T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = Named<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a named root tag")
+    }
+
+    /// Called by deserializers without root names, which hand over the
+    /// root tag directly.
+    #[inline]
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(|value| Named::new(String::new(), value))
+    }
+
+    /// Called by the binary deserializers with the name and then the root
+    /// tag.
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let Some(name) = seq.next_element()? else {
+            return Err(de::Error::invalid_length(0, &self));
+        };
+
+        let Some(value) = seq.next_element()? else {
+            return Err(de::Error::invalid_length(1, &self));
+        };
+
+        Ok(Named { name, value })
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(all(test, feature = "be"))]
+mod tests {
+    use alloc::{string::String, vec, vec::Vec};
+
+    use serde::{Deserialize, Serialize};
+
+    use super::{Named, NamedValue};
+    use crate::{
+        de::{self, from_be_slice},
+        ser::to_be_vec,
+        Value,
+    };
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Root {
+        x: i32,
+    }
+
+    #[test]
+    fn round_trips_the_root_name() {
+        for name in ["level", ""] {
+            let named = Named::new(name, Root { x: 1 });
+            let bytes = to_be_vec(&named).unwrap();
+
+            assert_eq!(
+                bytes[1..3],
+                u16::try_from(name.len()).unwrap().to_be_bytes()
+            );
+            assert_eq!(&bytes[3..3 + name.len()], name.as_bytes());
+            assert_eq!(from_be_slice::<Named<Root>>(&bytes).unwrap(), named);
+        }
+    }
+
+    #[test]
+    fn named_value_keeps_the_root_name() {
+        let bytes = to_be_vec(&Named::new("level", Root { x: 1 })).unwrap();
+        let named = from_be_slice::<NamedValue>(&bytes).unwrap();
+
+        assert_eq!(named.name, "level");
+        let compound = named.value.as_compound().unwrap();
+        assert_eq!(compound.get("x"), Some(&Value::Int(1)));
+        assert_eq!(to_be_vec(&named).unwrap(), bytes);
+    }
+
+    #[test]
+    fn formats_without_root_names_give_an_empty_name() {
+        let value = from_be_slice::<Value>(&to_be_vec(&Root { x: 1 }).unwrap()).unwrap();
+        let named = Named::<Root>::deserialize(de::value::Deserializer::new(value)).unwrap();
+        assert_eq!(named, Named::new("", Root { x: 1 }));
+
+        #[cfg(feature = "snbt")]
+        {
+            let named = de::from_snbt_str::<Named<Root>>("{x: 1}").unwrap();
+            assert_eq!(named, Named::new("", Root { x: 1 }));
+        }
+    }
+
+    #[test]
+    fn nested_named_is_serialized_as_its_value() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Outer {
+            inner: Named<Root>,
+            list: Vec<Named<String>>,
+        }
+
+        #[derive(Serialize)]
+        struct Plain {
+            inner: Root,
+            list: Vec<String>,
+        }
+
+        let outer = Outer {
+            inner: Named::new("dropped", Root { x: 1 }),
+            list: vec![Named::new("dropped", "a".into())],
+        };
+        let bytes = to_be_vec(&outer).unwrap();
+        let plain = Plain {
+            inner: Root { x: 1 },
+            list: vec!["a".into()],
+        };
+        assert_eq!(bytes, to_be_vec(&plain).unwrap());
+
+        let outer = from_be_slice::<Outer>(&bytes).unwrap();
+        assert_eq!(outer.inner, Named::new("", Root { x: 1 }));
+        assert_eq!(outer.list, [Named::new("", String::from("a"))]);
+    }
+}
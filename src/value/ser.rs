@@ -0,0 +1,195 @@
+//! TODO
+
+use super::{Byte, List, Value, ValueRef};
+use crate::{
+    map::Map,
+    util::{BYTE_ARRAY_TOKEN, INT_ARRAY_TOKEN, LONG_ARRAY_TOKEN},
+};
+
+use alloc::vec::Vec;
+
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
+
+////////////////////////////////////////////////////////////////////////////////
+// `Value`
+////////////////////////////////////////////////////////////////////////////////
+
+impl Serialize for Value {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Value::Byte(v) => v.serialize(serializer),
+            Value::Short(v) => serializer.serialize_i16(*v),
+            Value::Int(v) => serializer.serialize_i32(*v),
+            Value::Long(v) => serializer.serialize_i64(*v),
+            Value::Float(v) => serializer.serialize_f32(*v),
+            Value::Double(v) => serializer.serialize_f64(*v),
+            Value::ByteArray(v) => Array::new(BYTE_ARRAY_TOKEN, v).serialize(serializer),
+            Value::String(v) => serializer.serialize_str(v),
+            Value::List(v) => v.serialize(serializer),
+            Value::Compound(v) => v.serialize(serializer),
+            Value::IntArray(v) => Array::new(INT_ARRAY_TOKEN, v).serialize(serializer),
+            Value::LongArray(v) => Array::new(LONG_ARRAY_TOKEN, v).serialize(serializer),
+        }
+    }
+}
+
+/// Serializes a slice as one of the array types.
+///
+/// Serializers built for NBT recognize the newtype struct named after the
+/// private token and write an array instead of a `List`; any other serializer
+/// just sees the sequence.
+struct Array<'a, T> {
+    token: &'static str,
+    elements: &'a [T],
+}
+
+impl<'a, T> Array<'a, T> {
+    #[inline]
+    fn new(token: &'static str, elements: &'a [T]) -> Self {
+        Array { token, elements }
+    }
+}
+
+impl<T> Serialize for Array<'_, T>
+where
+    T: Serialize,
+{
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_newtype_struct(self.token, self.elements)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// `ValueRef`
+////////////////////////////////////////////////////////////////////////////////
+
+impl Serialize for ValueRef<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            ValueRef::Byte(v) => v.serialize(serializer),
+            ValueRef::Short(v) => serializer.serialize_i16(*v),
+            ValueRef::Int(v) => serializer.serialize_i32(*v),
+            ValueRef::Long(v) => serializer.serialize_i64(*v),
+            ValueRef::Float(v) => serializer.serialize_f32(*v),
+            ValueRef::Double(v) => serializer.serialize_f64(*v),
+            ValueRef::ByteArray(v) => {
+                serializer.serialize_newtype_struct(BYTE_ARRAY_TOKEN, &Bytes(v))
+            }
+            ValueRef::String(v) => serializer.serialize_str(v),
+            ValueRef::List(v) => v.serialize(serializer),
+            ValueRef::Compound(v) => v.serialize(serializer),
+            ValueRef::IntArray(v) => v.serialize(serializer),
+            ValueRef::LongArray(v) => v.serialize(serializer),
+        }
+    }
+}
+
+/// Serializes raw bytes as bytes rather than as a sequence.
+struct Bytes<'a>(&'a [u8]);
+
+impl Serialize for Bytes<'_> {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// `Byte`
+////////////////////////////////////////////////////////////////////////////////
+
+impl Serialize for Byte {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match *self {
+            Byte::Boolean(v) => serializer.serialize_bool(v),
+            Byte::Integer(v) => serializer.serialize_i8(v),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// `List`
+////////////////////////////////////////////////////////////////////////////////
+
+impl Serialize for List {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            List::Empty => serializer.serialize_seq(Some(0))?.end(),
+            List::Byte(v) => v.serialize(serializer),
+            List::Short(v) => v.serialize(serializer),
+            List::Int(v) => v.serialize(serializer),
+            List::Long(v) => v.serialize(serializer),
+            List::Float(v) => v.serialize(serializer),
+            List::Double(v) => v.serialize(serializer),
+            List::ByteArray(v) => serialize_arrays(serializer, BYTE_ARRAY_TOKEN, v),
+            List::String(v) => v.serialize(serializer),
+            List::List(v) => v.serialize(serializer),
+            List::Compound(v) => v.serialize(serializer),
+            List::IntArray(v) => serialize_arrays(serializer, INT_ARRAY_TOKEN, v),
+            List::LongArray(v) => serialize_arrays(serializer, LONG_ARRAY_TOKEN, v),
+        }
+    }
+}
+
+/// Serializes a `List` of arrays, which would otherwise come out as a `List`
+/// of `List`s.
+fn serialize_arrays<S, T>(
+    serializer: S,
+    token: &'static str,
+    arrays: &[Vec<T>],
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Serialize,
+{
+    let mut seq = serializer.serialize_seq(Some(arrays.len()))?;
+
+    for array in arrays {
+        seq.serialize_element(&Array::new(token, array))?;
+    }
+
+    seq.end()
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// `Map`
+////////////////////////////////////////////////////////////////////////////////
+
+impl<K, V, S> Serialize for Map<K, V, S>
+where
+    K: Serialize,
+    V: Serialize,
+{
+    fn serialize<T>(&self, serializer: T) -> Result<T::Ok, T::Error>
+    where
+        T: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+
+        for (key, value) in self {
+            map.serialize_entry(key, value)?;
+        }
+
+        map.end()
+    }
+}